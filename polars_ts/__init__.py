@@ -6,18 +6,42 @@ import polars_ts_rs as _rs_mod
 from polars._typing import IntoExpr
 from polars.plugins import register_plugin_function
 from polars_ts_rs.polars_ts_rs import (
+    compute_discords,
+    compute_dtw_alignment,
+    compute_knn_dtw,
+    compute_matrix_profile,
+    compute_motifs,
+    compute_pairwise_adtw,
+    compute_pairwise_chebyshev,
+    compute_pairwise_cid,
+    compute_pairwise_correlation,
+    compute_pairwise_cosine,
     compute_pairwise_ddtw,
+    compute_pairwise_ddtw_multi,
     compute_pairwise_dtw,
     compute_pairwise_dtw_multi,
     compute_pairwise_edr,
+    compute_pairwise_edr_multi,
     compute_pairwise_erp,
+    compute_pairwise_erp_multi,
+    compute_pairwise_euclidean,
     compute_pairwise_frechet,
+    compute_pairwise_frechet_multi,
+    compute_pairwise_gak,
     compute_pairwise_lcss,
+    compute_pairwise_lcss_multi,
+    compute_pairwise_minkowski,
     compute_pairwise_msm,
     compute_pairwise_msm_multi,
     compute_pairwise_sbd,
+    compute_pairwise_shapedtw,
+    compute_pairwise_softdtw,
     compute_pairwise_twe,
+    compute_pairwise_twe_multi,
+    compute_pairwise_wddtw,
     compute_pairwise_wdtw,
+    compute_pairwise_wdtw_multi,
+    compute_subsequence_dtw,
 )
 
 from polars_ts.distance import compute_pairwise_distance
@@ -224,15 +248,39 @@ __all__ = [
     "compute_pairwise_dtw",
     "compute_pairwise_ddtw",
     "compute_pairwise_wdtw",
+    "compute_pairwise_wddtw",
+    "compute_pairwise_adtw",
+    "compute_pairwise_shapedtw",
     "compute_pairwise_msm",
     "compute_pairwise_dtw_multi",
     "compute_pairwise_msm_multi",
+    "compute_pairwise_erp_multi",
+    "compute_pairwise_lcss_multi",
+    "compute_pairwise_edr_multi",
+    "compute_pairwise_twe_multi",
+    "compute_pairwise_wdtw_multi",
+    "compute_pairwise_ddtw_multi",
+    "compute_pairwise_frechet_multi",
     "compute_pairwise_erp",
     "compute_pairwise_lcss",
     "compute_pairwise_twe",
     "compute_pairwise_sbd",
     "compute_pairwise_frechet",
     "compute_pairwise_edr",
+    "compute_pairwise_softdtw",
+    "compute_pairwise_euclidean",
+    "compute_pairwise_minkowski",
+    "compute_pairwise_chebyshev",
+    "compute_pairwise_correlation",
+    "compute_pairwise_cid",
+    "compute_pairwise_cosine",
+    "compute_pairwise_gak",
+    "compute_dtw_alignment",
+    "compute_knn_dtw",
+    "compute_subsequence_dtw",
+    "compute_matrix_profile",
+    "compute_motifs",
+    "compute_discords",
     "mann_kendall",
     "sens_slope",
     *_LAZY_IMPORTS.keys(),
@@ -6,24 +6,44 @@ from typing import Any
 
 import polars as pl
 from polars_ts_rs.polars_ts_rs import (
+    compute_pairwise_adtw,
+    compute_pairwise_chebyshev,
+    compute_pairwise_cid,
+    compute_pairwise_correlation,
+    compute_pairwise_cosine,
     compute_pairwise_ddtw,
+    compute_pairwise_ddtw_multi,
     compute_pairwise_dtw,
     compute_pairwise_dtw_multi,
     compute_pairwise_edr,
+    compute_pairwise_edr_multi,
     compute_pairwise_erp,
+    compute_pairwise_erp_multi,
+    compute_pairwise_euclidean,
     compute_pairwise_frechet,
+    compute_pairwise_frechet_multi,
     compute_pairwise_lcss,
+    compute_pairwise_lcss_multi,
+    compute_pairwise_minkowski,
     compute_pairwise_msm,
     compute_pairwise_msm_multi,
     compute_pairwise_sbd,
+    compute_pairwise_shapedtw,
+    compute_pairwise_softdtw,
     compute_pairwise_twe,
+    compute_pairwise_twe_multi,
+    compute_pairwise_wddtw,
     compute_pairwise_wdtw,
+    compute_pairwise_wdtw_multi,
 )
 
 _DISTANCE_FUNCS = {
     "dtw": compute_pairwise_dtw,
     "ddtw": compute_pairwise_ddtw,
     "wdtw": compute_pairwise_wdtw,
+    "wddtw": compute_pairwise_wddtw,
+    "adtw": compute_pairwise_adtw,
+    "shapedtw": compute_pairwise_shapedtw,
     "msm": compute_pairwise_msm,
     "erp": compute_pairwise_erp,
     "lcss": compute_pairwise_lcss,
@@ -31,23 +51,69 @@ _DISTANCE_FUNCS = {
     "sbd": compute_pairwise_sbd,
     "frechet": compute_pairwise_frechet,
     "edr": compute_pairwise_edr,
+    "softdtw": compute_pairwise_softdtw,
+    "euclidean": compute_pairwise_euclidean,
+    "minkowski": compute_pairwise_minkowski,
+    "chebyshev": compute_pairwise_chebyshev,
+    "correlation": compute_pairwise_correlation,
+    "cid": compute_pairwise_cid,
+    "cosine": compute_pairwise_cosine,
     "dtw_multi": compute_pairwise_dtw_multi,
     "msm_multi": compute_pairwise_msm_multi,
+    "erp_multi": compute_pairwise_erp_multi,
+    "lcss_multi": compute_pairwise_lcss_multi,
+    "edr_multi": compute_pairwise_edr_multi,
+    "twe_multi": compute_pairwise_twe_multi,
+    "wdtw_multi": compute_pairwise_wdtw_multi,
+    "ddtw_multi": compute_pairwise_ddtw_multi,
+    "frechet_multi": compute_pairwise_frechet_multi,
+}
+
+# Options accepted by every Rust pairwise entry point (see ``PairwiseOptions``).
+_SHARED_KWARGS = {
+    "k",
+    "output",
+    "id_col",
+    "target_col",
+    "time_col",
+    "normalize",
+    "mode",
+    "missing",
+    "n_jobs",
+    "sink_parquet",
+    "memory_budget",
 }
 
 _VALID_KWARGS = {
-    "dtw": {"method", "param"},
-    "ddtw": set(),
-    "wdtw": {"g"},
-    "msm": {"c"},
-    "erp": {"g"},
-    "lcss": {"epsilon"},
-    "twe": {"nu", "lambda_"},
-    "sbd": set(),
+    "dtw": {"method", "param", "cost", "normalize_path"},
+    "ddtw": {"cost", "normalize_path"},
+    "wdtw": {"g", "cost", "normalize_path"},
+    "wddtw": {"g", "cost", "normalize_path"},
+    "adtw": {"penalty", "cost"},
+    "shapedtw": {"reach", "descriptor", "segments", "cost"},
+    "msm": {"c", "window"},
+    "erp": {"g", "window"},
+    "lcss": {"epsilon", "window"},
+    "twe": {"nu", "lambda_", "window"},
+    "sbd": {"return_shift"},
     "frechet": set(),
-    "edr": {"epsilon"},
-    "dtw_multi": {"metric"},
+    "edr": {"epsilon", "window"},
+    "softdtw": {"gamma"},
+    "euclidean": {"unequal_length"},
+    "minkowski": {"p", "unequal_length"},
+    "chebyshev": {"unequal_length"},
+    "correlation": {"unequal_length"},
+    "cid": {"unequal_length"},
+    "cosine": {"unequal_length"},
+    "dtw_multi": {"metric", "strategy", "weights", "window", "adaptive_threshold"},
     "msm_multi": {"c"},
+    "erp_multi": {"g", "window", "metric"},
+    "lcss_multi": {"epsilon", "window", "metric"},
+    "edr_multi": {"epsilon", "window", "metric"},
+    "twe_multi": {"nu", "lambda_", "window", "metric"},
+    "wdtw_multi": {"g", "metric"},
+    "ddtw_multi": {"metric"},
+    "frechet_multi": {"metric"},
 }
 
 
@@ -61,7 +127,7 @@ def compute_distances(
     if method not in _DISTANCE_FUNCS:
         raise ValueError(f"Unknown distance method {method!r}. Valid: {sorted(_DISTANCE_FUNCS)}")
     valid = _VALID_KWARGS[method]
-    unexpected = set(kwargs) - valid
+    unexpected = set(kwargs) - valid - _SHARED_KWARGS
     if unexpected:
         raise ValueError(f"Unexpected kwargs {sorted(unexpected)} for method {method!r}")
     return _DISTANCE_FUNCS[method](df1, df2, **kwargs)
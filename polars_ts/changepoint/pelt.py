@@ -39,6 +39,35 @@ def _cost_meanvar(data: np.ndarray, start: int, end: int) -> float:
 
 _COST_FNS = {"mean": _cost_mean, "var": _cost_var, "meanvar": _cost_meanvar}
 
+_MISSING_POLICIES = ("error", "drop", "linear_interpolate", "ffill")
+
+
+def _resolve_missing(data: np.ndarray, missing: str, gid: Any) -> tuple[np.ndarray, np.ndarray]:
+    """Apply the missing-value policy to one series.
+
+    Returns the cleaned values and the original row position of each of them.
+    """
+    positions = np.arange(len(data))
+    mask = np.isnan(data)
+    if not mask.any():
+        return data, positions
+    if missing == "error":
+        first = int(np.flatnonzero(mask)[0])
+        raise ValueError(
+            f"Series {gid} has a missing value (null or NaN) at position {first}. "
+            "Pass missing='drop', 'linear_interpolate' or 'ffill' to handle it."
+        )
+    if mask.all():
+        raise ValueError(f"Series {gid} has no observed values")
+    if missing == "drop":
+        return data[~mask], positions[~mask]
+    if missing == "linear_interpolate":
+        return np.interp(positions, positions[~mask], data[~mask]), positions
+    # ffill: leading gaps take the first observed value
+    last = np.maximum.accumulate(np.where(mask, 0, positions))
+    last[: np.flatnonzero(~mask)[0]] = np.flatnonzero(~mask)[0]
+    return data[last], positions
+
 
 def _pelt_python(
     df: pl.DataFrame,
@@ -48,6 +77,7 @@ def _pelt_python(
     cost: str,
     penalty: float | None,
     min_size: int,
+    missing: str = "error",
 ) -> pl.DataFrame:
     """Pure-Python PELT implementation (fallback)."""
     if cost not in _COST_FNS:
@@ -59,7 +89,8 @@ def _pelt_python(
     rows: list[dict[str, Any]] = []
     for group_id, group_df in sorted_df.group_by(id_col, maintain_order=True):
         gid = group_id[0]
-        data = np.array(group_df[target_col].to_list(), dtype=np.float64)
+        raw = np.array(group_df[target_col].to_list(), dtype=np.float64)
+        data, positions = _resolve_missing(raw, missing, gid)
         times = group_df[time_col].to_list()
         n = len(data)
 
@@ -85,7 +116,7 @@ def _pelt_python(
 
             candidates = [s for s in candidates if f[s] + cost_fn(data, s, t) <= f[t]] + [t]
 
-        changepoints = [c for c in cp[n] if c > 0]
+        changepoints = [int(positions[c]) for c in cp[n] if c > 0]
 
         for idx in changepoints:
             rows.append({id_col: gid, "changepoint_idx": idx, time_col: times[idx]})
@@ -105,6 +136,8 @@ def _pelt_rust(
     cost: str,
     penalty: float | None,
     min_size: int,
+    missing: str = "error",
+    n_jobs: int | None = None,
 ) -> pl.DataFrame:
     """Rust-accelerated PELT implementation."""
     from polars_ts_rs import pelt as _pelt_rs
@@ -119,6 +152,8 @@ def _pelt_rust(
         min_size=min_size,
         id_col=id_col,
         target_col=target_col,
+        missing=missing,
+        n_jobs=n_jobs,
     )
 
     if result.is_empty():
@@ -149,6 +184,8 @@ def pelt(
     cost: str = "mean",
     penalty: float | None = None,
     min_size: int = 2,
+    missing: str = "error",
+    n_jobs: int | None = None,
 ) -> pl.DataFrame:
     """Detect multiple changepoints using the PELT algorithm.
 
@@ -168,6 +205,14 @@ def pelt(
         Penalty per changepoint. Defaults to ``2 * log(n)`` (BIC-like).
     min_size
         Minimum segment length between changepoints.
+    missing
+        How null and NaN values are handled: ``"error"`` (default) raises
+        naming the series, ``"drop"`` removes them, ``"linear_interpolate"``
+        fills gaps linearly and ``"ffill"`` carries the last value forward.
+        ``changepoint_idx`` always refers to the original row positions.
+    n_jobs
+        Number of threads the series are processed on. Defaults to all
+        cores; ``1`` runs sequentially. Ignored by the pure-Python fallback.
 
     Returns
     -------
@@ -178,8 +223,10 @@ def pelt(
     """
     if cost not in _COST_FNS:
         raise ValueError(f"Unknown cost {cost!r}. Choose from {sorted(_COST_FNS)}")
+    if missing not in _MISSING_POLICIES:
+        raise ValueError(f"Unknown missing policy {missing!r}. Choose from {list(_MISSING_POLICIES)}")
 
     try:
-        return _pelt_rust(df, target_col, id_col, time_col, cost, penalty, min_size)
+        return _pelt_rust(df, target_col, id_col, time_col, cost, penalty, min_size, missing, n_jobs)
     except ImportError:
-        return _pelt_python(df, target_col, id_col, time_col, cost, penalty, min_size)
+        return _pelt_python(df, target_col, id_col, time_col, cost, penalty, min_size, missing)
@@ -7,7 +7,7 @@ from typing import Any
 
 import polars as pl
 
-from polars_ts._distance_dispatch import compute_distances, pairwise_to_dict
+from polars_ts._distance_dispatch import compute_distances
 
 
 class TimeSeriesKNNClassifier:
@@ -129,30 +129,20 @@ def knn_classify(
     train_dist = train_df.select(pl.col(id_col).alias("unique_id"), pl.col(target_col).alias("y"))
     test_dist = test_df.select(pl.col(id_col).alias("unique_id"), pl.col(target_col).alias("y"))
 
-    # Compute cross-distances
-    pairwise = compute_distances(train_dist, test_dist, method=method, **distance_kwargs)
-    dist = pairwise_to_dict(pairwise)
+    # Only the k nearest training series of each test series are returned. Test and
+    # training ids are independent, so a training series sharing a test id is kept.
+    neighbours = compute_distances(test_dist, train_dist, method=method, k=k, mode="cross_full", **distance_kwargs)
+    neighbours = neighbours.with_columns(pl.col("id_1").cast(pl.String), pl.col("id_2").cast(pl.String))
+    neighbour_map: dict[str, list[str]] = {}
+    for row in neighbours.sort("id_1", "rank").iter_rows(named=True):
+        neighbour_map.setdefault(row["id_1"], []).append(row["id_2"])
 
-    train_ids = set(train_dist["unique_id"].unique().to_list())
     test_ids = test_df[id_col].unique().sort().to_list()
-    test_str_ids = [str(tid) for tid in test_ids]
 
-    # Classify each test series
+    # Classify each test series by majority vote over its neighbours
     predictions = []
-    for test_id, test_str in zip(test_ids, test_str_ids, strict=False):
-        # Get distances to all training series
-        distances = []
-        for train_id in train_ids:
-            d = dist.get((test_str, str(train_id)))
-            if d is not None:
-                distances.append((train_id, d))
-
-        # Sort by distance, take k nearest
-        distances.sort(key=lambda x: x[1])
-        neighbors = distances[:k]
-
-        # Majority vote
-        votes = Counter(label_map[str(n[0])] for n in neighbors)
+    for test_id in test_ids:
+        votes = Counter(label_map[n] for n in neighbour_map.get(str(test_id), []))
         predicted = votes.most_common(1)[0][0]
         predictions.append((test_id, predicted))
 
@@ -3,6 +3,10 @@
 Implements the iterative averaging algorithm from:
 Petitjean, F. et al. (2011). *A global averaging method for dynamic time
 warping*. Pattern Recognition.
+
+Also provides soft-DTW barycenters from:
+Cuturi, M. & Blondel, M. (2017). *Soft-DTW: a differentiable loss function
+for time-series*. ICML.
 """
 
 from __future__ import annotations
@@ -10,14 +14,23 @@ from __future__ import annotations
 import numpy as np
 
 
-def _dtw_alignment_path(s: np.ndarray, t: np.ndarray) -> list[tuple[int, int]]:
-    """Compute full DTW cost matrix and return the optimal alignment path."""
+def _band_width(n: int, m: int, window: int | None) -> int:
+    """Sakoe-Chiba band half-width, widened to reach the corner as in Rust."""
+    return max(n, m) if window is None else max(window, abs(n - m))
+
+
+def _dtw_alignment_path(s: np.ndarray, t: np.ndarray, window: int | None = None) -> list[tuple[int, int]]:
+    """Compute the DTW cost matrix and return the optimal alignment path.
+
+    ``window`` restricts the alignment to a Sakoe-Chiba band.
+    """
     n, m = len(s), len(t)
+    w = _band_width(n, m, window)
     cost = np.full((n + 1, m + 1), np.inf)
     cost[0, 0] = 0.0
 
     for i in range(1, n + 1):
-        for j in range(1, m + 1):
+        for j in range(max(1, i - w), min(m, i + w) + 1):
             d = (s[i - 1] - t[j - 1]) ** 2
             cost[i, j] = d + min(cost[i - 1, j], cost[i, j - 1], cost[i - 1, j - 1])
 
@@ -50,9 +63,14 @@ def dba(
     max_iter: int = 30,
     tol: float = 1e-5,
     init: np.ndarray | None = None,
+    window: int | None = None,
+    n_jobs: int | None = None,
 ) -> np.ndarray:
     """Compute the DTW Barycentric Average of a set of time series.
 
+    Delegates the refinement loop to Rust when available, falling back to
+    pure Python otherwise.
+
     Parameters
     ----------
     series
@@ -62,8 +80,14 @@ def dba(
     tol
         Convergence threshold on the mean absolute change.
     init
-        Initial centroid estimate. If *None*, uses the medoid
-        (series with minimum total DTW distance to all others).
+        Initial centroid estimate. If *None*, uses the medoid (series with
+        minimum total squared Euclidean distance to all others, zero-padded
+        to the longest length), or the first series when there are two.
+    window
+        Sakoe-Chiba band for the DTW alignments. Default *None* (unconstrained).
+    n_jobs
+        Number of threads aligning the series in Rust. Defaults to all
+        cores; ``1`` runs sequentially.
 
     Returns
     -------
@@ -76,6 +100,20 @@ def dba(
     if len(series) == 1:
         return series[0].copy()
 
+    try:
+        return _dba_rust(series, max_iter, tol, init, window, n_jobs)
+    except ImportError:
+        return _dba_python(series, max_iter, tol, init, window)
+
+
+def _dba_python(
+    series: list[np.ndarray],
+    max_iter: int,
+    tol: float,
+    init: np.ndarray | None,
+    window: int | None,
+) -> np.ndarray:
+    """Pure-Python DBA refinement loop."""
     # Initialise centroid
     if init is not None:
         centroid = init.copy().astype(np.float64)
@@ -84,7 +122,7 @@ def dba(
         centroid = _medoid_init(series)
 
     for _ in range(max_iter):
-        new_centroid = _dba_update(centroid, series)
+        new_centroid = _dba_update(centroid, series, window)
         change = np.mean(np.abs(new_centroid - centroid))
         centroid = new_centroid
         if change < tol:
@@ -93,8 +131,84 @@ def dba(
     return centroid
 
 
+def softdtw_barycenter(
+    series: list[np.ndarray],
+    gamma: float = 1.0,
+    max_iter: int = 50,
+    tol: float = 1e-5,
+    init: np.ndarray | None = None,
+    weights: np.ndarray | None = None,
+    n_jobs: int | None = None,
+) -> np.ndarray:
+    """Compute the soft-DTW barycenter of a set of time series.
+
+    Minimises the weighted sum of soft-DTW values to all series with
+    L-BFGS. The objective and its gradient are evaluated in Rust.
+
+    Parameters
+    ----------
+    series
+        List of 1-D numpy arrays (may differ in length).
+    gamma
+        Soft-DTW smoothing parameter. Smaller values are closer to DTW.
+    max_iter
+        Maximum number of L-BFGS iterations.
+    tol
+        Convergence tolerance passed to the optimiser.
+    init
+        Initial barycenter, which also fixes its length. If *None*, uses
+        the same medoid initialisation as :func:`dba`.
+    weights
+        Per-series weights. Defaults to uniform weights.
+    n_jobs
+        Number of threads evaluating the objective. Defaults to all cores;
+        ``1`` runs sequentially.
+
+    Returns
+    -------
+    np.ndarray
+        The soft-DTW barycenter.
+
+    """
+    if len(series) == 0:
+        return np.array([])
+    if len(series) == 1 and init is None:
+        return series[0].copy().astype(np.float64)
+
+    from polars_ts_rs.polars_ts_rs import softdtw_barycenter_grad
+    from scipy.optimize import minimize
+
+    z0 = init.copy().astype(np.float64) if init is not None else _medoid_init(series)
+    data = [np.asarray(s, dtype=np.float64).tolist() for s in series]
+    w = None if weights is None else np.asarray(weights, dtype=np.float64).tolist()
+
+    def _objective(z: np.ndarray) -> tuple[float, np.ndarray]:
+        value, grad = softdtw_barycenter_grad(z.tolist(), data, gamma, w, n_jobs=n_jobs)
+        return value, np.asarray(grad)
+
+    result = minimize(_objective, z0, jac=True, method="L-BFGS-B", tol=tol, options={"maxiter": max_iter})
+    return np.asarray(result.x, dtype=np.float64)
+
+
+def _dba_rust(
+    series: list[np.ndarray],
+    max_iter: int,
+    tol: float,
+    init: np.ndarray | None,
+    window: int | None,
+    n_jobs: int | None = None,
+) -> np.ndarray:
+    """Run DBA via Rust extension."""
+    from polars_ts_rs.polars_ts_rs import dba as _dba_rs
+
+    init_list = None if init is None else np.asarray(init, dtype=np.float64).tolist()
+    data = [np.asarray(s, dtype=np.float64).tolist() for s in series]
+    centroid = _dba_rs(data, init=init_list, max_iter=max_iter, window=window, tol=tol, n_jobs=n_jobs)
+    return np.asarray(centroid, dtype=np.float64)
+
+
 def _medoid_init(series: list[np.ndarray]) -> np.ndarray:
-    """Pick the series with minimum total squared DTW distance as init."""
+    """Pick the series with minimum total squared Euclidean distance as init."""
     n = len(series)
     if n <= 2:
         return series[0].copy().astype(np.float64)
@@ -113,14 +227,14 @@ def _medoid_init(series: list[np.ndarray]) -> np.ndarray:
     return padded[int(np.argmin(costs))]
 
 
-def _dba_update(centroid: np.ndarray, series: list[np.ndarray]) -> np.ndarray:
+def _dba_update(centroid: np.ndarray, series: list[np.ndarray], window: int | None = None) -> np.ndarray:
     """One DBA refinement step: align all series to centroid, average."""
     c_len = len(centroid)
     total = np.zeros(c_len)
     counts = np.zeros(c_len)
 
     for s in series:
-        path = _dtw_alignment_path(centroid, s)
+        path = _dtw_alignment_path(centroid, s, window)
         for ci, si in path:
             total[ci] += s[si]
             counts[ci] += 1
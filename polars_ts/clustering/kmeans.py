@@ -2,7 +2,8 @@
 
 Uses DTW-based distances for the assignment step and DBA for the centroid
 update step, producing synthetic centroids that better represent cluster
-averages than medoid-based approaches.
+averages than medoid-based approaches. With ``metric="softdtw"`` both steps
+use soft-DTW instead, with barycenters computed by gradient descent.
 """
 
 from __future__ import annotations
@@ -13,7 +14,7 @@ from typing import Any
 import numpy as np
 import polars as pl
 
-from polars_ts.clustering.dba import dba
+from polars_ts.clustering.dba import _band_width, dba, softdtw_barycenter
 
 
 class TimeSeriesKMeans:
@@ -24,14 +25,22 @@ class TimeSeriesKMeans:
     n_clusters
         Number of clusters. Default 2.
     metric
-        Distance metric name. Currently only ``"dtw"`` is supported
-        (DBA requires DTW alignment paths). Default ``"dtw"``.
+        ``"dtw"`` (DBA centroids) or ``"softdtw"`` (soft-DTW assignment and
+        soft-DTW barycenters). Default ``"dtw"``.
     max_iter
         Maximum number of k-means iterations. Default 50.
     dba_max_iter
-        Maximum DBA refinement iterations per centroid update. Default 30.
+        Maximum DBA (or soft-DTW barycenter) iterations per centroid update. Default 30.
     seed
         Random seed for initial centroid selection. Default 42.
+    gamma
+        Soft-DTW smoothing parameter, used with ``metric="softdtw"``. Default 1.0.
+    window
+        Sakoe-Chiba band for DTW assignment and DBA alignment, used with
+        ``metric="dtw"``. Default *None* (unconstrained).
+    n_jobs
+        Number of threads for the assignment and centroid updates. Defaults
+        to all cores; ``1`` runs sequentially.
     **distance_kwargs
         Extra keyword arguments forwarded to the distance function.
 
@@ -44,6 +53,9 @@ class TimeSeriesKMeans:
         max_iter: int = 50,
         dba_max_iter: int = 30,
         seed: int = 42,
+        gamma: float = 1.0,
+        window: int | None = None,
+        n_jobs: int | None = None,
         **distance_kwargs: Any,
     ) -> None:
         self.n_clusters = n_clusters
@@ -51,6 +63,9 @@ class TimeSeriesKMeans:
         self.max_iter = max_iter
         self.dba_max_iter = dba_max_iter
         self.seed = seed
+        self.gamma = gamma
+        self.window = window
+        self.n_jobs = n_jobs
         self.distance_kwargs = distance_kwargs
         self.labels_: pl.DataFrame | None = None
         self.centroids_: list[np.ndarray] = []
@@ -77,8 +92,8 @@ class TimeSeriesKMeans:
         self
 
         """
-        if self.metric != "dtw":
-            raise ValueError(f"Only metric='dtw' is supported for DBA-based k-means, got {self.metric!r}")
+        if self.metric not in ("dtw", "softdtw"):
+            raise ValueError(f"Only metric='dtw' or 'softdtw' is supported for k-means, got {self.metric!r}")
 
         ids = sorted(df[id_col].unique().cast(pl.String).to_list())
         n = len(ids)
@@ -95,12 +110,43 @@ class TimeSeriesKMeans:
 
         series_list = [series_map[uid] for uid in ids]
 
-        # Initialize centroids by picking k random series
-        rng = random.Random(self.seed)
-        init_indices = rng.sample(range(n), self.n_clusters)
-        centroids = [series_list[i].copy() for i in init_indices]
+        # The DTW/DBA loop runs in Rust when available (assignment and DBA in parallel)
+        if self.metric == "dtw":
+            try:
+                assignments, centroids = self._fit_rust(series_list)
+            except ImportError:
+                assignments, centroids = self._fit_python(series_list)
+        else:
+            assignments, centroids = self._fit_python(series_list)
 
-        assignments = [-1] * n
+        self.centroids_ = centroids
+        self.labels_ = pl.DataFrame(
+            {id_col: ids, "cluster": assignments},
+            schema={id_col: df[id_col].dtype, "cluster": pl.Int64},
+        )
+        return self
+
+    def _fit_rust(self, series_list: list[np.ndarray]) -> tuple[list[int], list[np.ndarray]]:
+        """Run the k-means-DBA loop via Rust extension."""
+        from polars_ts_rs.polars_ts_rs import kmeans_dba_fit
+
+        n = len(series_list)
+        labels, centroids = kmeans_dba_fit(
+            [s.tolist() for s in series_list],
+            self._init_indices(n),
+            [self._reset_index(ci, n) for ci in range(self.n_clusters)],
+            max_iter=self.max_iter,
+            dba_max_iter=self.dba_max_iter,
+            window=self.window,
+            n_jobs=self.n_jobs,
+        )
+        return list(labels), [np.asarray(c, dtype=np.float64) for c in centroids]
+
+    def _fit_python(self, series_list: list[np.ndarray]) -> tuple[list[int], list[np.ndarray]]:
+        """Pure-Python k-means loop (soft-DTW, or fallback for DTW)."""
+        centroids = [series_list[i].copy() for i in self._init_indices(len(series_list))]
+
+        assignments = [-1] * len(series_list)
 
         for _ in range(self.max_iter):
             # Assignment step: assign each series to nearest centroid
@@ -113,26 +159,37 @@ class TimeSeriesKMeans:
 
             # Update centroids via DBA
             centroids = self._update_centroids(series_list, assignments)
+        return assignments, centroids
 
-        self.centroids_ = centroids
-        self.labels_ = pl.DataFrame(
-            {id_col: ids, "cluster": assignments},
-            schema={id_col: df[id_col].dtype, "cluster": pl.Int64},
-        )
-        return self
+    def _init_indices(self, n: int) -> list[int]:
+        """Indices of the series picked as initial centroids."""
+        return random.Random(self.seed).sample(range(n), self.n_clusters)
+
+    def _reset_index(self, cluster: int, n: int) -> int:
+        """Index of the series an emptied cluster is reset to."""
+        return random.Random(self.seed + cluster).randint(0, n - 1)
 
     def _assign(
         self,
         series_list: list[np.ndarray],
         centroids: list[np.ndarray],
     ) -> list[int]:
-        """Assign each series to the nearest centroid using DTW distance."""
+        """Assign each series to the nearest centroid using DTW (or soft-DTW) distance."""
+        if self.metric == "softdtw":
+            # All (series, centroid) pairs in one parallel Rust call when available
+            try:
+                return self._softdtw_assign_rust(series_list, centroids)
+            except ImportError:
+                pass
         assignments = []
         for s in series_list:
             best_cluster = 0
             best_dist = float("inf")
             for ci, c in enumerate(centroids):
-                d = self._dtw_distance(s, c)
+                if self.metric == "softdtw":
+                    d = self._softdtw_distance(s, c, self.gamma)
+                else:
+                    d = self._dtw_distance(s, c, self.window)
                 if d < best_dist:
                     best_dist = d
                     best_cluster = ci
@@ -140,31 +197,60 @@ class TimeSeriesKMeans:
         return assignments
 
     @staticmethod
-    def _dtw_distance(s: np.ndarray, t: np.ndarray) -> float:
-        """Compute DTW distance between two series."""
+    def _dtw_distance(s: np.ndarray, t: np.ndarray, window: int | None = None) -> float:
+        """Compute DTW distance between two series, optionally within a Sakoe-Chiba band."""
         n, m = len(s), len(t)
+        w = _band_width(n, m, window)
         cost = np.full((n + 1, m + 1), np.inf)
         cost[0, 0] = 0.0
         for i in range(1, n + 1):
-            for j in range(1, m + 1):
+            for j in range(max(1, i - w), min(m, i + w) + 1):
                 d = (s[i - 1] - t[j - 1]) ** 2
                 cost[i, j] = d + min(cost[i - 1, j], cost[i, j - 1], cost[i - 1, j - 1])
         return float(cost[n, m])
 
+    def _softdtw_assign_rust(self, series_list: list[np.ndarray], centroids: list[np.ndarray]) -> list[int]:
+        """Assign every series to its nearest centroid by soft-DTW via Rust extension."""
+        from polars_ts_rs.polars_ts_rs import softdtw_assign
+
+        return list(
+            softdtw_assign(
+                [s.tolist() for s in series_list], [c.tolist() for c in centroids], self.gamma, n_jobs=self.n_jobs
+            )
+        )
+
+    @staticmethod
+    def _softdtw_distance(s: np.ndarray, t: np.ndarray, gamma: float) -> float:
+        """Compute the soft-DTW value between two series over squared costs."""
+        n, m = len(s), len(t)
+        cost = np.full((n + 1, m + 1), np.inf)
+        cost[0, 0] = 0.0
+        for i in range(1, n + 1):
+            for j in range(1, m + 1):
+                prev = np.array([cost[i - 1, j - 1], cost[i - 1, j], cost[i, j - 1]])
+                lo = prev.min()
+                soft = lo if np.isinf(lo) else lo - gamma * np.log(np.exp(-(prev - lo) / gamma).sum())
+                cost[i, j] = (s[i - 1] - t[j - 1]) ** 2 + soft
+        return float(cost[n, m])
+
     def _update_centroids(
         self,
         series_list: list[np.ndarray],
         assignments: list[int],
     ) -> list[np.ndarray]:
-        """Recompute centroids via DBA."""
+        """Recompute centroids via DBA or soft-DTW barycenters."""
         centroids = []
         for ci in range(self.n_clusters):
             members = [series_list[i] for i, a in enumerate(assignments) if a == ci]
-            if members:
-                centroids.append(dba(members, max_iter=self.dba_max_iter))
+            if members and self.metric == "softdtw":
+                centroids.append(
+                    softdtw_barycenter(members, gamma=self.gamma, max_iter=self.dba_max_iter, n_jobs=self.n_jobs)
+                )
+            elif members:
+                centroids.append(dba(members, max_iter=self.dba_max_iter, window=self.window, n_jobs=self.n_jobs))
             else:
                 # Empty cluster: reinitialize with a random series
-                centroids.append(series_list[random.Random(self.seed + ci).randint(0, len(series_list) - 1)].copy())
+                centroids.append(series_list[self._reset_index(ci, len(series_list))].copy())
         return centroids
 
 
@@ -187,7 +273,8 @@ def kmeans_dba(
     k
         Number of clusters.
     method
-        Distance metric name (e.g. ``"dtw"``). Default ``"dtw"``.
+        ``"dtw"`` for DBA centroids or ``"softdtw"`` for soft-DTW
+        barycenters. Default ``"dtw"``.
     max_iter
         Maximum k-means iterations.
     seed
@@ -103,31 +103,27 @@ class TimeSeriesKMedoids:
         return self
 
 
-def _build_dist_matrix(dist_dict: dict[tuple[str, str], float], str_ids: list[str]) -> list[float]:
-    """Convert distance dict to flat n×n row-major matrix."""
-    n = len(str_ids)
-    id_to_idx = {sid: i for i, sid in enumerate(str_ids)}
-    flat = [0.0] * (n * n)
-    for (a, b), d in dist_dict.items():
-        if a in id_to_idx and b in id_to_idx:
-            i, j = id_to_idx[a], id_to_idx[b]
-            flat[i * n + j] = d
-    return flat
+def _matrix_to_flat(matrix: pl.DataFrame, str_ids: list[str]) -> list[float]:
+    """Reorder a wide ``output="matrix"`` distance frame to ``str_ids`` and flatten it row-major."""
+    position = {sid: i for i, sid in enumerate(str_ids)}
+    ordered = matrix.with_columns(pl.col("unique_id").cast(pl.String)).sort(
+        pl.col("unique_id").replace_strict(position, return_dtype=pl.Int64)
+    )
+    return ordered.select(str_ids).to_numpy().ravel().tolist()
 
 
 def _kmedoids_rust(
-    dist_dict: dict[tuple[str, str], float],
-    str_ids: list[str],
+    flat: list[float],
+    n: int,
     k: int,
     max_iter: int,
     seed: int,
+    n_jobs: int | None = None,
 ) -> tuple[list[int], list[int]]:
     """Run PAM via Rust extension."""
     from polars_ts_rs import kmedoids_pam
 
-    n = len(str_ids)
-    flat = _build_dist_matrix(dist_dict, str_ids)
-    medoid_indices, assignments = kmedoids_pam(flat, n, k, max_iter, seed)
+    medoid_indices, assignments = kmedoids_pam(flat, n, k, max_iter, seed, n_jobs=n_jobs)
     return medoid_indices, assignments
 
 
@@ -193,6 +189,7 @@ def kmedoids(
     seed: int = 42,
     id_col: str = "unique_id",
     target_col: str = "y",
+    n_jobs: int | None = None,
     **distance_kwargs: Any,
 ) -> pl.DataFrame:
     """K-Medoids (PAM) clustering over time series.
@@ -213,6 +210,9 @@ def kmedoids(
         Column identifying each time series.
     target_col
         Column with the time series values.
+    n_jobs
+        Number of threads for the distance matrix and the swap loop.
+        Defaults to all cores; ``1`` runs sequentially.
     **distance_kwargs
         Extra keyword arguments forwarded to the distance function.
 
@@ -229,21 +229,17 @@ def kmedoids(
     if k > n:
         raise ValueError(f"k ({k}) must be <= number of series ({n})")
 
-    # Compute pairwise distances
+    # Compute the dense distance matrix, flattened in ``ids`` order
     dist_df = df.select(pl.col(id_col).alias("unique_id"), pl.col(target_col).alias("y"))
-    pairwise = compute_distances(dist_df, dist_df, method=method, **distance_kwargs)
-    dist_dict = pairwise_to_dict(pairwise)
-
+    matrix = compute_distances(dist_df, dist_df, method=method, output="matrix", n_jobs=n_jobs, **distance_kwargs)
     str_ids = [str(i) for i in ids]
-
-    # Self-distance is 0
-    for sid in str_ids:
-        dist_dict[(sid, sid)] = 0.0
+    flat = _matrix_to_flat(matrix, str_ids)
 
     # Try Rust, fall back to Python
     try:
-        _medoid_indices, assignment_labels = _kmedoids_rust(dist_dict, str_ids, k, max_iter, seed)
+        _medoid_indices, assignment_labels = _kmedoids_rust(flat, n, k, max_iter, seed, n_jobs)
     except ImportError:
+        dist_dict = {(a, b): flat[i * n + j] for i, a in enumerate(str_ids) for j, b in enumerate(str_ids)}
         _medoid_indices, assignment_labels = _kmedoids_python(dist_dict, str_ids, k, max_iter, seed)
 
     rows = list(zip(ids, assignment_labels, strict=False))
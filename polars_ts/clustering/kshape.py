@@ -29,38 +29,6 @@ def _sbd(x: np.ndarray, y: np.ndarray) -> tuple[float, np.ndarray]:
     return float(1.0 - ncc.max()), y_shifted
 
 
-def _shape_extraction(cluster_series: list[np.ndarray], length: int) -> np.ndarray:
-    """Extract centroid shape via eigenvalue decomposition (Paparrizos & Gravano).
-
-    Computes the first eigenvector of the cross-correlation matrix S^T * S,
-    which maximizes the sum of squared normalized cross-correlations.
-    """
-    if not cluster_series:
-        return np.zeros(length)
-
-    # Z-normalize all series
-    normed = [_zscore(s) for s in cluster_series]
-
-    # Build the matrix M = S^T @ S where S stacks the series
-    s_matrix = np.column_stack(normed) if len(normed) > 1 else normed[0].reshape(-1, 1)
-    m = s_matrix @ s_matrix.T
-
-    # Dominant eigenvector via power iteration
-    vec = np.random.default_rng(42).standard_normal(length)
-    for _ in range(100):
-        new_vec = m @ vec
-        norm = np.linalg.norm(new_vec)
-        if norm == 0:
-            return np.zeros(length)
-        vec = new_vec / norm
-
-    # Pick sign that maximizes correlation with first series
-    if normed and np.dot(vec, normed[0]) < 0:
-        vec = -vec
-
-    return _zscore(vec)
-
-
 class KShape:
     """k-Shape clustering for time series.
 
@@ -70,6 +38,8 @@ class KShape:
     Args:
         n_clusters: Number of clusters. Default 2.
         max_iter: Maximum number of iterations. Default 100.
+        n_jobs: Number of threads for the assignment and alignment steps.
+            Defaults to all cores; ``1`` runs sequentially.
 
     Examples:
         >>> ks = KShape(n_clusters=3)
@@ -78,9 +48,10 @@ class KShape:
 
     """
 
-    def __init__(self, n_clusters: int = 2, max_iter: int = 100) -> None:
+    def __init__(self, n_clusters: int = 2, max_iter: int = 100, n_jobs: int | None = None) -> None:
         self.n_clusters = n_clusters
         self.max_iter = max_iter
+        self.n_jobs = n_jobs
         self.labels_: pl.DataFrame | None = None
         self.centroids_: list[np.ndarray] = []
 
@@ -106,49 +77,8 @@ class KShape:
         if n < self.n_clusters:
             raise ValueError(f"Cannot create {self.n_clusters} clusters from {n} time series")
 
-        length = max(len(s) for s in series_list)
-
-        # Pad series to same length
-        padded = []
-        for s in series_list:
-            if len(s) < length:
-                s = np.pad(s, (0, length - len(s)))
-            padded.append(s)
-
-        # Initialize assignments round-robin
-        assignments = [i % self.n_clusters for i in range(n)]
-
-        # Initialize centroids
-        centroids = [np.zeros(length) for _ in range(self.n_clusters)]
-        for ci in range(self.n_clusters):
-            members = [padded[i] for i in range(n) if assignments[i] == ci]
-            if members:
-                centroids[ci] = _shape_extraction(members, length)
-
-        # Iterate
-        for _ in range(self.max_iter):
-            # Assignment step
-            new_assignments = []
-            for i in range(n):
-                best_cluster = 0
-                best_dist = float("inf")
-                for ci in range(self.n_clusters):
-                    dist, _ = _sbd(padded[i], centroids[ci])
-                    if dist < best_dist:
-                        best_dist = dist
-                        best_cluster = ci
-                new_assignments.append(best_cluster)
-
-            # Check convergence
-            if new_assignments == assignments:
-                break
-            assignments = new_assignments
-
-            # Update centroids
-            for ci in range(self.n_clusters):
-                members = [padded[i] for i in range(n) if assignments[i] == ci]
-                if members:
-                    centroids[ci] = _shape_extraction(members, length)
+        # The k-Shape loop runs in Rust (FFT-based SBD, parallel assignment)
+        assignments, centroids = self._fit_rust(series_list)
 
         self.centroids_ = centroids
         self.labels_ = pl.DataFrame(
@@ -158,3 +88,12 @@ class KShape:
             }
         )
         return self
+
+    def _fit_rust(self, series_list: list[np.ndarray]) -> tuple[list[int], list[np.ndarray]]:
+        """Run the k-Shape loop via Rust extension."""
+        from polars_ts_rs.polars_ts_rs import kshape_fit
+
+        labels, centroids = kshape_fit(
+            [s.tolist() for s in series_list], self.n_clusters, max_iter=self.max_iter, n_jobs=self.n_jobs
+        )
+        return list(labels), [np.asarray(c, dtype=np.float64) for c in centroids]
@@ -6,24 +6,45 @@ from typing import Any, Literal
 
 import polars as pl
 from polars_ts_rs.polars_ts_rs import (
+    compute_pairwise_adtw,
+    compute_pairwise_chebyshev,
+    compute_pairwise_cid,
+    compute_pairwise_correlation,
+    compute_pairwise_cosine,
     compute_pairwise_ddtw,
+    compute_pairwise_ddtw_multi,
     compute_pairwise_dtw,
     compute_pairwise_dtw_multi,
     compute_pairwise_edr,
+    compute_pairwise_edr_multi,
     compute_pairwise_erp,
+    compute_pairwise_erp_multi,
+    compute_pairwise_euclidean,
     compute_pairwise_frechet,
+    compute_pairwise_frechet_multi,
+    compute_pairwise_gak,
     compute_pairwise_lcss,
+    compute_pairwise_lcss_multi,
+    compute_pairwise_minkowski,
     compute_pairwise_msm,
     compute_pairwise_msm_multi,
     compute_pairwise_sbd,
+    compute_pairwise_shapedtw,
+    compute_pairwise_softdtw,
     compute_pairwise_twe,
+    compute_pairwise_twe_multi,
+    compute_pairwise_wddtw,
     compute_pairwise_wdtw,
+    compute_pairwise_wdtw_multi,
 )
 
 _UNIVARIATE_METHODS = {
     "dtw",
     "ddtw",
     "wdtw",
+    "wddtw",
+    "adtw",
+    "shapedtw",
     "msm",
     "erp",
     "lcss",
@@ -31,15 +52,54 @@ _UNIVARIATE_METHODS = {
     "sbd",
     "frechet",
     "edr",
+    "softdtw",
+    "gak",
+    "euclidean",
+    "minkowski",
+    "chebyshev",
+    "correlation",
+    "cid",
+    "cosine",
 }
 
 _MULTIVARIATE_METHODS = {
     "dtw_multi",
     "msm_multi",
+    "erp_multi",
+    "lcss_multi",
+    "edr_multi",
+    "twe_multi",
+    "wdtw_multi",
+    "ddtw_multi",
+    "frechet_multi",
 }
 
 _ALL_METHODS = _UNIVARIATE_METHODS | _MULTIVARIATE_METHODS
 
+# Lock-step methods whose only method-specific option is ``unequal_length``.
+_LOCKSTEP_FUNCS = {
+    "euclidean": compute_pairwise_euclidean,
+    "chebyshev": compute_pairwise_chebyshev,
+    "correlation": compute_pairwise_correlation,
+    "cid": compute_pairwise_cid,
+    "cosine": compute_pairwise_cosine,
+}
+
+# Options accepted by every method, forwarded unchanged to the Rust kernels.
+_SHARED_OPTIONS = {
+    "k",
+    "output",
+    "id_col",
+    "target_col",
+    "time_col",
+    "normalize",
+    "mode",
+    "missing",
+    "n_jobs",
+    "sink_parquet",
+    "memory_budget",
+}
+
 
 def compute_pairwise_distance(
     input1: pl.DataFrame,
@@ -48,6 +108,9 @@ def compute_pairwise_distance(
         "dtw",
         "ddtw",
         "wdtw",
+        "wddtw",
+        "adtw",
+        "shapedtw",
         "msm",
         "erp",
         "lcss",
@@ -55,8 +118,23 @@ def compute_pairwise_distance(
         "sbd",
         "frechet",
         "edr",
+        "softdtw",
+        "gak",
+        "euclidean",
+        "minkowski",
+        "chebyshev",
+        "correlation",
+        "cid",
+        "cosine",
         "dtw_multi",
         "msm_multi",
+        "erp_multi",
+        "lcss_multi",
+        "edr_multi",
+        "twe_multi",
+        "wdtw_multi",
+        "ddtw_multi",
+        "frechet_multi",
     ] = "dtw",
     **kwargs: Any,
 ) -> pl.DataFrame:
@@ -67,7 +145,8 @@ def compute_pairwise_distance(
 
     Args:
         input1: DataFrame with columns ``unique_id`` and ``y`` (univariate)
-            or ``unique_id`` and multiple value columns (multivariate).
+            or ``unique_id`` and multiple value columns (multivariate). Other
+            column names can be selected with ``id_col`` / ``target_col``.
         input2: DataFrame with the same schema as ``input1``.
         method: The distance metric to use. One of:
 
@@ -75,23 +154,113 @@ def compute_pairwise_distance(
 
             - ``dtw`` — Dynamic Time Warping (default). Accepts ``dtw_method``
               (``standard``, ``sakoe_chiba``, ``itakura``, ``fast``) and ``param``.
-            - ``ddtw`` — Derivative DTW. No extra parameters.
+            - ``ddtw`` — Derivative DTW.
             - ``wdtw`` — Weighted DTW. Accepts ``g`` (weight penalty, default 0.05).
+            - ``wddtw`` — Weighted derivative DTW. Accepts ``g`` like ``wdtw``.
+            - ``adtw`` — Amerced DTW. Accepts ``penalty`` (cost of each
+              non-diagonal warping step, default 1.0).
+            - ``shapedtw`` — shapeDTW. Accepts ``reach`` (subsequences of
+              ``2 * reach + 1`` observations, default 15), ``descriptor``
+              (``"raw"`` (default), ``"paa"`` or ``"slope"``) and ``segments``
+              (segments of the ``paa`` and ``slope`` descriptors, default 5).
+
+            ``dtw``, ``ddtw``, ``wdtw``, ``wddtw``, ``adtw`` and ``shapedtw``
+            also accept ``cost``, the local cost between two observations:
+            ``"abs"`` (default for ``dtw`` and ``ddtw``), ``"squared"``
+            (default for the others) or ``"huber(delta)"``. ``dtw``, ``ddtw``,
+            ``wdtw`` and ``wddtw`` accept ``normalize_path=True`` to divide
+            each distance by the length of its warping path.
             - ``msm`` — Move-Split-Merge. Accepts ``c`` (cost, default 1.0).
             - ``erp`` — Edit Distance with Real Penalty. Accepts ``g`` (gap value, default 0.0).
             - ``lcss`` — Longest Common Subsequence. Accepts ``epsilon`` (threshold, default 1.0).
             - ``twe`` — Time Warp Edit Distance. Accepts ``nu`` (stiffness, default 0.001)
               and ``lambda_`` (edit penalty, default 1.0).
+            - ``edr`` — Edit Distance on Real sequences. Accepts ``epsilon`` (threshold, default 0.1).
+            - ``softdtw`` — Soft-DTW over squared costs. Accepts ``gamma``
+              (smoothing, default 1.0).
+            - ``gak`` — Global Alignment Kernel, a similarity rather than a
+              distance (higher means more alike). Accepts ``sigma`` (bandwidth,
+              default 1.0), ``triangular`` (band width, default unconstrained),
+              ``normalized`` (default ``True``, so k(x, x) = 1) and ``log``
+              (default ``False``). Not combinable with ``k``.
+            - ``sbd`` — Shape-Based Distance. Accepts ``return_shift``
+              (default ``False``) to add the aligning ``shift`` of each pair.
+
+            ``msm``, ``erp``, ``lcss``, ``twe`` and ``edr`` also accept ``window``,
+            a Sakoe-Chiba band in time steps (default unconstrained).
+
+            **Lock-step (univariate, observation ``i`` against observation ``i``):**
+
+            - ``euclidean``, ``chebyshev`` — Euclidean and maximum absolute difference.
+            - ``minkowski`` — Minkowski distance. Accepts ``p`` (order >= 1, default 2).
+            - ``correlation`` — ``1 - r`` with ``r`` the Pearson correlation.
+            - ``cid`` — Complexity-Invariant Distance.
+            - ``cosine`` — ``1 - cos(a, b)``.
+
+            Lock-step methods accept ``unequal_length``: ``"error"`` (default)
+            rejects series of different lengths and ``"resample"`` linearly
+            interpolates the shorter series of each pair to the longer length.
 
             **Multivariate:**
 
-            - ``dtw_multi`` — Multivariate DTW. Accepts ``metric`` (``manhattan`` or ``euclidean``).
+            - ``dtw_multi`` — Multivariate DTW. Accepts ``strategy``
+              (``"dependent"`` shares one warping path across dimensions,
+              ``"independent"`` sums per-dimension DTW, ``"adaptive"`` picks
+              per pair by comparing their ratio with ``adaptive_threshold``,
+              default 1.0), ``weights`` (one per dimension) and ``window``.
             - ``msm_multi`` — Multivariate MSM. Accepts ``c`` (cost, default 1.0).
-
-        **kwargs: Method-specific parameters (see above).
+            - ``erp_multi``, ``lcss_multi``, ``edr_multi``, ``twe_multi``,
+              ``wdtw_multi``, ``ddtw_multi``, ``frechet_multi`` — multivariate
+              versions of the univariate methods, with the same parameters.
+
+            Every multivariate method except ``msm_multi`` accepts ``metric``,
+            the distance between two time steps: ``manhattan`` (default),
+            ``euclidean`` or ``squared_euclidean`` (default for ``wdtw_multi``).
+
+            **Shared options (all methods):**
+
+            - ``k`` — keep only the ``k`` nearest ``input2`` series of each
+              ``input1`` series. The result gains a 1-based ``rank`` column.
+            - ``output`` — ``"long"`` (default) for one row per pair,
+              ``"matrix"`` for a dense wide DataFrame (a ``unique_id`` column
+              with the ``input1`` ids, then one column per ``input2`` id), or
+              ``"condensed"`` for the upper triangle in ``scipy`` ``pdist`` order.
+            - ``id_col``, ``target_col``, ``time_col`` — input column names
+              (defaults ``unique_id`` and ``y``). Observations are sorted by
+              ``time_col`` before the distance, which defaults to ``ds`` when the
+              input has that column; pass ``time_col=None`` to keep the row
+              order. Multivariate methods use every column except ``id_col``
+              and ``time_col`` as a dimension.
+            - ``normalize`` — per-series rescaling applied before the distance:
+              ``"none"`` (default), ``"zscore"``, ``"minmax"``, ``"mean"`` or
+              ``"robust"`` (median / MAD). Multivariate series are rescaled per dimension.
+            - ``mode`` — which pairs are evaluated: ``"cross_dedup"`` (default)
+              skips same-id pairs and evaluates mirrored pairs once,
+              ``"cross_full"`` returns the full rectangular ``input1`` x ``input2``
+              table including same-id pairs (query vs. reference sets), and
+              ``"self_symmetric"`` returns the upper triangle of a self-distance
+              matrix and requires both inputs to hold the same ids.
+            - ``missing`` — how null and NaN observations are handled before the
+              distance: ``"error"`` (default) raises naming the series id,
+              ``"drop"`` removes them, ``"linear_interpolate"`` fills gaps
+              linearly and ``"ffill"`` carries the last value forward.
+            - ``n_jobs`` — number of worker threads. Defaults to all cores;
+              ``1`` computes sequentially, which avoids oversubscription when
+              the call already runs inside a multiprocessing or Dask worker.
+            - ``sink_parquet`` — path of a Parquet file to stream the long
+              output to instead of returning it. Pairs are computed in blocks,
+              each written as one row group, so the full table never has to
+              fit in memory. Not combinable with ``k`` or ``output``.
+            - ``memory_budget`` — approximate bytes of results held in memory at
+              once with ``sink_parquet`` (default 256 MiB).
+
+        **kwargs: Method-specific parameters and shared options (see above).
 
     Returns:
         A DataFrame with columns ``unique_id_1``, ``unique_id_2``, and the distance column.
+        With ``sink_parquet``, a one-row summary with the ``path``, the number of
+        ``pairs`` written and the number of ``blocks``; read the result with
+        ``pl.scan_parquet(path)``.
 
     Raises:
         ValueError: If an unknown method or unexpected keyword argument is passed.
@@ -100,69 +269,137 @@ def compute_pairwise_distance(
         >>> compute_pairwise_distance(df, df, method="dtw")
         >>> compute_pairwise_distance(df, df, method="lcss", epsilon=0.5)
         >>> compute_pairwise_distance(df, df, method="dtw", dtw_method="sakoe_chiba", param=3.0)
+        >>> compute_pairwise_distance(queries, train, method="msm", k=5)
 
     """
     if method not in _ALL_METHODS:
         raise ValueError(f"Unknown method {method!r}. Choose from: {sorted(_ALL_METHODS)}")
 
+    shared = _pick(kwargs, *_SHARED_OPTIONS)
+    kwargs = {k: v for k, v in kwargs.items() if k not in _SHARED_OPTIONS}
+
     if method == "dtw":
-        _check_kwargs(kwargs, {"dtw_method", "param"}, method)
-        dtw_kw: dict[str, Any] = {}
+        _check_kwargs(kwargs, {"dtw_method", "param", "cost", "normalize_path"}, method)
+        dtw_kw: dict[str, Any] = _pick(kwargs, "param", "cost", "normalize_path")
         if "dtw_method" in kwargs:
             dtw_kw["method"] = kwargs["dtw_method"]
-        if "param" in kwargs:
-            dtw_kw["param"] = kwargs["param"]
-        return compute_pairwise_dtw(input1, input2, **dtw_kw)
+        return compute_pairwise_dtw(input1, input2, **dtw_kw, **shared)
 
     if method == "ddtw":
-        _check_kwargs(kwargs, set(), method)
-        return compute_pairwise_ddtw(input1, input2)
+        _check_kwargs(kwargs, {"cost", "normalize_path"}, method)
+        return compute_pairwise_ddtw(input1, input2, **_pick(kwargs, "cost", "normalize_path"), **shared)
 
     if method == "wdtw":
-        _check_kwargs(kwargs, {"g"}, method)
-        return compute_pairwise_wdtw(input1, input2, **_pick(kwargs, "g"))
+        _check_kwargs(kwargs, {"g", "cost", "normalize_path"}, method)
+        return compute_pairwise_wdtw(input1, input2, **_pick(kwargs, "g", "cost", "normalize_path"), **shared)
+
+    if method == "wddtw":
+        _check_kwargs(kwargs, {"g", "cost", "normalize_path"}, method)
+        return compute_pairwise_wddtw(input1, input2, **_pick(kwargs, "g", "cost", "normalize_path"), **shared)
+
+    if method == "adtw":
+        _check_kwargs(kwargs, {"penalty", "cost"}, method)
+        return compute_pairwise_adtw(input1, input2, **_pick(kwargs, "penalty", "cost"), **shared)
+
+    if method == "shapedtw":
+        _check_kwargs(kwargs, {"reach", "descriptor", "segments", "cost"}, method)
+        return compute_pairwise_shapedtw(
+            input1, input2, **_pick(kwargs, "reach", "descriptor", "segments", "cost"), **shared
+        )
 
     if method == "msm":
-        _check_kwargs(kwargs, {"c"}, method)
-        return compute_pairwise_msm(input1, input2, **_pick(kwargs, "c"))
+        _check_kwargs(kwargs, {"c", "window"}, method)
+        return compute_pairwise_msm(input1, input2, **_pick(kwargs, "c", "window"), **shared)
 
     if method == "erp":
-        _check_kwargs(kwargs, {"g"}, method)
-        return compute_pairwise_erp(input1, input2, **_pick(kwargs, "g"))
+        _check_kwargs(kwargs, {"g", "window"}, method)
+        return compute_pairwise_erp(input1, input2, **_pick(kwargs, "g", "window"), **shared)
 
     if method == "lcss":
-        _check_kwargs(kwargs, {"epsilon"}, method)
-        return compute_pairwise_lcss(input1, input2, **_pick(kwargs, "epsilon"))
+        _check_kwargs(kwargs, {"epsilon", "window"}, method)
+        return compute_pairwise_lcss(input1, input2, **_pick(kwargs, "epsilon", "window"), **shared)
 
     if method == "twe":
-        _check_kwargs(kwargs, {"nu", "lambda_"}, method)
-        twe_kw: dict[str, Any] = {}
-        if "nu" in kwargs:
-            twe_kw["nu"] = kwargs["nu"]
+        _check_kwargs(kwargs, {"nu", "lambda_", "window"}, method)
+        twe_kw: dict[str, Any] = _pick(kwargs, "nu", "window")
         if "lambda_" in kwargs:
             # Rust param is named "lambda" which is a Python reserved word
             twe_kw["lambda"] = kwargs["lambda_"]
-        return compute_pairwise_twe(input1, input2, **twe_kw)
+        return compute_pairwise_twe(input1, input2, **twe_kw, **shared)
 
     if method == "dtw_multi":
-        _check_kwargs(kwargs, {"metric"}, method)
-        return compute_pairwise_dtw_multi(input1, input2, **_pick(kwargs, "metric"))
+        _check_kwargs(kwargs, {"metric", "strategy", "weights", "window", "adaptive_threshold"}, method)
+        return compute_pairwise_dtw_multi(
+            input1,
+            input2,
+            **_pick(kwargs, "metric", "strategy", "weights", "window", "adaptive_threshold"),
+            **shared,
+        )
 
     if method == "msm_multi":
         _check_kwargs(kwargs, {"c"}, method)
-        return compute_pairwise_msm_multi(input1, input2, **_pick(kwargs, "c"))
+        return compute_pairwise_msm_multi(input1, input2, **_pick(kwargs, "c"), **shared)
+
+    if method == "erp_multi":
+        _check_kwargs(kwargs, {"g", "window", "metric"}, method)
+        return compute_pairwise_erp_multi(input1, input2, **_pick(kwargs, "g", "window", "metric"), **shared)
+
+    if method == "lcss_multi":
+        _check_kwargs(kwargs, {"epsilon", "window", "metric"}, method)
+        return compute_pairwise_lcss_multi(input1, input2, **_pick(kwargs, "epsilon", "window", "metric"), **shared)
+
+    if method == "edr_multi":
+        _check_kwargs(kwargs, {"epsilon", "window", "metric"}, method)
+        return compute_pairwise_edr_multi(input1, input2, **_pick(kwargs, "epsilon", "window", "metric"), **shared)
+
+    if method == "twe_multi":
+        _check_kwargs(kwargs, {"nu", "lambda_", "window", "metric"}, method)
+        twe_multi_kw: dict[str, Any] = _pick(kwargs, "nu", "window", "metric")
+        if "lambda_" in kwargs:
+            twe_multi_kw["lambda"] = kwargs["lambda_"]
+        return compute_pairwise_twe_multi(input1, input2, **twe_multi_kw, **shared)
+
+    if method == "wdtw_multi":
+        _check_kwargs(kwargs, {"g", "metric"}, method)
+        return compute_pairwise_wdtw_multi(input1, input2, **_pick(kwargs, "g", "metric"), **shared)
+
+    if method == "ddtw_multi":
+        _check_kwargs(kwargs, {"metric"}, method)
+        return compute_pairwise_ddtw_multi(input1, input2, **_pick(kwargs, "metric"), **shared)
+
+    if method == "frechet_multi":
+        _check_kwargs(kwargs, {"metric"}, method)
+        return compute_pairwise_frechet_multi(input1, input2, **_pick(kwargs, "metric"), **shared)
 
     if method == "sbd":
-        _check_kwargs(kwargs, set(), method)
-        return compute_pairwise_sbd(input1, input2)
+        _check_kwargs(kwargs, {"return_shift"}, method)
+        return compute_pairwise_sbd(input1, input2, **_pick(kwargs, "return_shift"), **shared)
 
     if method == "frechet":
         _check_kwargs(kwargs, set(), method)
-        return compute_pairwise_frechet(input1, input2)
+        return compute_pairwise_frechet(input1, input2, **shared)
 
     if method == "edr":
-        _check_kwargs(kwargs, {"epsilon"}, method)
-        return compute_pairwise_edr(input1, input2, **_pick(kwargs, "epsilon"))
+        _check_kwargs(kwargs, {"epsilon", "window"}, method)
+        return compute_pairwise_edr(input1, input2, **_pick(kwargs, "epsilon", "window"), **shared)
+
+    if method == "softdtw":
+        _check_kwargs(kwargs, {"gamma"}, method)
+        return compute_pairwise_softdtw(input1, input2, **_pick(kwargs, "gamma"), **shared)
+
+    if method == "gak":
+        _check_kwargs(kwargs, {"sigma", "triangular", "normalized", "log"}, method)
+        return compute_pairwise_gak(
+            input1, input2, **_pick(kwargs, "sigma", "triangular", "normalized", "log"), **shared
+        )
+
+    if method == "minkowski":
+        _check_kwargs(kwargs, {"p", "unequal_length"}, method)
+        return compute_pairwise_minkowski(input1, input2, **_pick(kwargs, "p", "unequal_length"), **shared)
+
+    if method in _LOCKSTEP_FUNCS:
+        _check_kwargs(kwargs, {"unequal_length"}, method)
+        return _LOCKSTEP_FUNCS[method](input1, input2, **_pick(kwargs, "unequal_length"), **shared)
 
     # unreachable due to the check above, but keeps mypy happy
     raise ValueError(f"Unknown method {method!r}")
@@ -178,5 +415,6 @@ def _check_kwargs(kwargs: dict, valid: set[str], method: str) -> None:
     if unexpected:
         raise ValueError(
             f"Unexpected keyword argument(s) {sorted(unexpected)} for method {method!r}. "
-            f"Valid options: {sorted(valid) if valid else '(none)'}"
+            f"Valid options: {sorted(valid) if valid else '(none)'} "
+            f"plus shared options {sorted(_SHARED_OPTIONS)}"
         )
@@ -0,0 +1,79 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_polars::PyDataFrame;
+
+use crate::pointwise::PointCost;
+use crate::utils::{compute_pairwise, PairwiseOptions};
+
+/// Amerced DTW (Herrmann & Webb, 2023): DTW where every step off the
+/// diagonal pays an additive `penalty`. A penalty of 0 is plain DTW and an
+/// infinite one forces the diagonal. O(m) memory.
+fn adtw_distance(a: &[f64], b: &[f64], penalty: f64, cost: PointCost) -> f64 {
+    let n = a.len();
+    let m = b.len();
+    if n == 0 || m == 0 {
+        return f64::INFINITY;
+    }
+    let mut prev = vec![f64::INFINITY; m + 1];
+    let mut curr = vec![f64::INFINITY; m + 1];
+    prev[0] = 0.0;
+
+    for i in 1..=n {
+        curr[0] = f64::INFINITY;
+        for j in 1..=m {
+            let min_prev = prev[j - 1].min(prev[j] + penalty).min(curr[j - 1] + penalty);
+            curr[j] = cost.cost(a[i - 1], b[j - 1]) + min_prev;
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[m]
+}
+
+/// Pairwise Amerced DTW distances.
+///
+/// `penalty` is the cost of each non-diagonal warping step (default 1.0)
+/// and `cost` the local cost, `"squared"` by default.
+#[pyfunction]
+#[pyo3(signature = (input1, input2, penalty=None, cost=None, **options))]
+pub fn compute_pairwise_adtw(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    penalty: Option<f64>,
+    cost: Option<&str>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let penalty = penalty.unwrap_or(1.0);
+    if penalty.is_nan() || penalty < 0.0 {
+        return Err(PyValueError::new_err("penalty must be >= 0"));
+    }
+    let cost = PointCost::parse(cost, PointCost::Squared)?;
+    compute_pairwise(input1, input2, "adtw", &options, move |a, b| {
+        adtw_distance(a, b, penalty, cost)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dtw::dtw_distance;
+
+    #[test]
+    fn test_zero_penalty_is_dtw() {
+        let a = [0.0, 1.0, 3.0, 2.0, 0.0];
+        let b = [0.0, 0.0, 1.0, 3.0, 2.0, 1.0];
+        let d = adtw_distance(&a, &b, 0.0, PointCost::Squared);
+        assert!((d - dtw_distance(&a, &b, PointCost::Squared)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_infinite_penalty_is_lock_step() {
+        let a = [0.0_f64, 1.0, 3.0, 2.0];
+        let b = [1.0_f64, 3.0, 2.0, 0.0];
+        let lock_step: f64 = a.iter().zip(&b).map(|(x, y)| (x - y).powi(2)).sum();
+        assert_eq!(adtw_distance(&a, &b, f64::INFINITY, PointCost::Squared), lock_step);
+        let warped = adtw_distance(&a, &b, 0.5, PointCost::Squared);
+        assert!(warped < lock_step);
+    }
+}
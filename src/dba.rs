@@ -0,0 +1,318 @@
+//! DTW Barycenter Averaging (DBA) and k-means with DBA centroids.
+//!
+//! Implements the iterative averaging of Petitjean et al. (2011): every
+//! iteration aligns each member to the current barycenter with the DTW path
+//! code from `dtw.rs` and replaces each barycenter point by the mean of the
+//! member points aligned to it. Members are aligned in parallel, and the
+//! k-means loop assigns series to centroids in parallel as well, on the
+//! threads chosen by `n_jobs` and with the GIL released.
+//!
+//! Both mirror the pure-Python fallbacks in `polars_ts.clustering` step for
+//! step (squared pointwise cost, medoid initialisation), and the k-means
+//! loop takes its initial and reset centroids as indices drawn by the Python
+//! caller, so results do not depend on whether the extension is built.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use crate::dtw::{dtw_distance, dtw_full_path, dtw_path_sakoe_chiba, dtw_sakoe_chiba};
+use crate::pointwise::PointCost;
+use crate::utils::JobPool;
+
+/// Default convergence threshold on the mean absolute barycenter change.
+const DBA_TOL: f64 = 1e-5;
+
+fn dtw_value(a: &[f64], b: &[f64], window: Option<usize>) -> f64 {
+    match window {
+        Some(w) => dtw_sakoe_chiba(a, b, w, PointCost::Squared),
+        None => dtw_distance(a, b, PointCost::Squared),
+    }
+}
+
+fn dtw_path(a: &[f64], b: &[f64], window: Option<usize>) -> Vec<(usize, usize)> {
+    match window {
+        Some(w) => dtw_path_sakoe_chiba(a, b, w, PointCost::Squared),
+        None => dtw_full_path(a, b, PointCost::Squared),
+    }
+}
+
+/// One DBA refinement step: align all members to `centroid` and average.
+fn dba_update(centroid: &[f64], members: &[&[f64]], window: Option<usize>, pool: &JobPool) -> Vec<f64> {
+    let len = centroid.len();
+    let aligned = pool.map(members, |member| {
+        let mut sums = vec![0.0; len];
+        let mut counts = vec![0usize; len];
+        for (ci, si) in dtw_path(centroid, member, window) {
+            sums[ci] += member[si];
+            counts[ci] += 1;
+        }
+        (sums, counts)
+    });
+    let (mut sums, mut counts) = (vec![0.0; len], vec![0usize; len]);
+    for (member_sums, member_counts) in aligned {
+        for i in 0..len {
+            sums[i] += member_sums[i];
+            counts[i] += member_counts[i];
+        }
+    }
+    // Every barycenter point is on every warping path, so counts are non-zero
+    // unless all members are empty; such points become 0 like in Python
+    (0..len)
+        .map(|i| if counts[i] > 0 { sums[i] / counts[i] as f64 } else { 0.0 })
+        .collect()
+}
+
+/// Initial barycenter: the first member when there are at most two, else the
+/// member with the smallest total squared Euclidean distance to the others,
+/// all zero-padded to the longest length (as `_medoid_init` does).
+fn medoid(members: &[&[f64]], pool: &JobPool) -> Vec<f64> {
+    if members.len() <= 2 {
+        return members[0].to_vec();
+    }
+    let max_len = members.iter().map(|m| m.len()).max().unwrap_or(0);
+    let mut padded: Vec<Vec<f64>> = members
+        .iter()
+        .map(|m| {
+            let mut p = m.to_vec();
+            p.resize(max_len, 0.0);
+            p
+        })
+        .collect();
+    let totals: Vec<f64> = pool.map(&padded, |a| {
+        padded
+            .iter()
+            .map(|b| a.iter().zip(b).map(|(x, y)| (y - x) * (y - x)).sum::<f64>())
+            .sum()
+    });
+    let best = totals
+        .iter()
+        .enumerate()
+        .min_by(|x, y| x.1.total_cmp(y.1))
+        .map_or(0, |(i, _)| i);
+    padded.swap_remove(best)
+}
+
+/// DBA of `members` as the Python `dba()` computes it: a single member is
+/// returned as is, otherwise `init` (default: [`medoid`]) is refined.
+fn dba_average(
+    members: &[&[f64]],
+    init: Option<Vec<f64>>,
+    max_iter: usize,
+    window: Option<usize>,
+    tol: f64,
+    pool: &JobPool,
+) -> Vec<f64> {
+    match members {
+        [] => init.unwrap_or_default(),
+        [only] => only.to_vec(),
+        _ => {
+            let init = init.unwrap_or_else(|| medoid(members, pool));
+            dba_barycenter(members, init, max_iter, window, tol, pool)
+        }
+    }
+}
+
+/// Refine `init` into the DBA barycenter of `members`.
+///
+/// Stops after `max_iter` iterations or once the mean absolute change of the
+/// barycenter drops below `tol`.
+pub(crate) fn dba_barycenter(
+    members: &[&[f64]],
+    init: Vec<f64>,
+    max_iter: usize,
+    window: Option<usize>,
+    tol: f64,
+    pool: &JobPool,
+) -> Vec<f64> {
+    let mut centroid = init;
+    if members.is_empty() || centroid.is_empty() {
+        return centroid;
+    }
+    for _ in 0..max_iter {
+        let updated = dba_update(&centroid, members, window, pool);
+        let change = updated.iter().zip(&centroid).map(|(a, b)| (a - b).abs()).sum::<f64>() / centroid.len() as f64;
+        centroid = updated;
+        if change < tol {
+            break;
+        }
+    }
+    centroid
+}
+
+/// Lloyd iterations with DTW assignment and DBA centroid updates.
+///
+/// Initial centroids are the series at `init_indices`, and every update
+/// recomputes each centroid from its members with [`dba_average`]. A cluster
+/// that loses all its members is reset to the series at its entry of
+/// `reset_indices`.
+/// `check_interrupt` runs before every iteration; an error aborts the run.
+/// Returns the cluster of every series and the final centroids.
+#[allow(clippy::too_many_arguments)]
+fn kmeans_dba_loop<C>(
+    series: &[&[f64]],
+    init_indices: &[usize],
+    reset_indices: &[usize],
+    max_iter: usize,
+    dba_max_iter: usize,
+    window: Option<usize>,
+    pool: &JobPool,
+    mut check_interrupt: C,
+) -> PyResult<(Vec<usize>, Vec<Vec<f64>>)>
+where
+    C: FnMut() -> PyResult<()>,
+{
+    let mut centroids: Vec<Vec<f64>> = init_indices.iter().map(|&i| series[i].to_vec()).collect();
+
+    let clusters: Vec<usize> = (0..init_indices.len()).collect();
+    let mut assignments: Vec<usize> = Vec::new();
+    for _ in 0..max_iter {
+        check_interrupt()?;
+        let updated: Vec<usize> = pool.map(series, |s| {
+            centroids
+                .iter()
+                .map(|c| dtw_value(s, c, window))
+                .enumerate()
+                .min_by(|x, y| x.1.total_cmp(&y.1))
+                .map_or(0, |(ci, _)| ci)
+        });
+        if updated == assignments {
+            break;
+        }
+        assignments = updated;
+
+        centroids = pool.map(&clusters, |&ci| {
+            let members: Vec<&[f64]> = series
+                .iter()
+                .zip(&assignments)
+                .filter(|(_, &a)| a == ci)
+                .map(|(s, _)| *s)
+                .collect();
+            if members.is_empty() {
+                series[reset_indices[ci]].to_vec()
+            } else {
+                dba_average(&members, None, dba_max_iter, window, DBA_TOL, pool)
+            }
+        });
+    }
+    Ok((assignments, centroids))
+}
+
+/// DTW Barycenter Averaging of `series_list`.
+///
+/// `init` is the starting barycenter and fixes its length; by default the
+/// medoid of the series is used (see [`medoid`]). `window` is an optional
+/// Sakoe-Chiba band. `n_jobs` limits the worker threads (rayon's global pool
+/// by default; 1 is sequential).
+#[pyfunction]
+#[pyo3(signature = (series_list, init=None, max_iter=30, window=None, tol=DBA_TOL, n_jobs=None))]
+pub fn dba(
+    py: Python<'_>,
+    series_list: Vec<Vec<f64>>,
+    init: Option<Vec<f64>>,
+    max_iter: usize,
+    window: Option<usize>,
+    tol: f64,
+    n_jobs: Option<usize>,
+) -> PyResult<Vec<f64>> {
+    let pool = JobPool::new(n_jobs)?;
+    let members: Vec<&[f64]> = series_list.iter().map(Vec::as_slice).collect();
+    Ok(py.allow_threads(|| dba_average(&members, init, max_iter, window, tol, &pool)))
+}
+
+/// K-means over `series_list` with DTW assignment and DBA centroids.
+///
+/// The number of clusters is `len(init_indices)`: cluster `c` starts from
+/// series `init_indices[c]` and is reset to series `reset_indices[c]` when
+/// it loses all its members. The caller draws both, so the seeding stays
+/// with Python's `random`. The loop runs with the GIL released and checks
+/// for Python signals between iterations. `n_jobs` limits the worker
+/// threads as in [`dba`]. Returns `(labels, centroids)`.
+#[pyfunction]
+#[pyo3(signature = (series_list, init_indices, reset_indices, max_iter=50, dba_max_iter=30, window=None, n_jobs=None))]
+#[allow(clippy::too_many_arguments)]
+pub fn kmeans_dba_fit(
+    py: Python<'_>,
+    series_list: Vec<Vec<f64>>,
+    init_indices: Vec<usize>,
+    reset_indices: Vec<usize>,
+    max_iter: usize,
+    dba_max_iter: usize,
+    window: Option<usize>,
+    n_jobs: Option<usize>,
+) -> PyResult<(Vec<usize>, Vec<Vec<f64>>)> {
+    let k = init_indices.len();
+    if k < 1 {
+        return Err(PyValueError::new_err("init_indices must not be empty"));
+    }
+    if k > series_list.len() {
+        return Err(PyValueError::new_err(format!(
+            "Cannot create {k} clusters from {} time series",
+            series_list.len()
+        )));
+    }
+    if reset_indices.len() != k {
+        return Err(PyValueError::new_err(format!(
+            "reset_indices has {} entries but there are {k} clusters",
+            reset_indices.len()
+        )));
+    }
+    if let Some(&i) = init_indices.iter().chain(&reset_indices).find(|&&i| i >= series_list.len()) {
+        return Err(PyValueError::new_err(format!(
+            "Index {i} is out of range for {} time series",
+            series_list.len()
+        )));
+    }
+    let pool = JobPool::new(n_jobs)?;
+    let series: Vec<&[f64]> = series_list.iter().map(Vec::as_slice).collect();
+    py.allow_threads(|| {
+        kmeans_dba_loop(&series, &init_indices, &reset_indices, max_iter, dba_max_iter, window, &pool, || {
+            Python::with_gil(|py| py.check_signals())
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_members() {
+        let s = [1.0, 2.0, 3.0, 4.0];
+        let result = dba_barycenter(&[&s, &s, &s], vec![0.0, 0.0, 5.0, 5.0], 10, None, DBA_TOL, &JobPool::Global);
+        assert_eq!(result, s.to_vec());
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        // Symmetric members around the init leave it unchanged
+        let s1 = [1.0, 2.0, 3.0];
+        let s2 = [3.0, 4.0, 5.0];
+        let result = dba_barycenter(&[&s1, &s2], vec![2.0, 3.0, 4.0], 10, None, DBA_TOL, &JobPool::Global);
+        for (r, e) in result.iter().zip([2.0, 3.0, 4.0]) {
+            assert!((r - e).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_band_matches_full_for_wide_window() {
+        let s1 = [0.0, 1.0, 3.0, 1.0, 0.0];
+        let s2 = [0.0, 0.0, 1.0, 3.0, 1.0];
+        let full = dba_barycenter(&[&s1, &s2], s1.to_vec(), 10, None, DBA_TOL, &JobPool::Global);
+        let banded = dba_barycenter(&[&s1, &s2], s1.to_vec(), 10, Some(5), DBA_TOL, &JobPool::Global);
+        assert_eq!(full, banded);
+    }
+
+    #[test]
+    fn test_kmeans_separates_groups() {
+        let up1 = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let up2 = [1.1, 2.1, 3.1, 4.1, 5.1];
+        let down1 = [5.0, 4.0, 3.0, 2.0, 1.0];
+        let down2 = [5.1, 4.1, 3.1, 2.1, 1.1];
+        let series: Vec<&[f64]> = vec![&up1, &down1, &up2, &down2];
+        let (labels, centroids) =
+            kmeans_dba_loop(&series, &[0, 1], &[0, 1], 20, 10, None, &JobPool::Sequential, || Ok(())).unwrap();
+        assert_eq!(centroids.len(), 2);
+        assert_eq!(labels[0], labels[2]);
+        assert_eq!(labels[1], labels[3]);
+        assert_ne!(labels[0], labels[1]);
+    }
+}
@@ -1,10 +1,13 @@
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
 
-use crate::utils::compute_pairwise;
+use crate::dtw::{dtw_distance, dtw_full_path, path_mean_cost};
+use crate::pointwise::PointCost;
+use crate::utils::{compute_pairwise, PairwiseOptions};
 
 /// Compute the derivative of a time series using the method from Keogh & Pazzani (2001).
-fn compute_derivative(q: &[f64]) -> Vec<f64> {
+pub(crate) fn compute_derivative(q: &[f64]) -> Vec<f64> {
     if q.len() < 3 {
         return Vec::new();
     }
@@ -17,39 +20,33 @@ fn compute_derivative(q: &[f64]) -> Vec<f64> {
     derivative
 }
 
-/// DTW distance using O(m) memory (two-row approach).
-fn dtw_distance(a: &[f64], b: &[f64]) -> f64 {
-    let n = a.len();
-    let m = b.len();
-    if n == 0 || m == 0 {
-        return f64::INFINITY;
-    }
-    let mut prev = vec![f64::MAX; m + 1];
-    let mut curr = vec![f64::MAX; m + 1];
-    prev[0] = 0.0;
-    for i in 1..=n {
-        curr[0] = f64::MAX;
-        for j in 1..=m {
-            let cost = (a[i - 1] - b[j - 1]).abs();
-            let min_prev = prev[j].min(curr[j - 1]).min(prev[j - 1]);
-            curr[j] = cost + min_prev;
-        }
-        std::mem::swap(&mut prev, &mut curr);
-    }
-    prev[m]
-}
-
 /// DDTW distance: derivative of each series, then DTW on derivatives.
-fn ddtw_distance(a: &[f64], b: &[f64]) -> f64 {
+fn ddtw_distance(a: &[f64], b: &[f64], cost: PointCost, normalize_path: bool) -> f64 {
     let a_d = compute_derivative(a);
     let b_d = compute_derivative(b);
     if a_d.is_empty() || b_d.is_empty() {
         return f64::INFINITY;
     }
-    dtw_distance(&a_d, &b_d)
+    if normalize_path {
+        return path_mean_cost(&a_d, &b_d, &dtw_full_path(&a_d, &b_d, cost), cost);
+    }
+    dtw_distance(&a_d, &b_d, cost)
 }
 
+/// Pairwise DDTW distances. `cost` and `normalize_path` behave as in
+/// `compute_pairwise_dtw`.
 #[pyfunction]
-pub fn compute_pairwise_ddtw(input1: PyDataFrame, input2: PyDataFrame) -> PyResult<PyDataFrame> {
-    compute_pairwise(input1, input2, "ddtw", ddtw_distance)
+#[pyo3(signature = (input1, input2, cost=None, normalize_path=false, **options))]
+pub fn compute_pairwise_ddtw(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    cost: Option<&str>,
+    normalize_path: bool,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let cost = PointCost::parse(cost, PointCost::Abs)?;
+    compute_pairwise(input1, input2, "ddtw", &options, move |a, b| {
+        ddtw_distance(a, b, cost, normalize_path)
+    })
 }
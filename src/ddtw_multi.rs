@@ -0,0 +1,48 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_polars::PyDataFrame;
+
+use crate::dtw_multi::dtw_distance_multivariate;
+use crate::pointwise::PointMetric;
+use crate::utils::{compute_pairwise_multivariate, PairwiseOptions};
+
+/// Keogh & Pazzani (2001) derivative of every dimension of a multivariate series.
+fn compute_derivative(q: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    if q.len() < 3 {
+        return Vec::new();
+    }
+    (1..q.len() - 1)
+        .map(|i| {
+            q[i].iter()
+                .zip(&q[i - 1])
+                .zip(&q[i + 1])
+                .map(|((&x, &before), &after)| ((x - before) + (after - before) / 2.0) / 2.0)
+                .collect()
+        })
+        .collect()
+}
+
+/// Multivariate DDTW: derivative of each dimension, then multivariate DTW.
+fn ddtw_distance(a: &[Vec<f64>], b: &[Vec<f64>], metric: PointMetric) -> f64 {
+    let a_d = compute_derivative(a);
+    let b_d = compute_derivative(b);
+    if a_d.is_empty() || b_d.is_empty() {
+        return f64::INFINITY;
+    }
+    dtw_distance_multivariate(&a_d, &b_d, metric)
+}
+
+#[pyfunction]
+#[pyo3(signature = (input1, input2, metric=None, **options))]
+pub fn compute_pairwise_ddtw_multi(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    metric: Option<String>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let metric = PointMetric::parse(metric.as_deref(), PointMetric::Manhattan)?;
+    compute_pairwise_multivariate(input1, input2, "ddtw_multi", &options, move |a, b| {
+        ddtw_distance(a, b, metric)
+    })
+}
@@ -1,15 +1,23 @@
+use polars::prelude::*;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
-use std::collections::HashSet;
+use ordered_float::OrderedFloat;
+use std::collections::{BinaryHeap, HashSet, VecDeque};
 
-use crate::utils::compute_pairwise;
+use crate::pointwise::PointCost;
+use crate::utils::{
+    build_knn_output_df, cast_id_columns, compute_pairwise, load_univariate, par_map_interruptible, prepare_univariate,
+    PairwiseOptions,
+};
 
 // ---------------------------------------------------------------------------
 // DTW distance kernels
 // ---------------------------------------------------------------------------
 
 /// Standard unconstrained DTW using O(m) memory (two-row approach).
-fn dtw_distance(a: &[f64], b: &[f64]) -> f64 {
+pub(crate) fn dtw_distance(a: &[f64], b: &[f64], cost: PointCost) -> f64 {
     let n = a.len();
     let m = b.len();
     let mut prev = vec![f64::MAX; m + 1];
@@ -19,9 +27,8 @@ fn dtw_distance(a: &[f64], b: &[f64]) -> f64 {
     for i in 1..=n {
         curr[0] = f64::MAX;
         for j in 1..=m {
-            let cost = (a[i - 1] - b[j - 1]).abs();
             let min_prev = prev[j].min(curr[j - 1]).min(prev[j - 1]);
-            curr[j] = cost + min_prev;
+            curr[j] = cost.cost(a[i - 1], b[j - 1]) + min_prev;
         }
         std::mem::swap(&mut prev, &mut curr);
     }
@@ -29,7 +36,14 @@ fn dtw_distance(a: &[f64], b: &[f64]) -> f64 {
 }
 
 /// DTW with Sakoe-Chiba band constraint.
-fn dtw_sakoe_chiba(a: &[f64], b: &[f64], window: usize) -> f64 {
+pub(crate) fn dtw_sakoe_chiba(a: &[f64], b: &[f64], window: usize, cost: PointCost) -> f64 {
+    dtw_sakoe_chiba_abandon(a, b, window, cost, f64::INFINITY)
+}
+
+/// Sakoe-Chiba DTW with early abandoning: as soon as every cell of a row
+/// exceeds `best_so_far` the final distance must too, so `f64::INFINITY`
+/// is returned without finishing the DP.
+fn dtw_sakoe_chiba_abandon(a: &[f64], b: &[f64], window: usize, cost: PointCost, best_so_far: f64) -> f64 {
     let n = a.len();
     let m = b.len();
     let w = window.max(n.abs_diff(m));
@@ -44,40 +58,46 @@ fn dtw_sakoe_chiba(a: &[f64], b: &[f64], window: usize) -> f64 {
         if j_start > 1 {
             curr[j_start - 1] = f64::MAX;
         }
+        let mut row_min = f64::MAX;
         for j in j_start..=j_end {
-            let cost = (a[i - 1] - b[j - 1]).abs();
             let min_prev = prev[j].min(curr[j - 1]).min(prev[j - 1]);
-            curr[j] = cost + min_prev;
+            curr[j] = cost.cost(a[i - 1], b[j - 1]) + min_prev;
+            row_min = row_min.min(curr[j]);
+        }
+        if row_min > best_so_far {
+            return f64::INFINITY;
         }
         std::mem::swap(&mut prev, &mut curr);
     }
     prev[m]
 }
 
+/// Whether cell (i, j) (1-based) lies inside the Itakura parallelogram.
+#[inline]
+fn in_itakura_band(i: usize, j: usize, n: usize, m: usize, max_slope: f64) -> bool {
+    let (fi, fj) = (i as f64, j as f64);
+    let (nf, mf) = (n as f64, m as f64);
+    let lower = (fi / max_slope).max(mf - (nf - fi) * max_slope);
+    let upper = (fi * max_slope).min(mf - (nf - fi) / max_slope);
+    fj >= lower && fj <= upper
+}
+
 /// DTW with Itakura parallelogram constraint.
-fn dtw_itakura(a: &[f64], b: &[f64], max_slope: f64) -> f64 {
+fn dtw_itakura(a: &[f64], b: &[f64], max_slope: f64, cost: PointCost) -> f64 {
     let n = a.len();
     let m = b.len();
     let mut prev = vec![f64::MAX; m + 1];
     let mut curr = vec![f64::MAX; m + 1];
     prev[0] = 0.0;
 
-    let nf = n as f64;
-    let mf = m as f64;
-
     for i in 1..=n {
         curr[0] = f64::MAX;
         for j in 1..=m {
-            let fi = i as f64;
-            let fj = j as f64;
-            let lower = (fi / max_slope).max(mf - (nf - fi) * max_slope);
-            let upper = (fi * max_slope).min(mf - (nf - fi) / max_slope);
-            if fj < lower || fj > upper {
+            if !in_itakura_band(i, j, n, m, max_slope) {
                 curr[j] = f64::MAX;
             } else {
-                let cost = (a[i - 1] - b[j - 1]).abs();
                 let min_prev = prev[j].min(curr[j - 1]).min(prev[j - 1]);
-                curr[j] = cost + min_prev;
+                curr[j] = cost.cost(a[i - 1], b[j - 1]) + min_prev;
             }
         }
         std::mem::swap(&mut prev, &mut curr);
@@ -101,15 +121,15 @@ fn reduce_by_half(x: &[f64]) -> Vec<f64> {
 
 /// FastDTW: approximate DTW in O(N) time using multi-resolution coarsening.
 /// Uses HashSet for O(path_len * radius^2) memory instead of O(n*m).
-fn fast_dtw(a: &[f64], b: &[f64], radius: usize) -> f64 {
+fn fast_dtw(a: &[f64], b: &[f64], radius: usize, cost: PointCost) -> f64 {
     let min_size = radius + 2;
     if a.len() <= min_size || b.len() <= min_size {
-        return dtw_distance(a, b);
+        return dtw_distance(a, b, cost);
     }
 
     let a_shrunk = reduce_by_half(a);
     let b_shrunk = reduce_by_half(b);
-    let path = fast_dtw_path(&a_shrunk, &b_shrunk, radius);
+    let path = fast_dtw_path(&a_shrunk, &b_shrunk, radius, cost);
 
     let n = a.len();
     let m = b.len();
@@ -134,41 +154,25 @@ fn fast_dtw(a: &[f64], b: &[f64], radius: usize) -> f64 {
         }
     }
 
-    dtw_with_window(a, b, &window)
+    dtw_with_window(a, b, &window, cost)
 }
 
 /// Compute DTW restricted to a HashSet window mask.
-fn dtw_with_window(a: &[f64], b: &[f64], window: &HashSet<(usize, usize)>) -> f64 {
-    let n = a.len();
-    let m = b.len();
-    let mut cost_matrix = vec![vec![f64::MAX; m + 1]; n + 1];
-    cost_matrix[0][0] = 0.0;
-
-    for i in 1..=n {
-        for j in 1..=m {
-            if !window.contains(&(i - 1, j - 1)) {
-                continue;
-            }
-            let cost = (a[i - 1] - b[j - 1]).abs();
-            let min_prev = cost_matrix[i - 1][j]
-                .min(cost_matrix[i][j - 1])
-                .min(cost_matrix[i - 1][j - 1]);
-            cost_matrix[i][j] = cost + min_prev;
-        }
-    }
-    cost_matrix[n][m]
+fn dtw_with_window(a: &[f64], b: &[f64], window: &HashSet<(usize, usize)>, cost: PointCost) -> f64 {
+    let cost_matrix = dtw_cost_matrix(a, b, cost, |i, j| window.contains(&(i, j)));
+    cost_matrix[a.len()][b.len()]
 }
 
 /// FastDTW helper that returns the warping path (used for recursive projection).
-fn fast_dtw_path(a: &[f64], b: &[f64], radius: usize) -> Vec<(usize, usize)> {
+fn fast_dtw_path(a: &[f64], b: &[f64], radius: usize, cost: PointCost) -> Vec<(usize, usize)> {
     let min_size = radius + 2;
     if a.len() <= min_size || b.len() <= min_size {
-        return dtw_full_path(a, b);
+        return dtw_full_path(a, b, cost);
     }
 
     let a_shrunk = reduce_by_half(a);
     let b_shrunk = reduce_by_half(b);
-    let path = fast_dtw_path(&a_shrunk, &b_shrunk, radius);
+    let path = fast_dtw_path(&a_shrunk, &b_shrunk, radius, cost);
 
     let n = a.len();
     let m = b.len();
@@ -193,11 +197,15 @@ fn fast_dtw_path(a: &[f64], b: &[f64], radius: usize) -> Vec<(usize, usize)> {
         }
     }
 
-    dtw_path_with_window(a, b, &window)
+    dtw_path_with_window(a, b, &window, cost)
 }
 
-/// Compute the full DTW cost matrix and extract the optimal warping path.
-fn dtw_full_path(a: &[f64], b: &[f64]) -> Vec<(usize, usize)> {
+/// Fill the (n+1)×(m+1) accumulated cost matrix, visiting only the cells
+/// (0-based) for which `allowed(i, j)` holds. Other cells stay at `f64::MAX`.
+fn dtw_cost_matrix<F>(a: &[f64], b: &[f64], cost: PointCost, allowed: F) -> Vec<Vec<f64>>
+where
+    F: Fn(usize, usize) -> bool,
+{
     let n = a.len();
     let m = b.len();
     let mut cost_matrix = vec![vec![f64::MAX; m + 1]; n + 1];
@@ -205,17 +213,24 @@ fn dtw_full_path(a: &[f64], b: &[f64]) -> Vec<(usize, usize)> {
 
     for i in 1..=n {
         for j in 1..=m {
-            let cost = (a[i - 1] - b[j - 1]).abs();
+            if !allowed(i - 1, j - 1) {
+                continue;
+            }
             let min_prev = cost_matrix[i - 1][j]
                 .min(cost_matrix[i][j - 1])
                 .min(cost_matrix[i - 1][j - 1]);
-            cost_matrix[i][j] = cost + min_prev;
+            cost_matrix[i][j] = cost.cost(a[i - 1], b[j - 1]) + min_prev;
         }
     }
+    cost_matrix
+}
 
+/// Walk an accumulated cost matrix back from (n, m) and return the optimal
+/// warping path as 0-based (i, j) index pairs in increasing order.
+pub(crate) fn backtrack_path(cost_matrix: &[Vec<f64>]) -> Vec<(usize, usize)> {
     let mut path = Vec::new();
-    let mut i = n;
-    let mut j = m;
+    let mut i = cost_matrix.len() - 1;
+    let mut j = cost_matrix[0].len() - 1;
     while i > 0 && j > 0 {
         path.push((i - 1, j - 1));
         let diag = cost_matrix[i - 1][j - 1];
@@ -234,75 +249,79 @@ fn dtw_full_path(a: &[f64], b: &[f64]) -> Vec<(usize, usize)> {
     path
 }
 
+/// Compute the full DTW cost matrix and extract the optimal warping path.
+pub(crate) fn dtw_full_path(a: &[f64], b: &[f64], cost: PointCost) -> Vec<(usize, usize)> {
+    backtrack_path(&dtw_cost_matrix(a, b, cost, |_, _| true))
+}
+
 /// Compute DTW path restricted to a HashSet window mask.
-fn dtw_path_with_window(a: &[f64], b: &[f64], window: &HashSet<(usize, usize)>) -> Vec<(usize, usize)> {
-    let n = a.len();
-    let m = b.len();
-    let mut cost_matrix = vec![vec![f64::MAX; m + 1]; n + 1];
-    cost_matrix[0][0] = 0.0;
+fn dtw_path_with_window(
+    a: &[f64],
+    b: &[f64],
+    window: &HashSet<(usize, usize)>,
+    cost: PointCost,
+) -> Vec<(usize, usize)> {
+    backtrack_path(&dtw_cost_matrix(a, b, cost, |i, j| window.contains(&(i, j))))
+}
 
-    for i in 1..=n {
-        for j in 1..=m {
-            if !window.contains(&(i - 1, j - 1)) {
-                continue;
-            }
-            let cost = (a[i - 1] - b[j - 1]).abs();
-            let min_prev = cost_matrix[i - 1][j]
-                .min(cost_matrix[i][j - 1])
-                .min(cost_matrix[i - 1][j - 1]);
-            cost_matrix[i][j] = cost + min_prev;
-        }
-    }
+/// DTW path under a Sakoe-Chiba band (same band width rule as `dtw_sakoe_chiba`).
+pub(crate) fn dtw_path_sakoe_chiba(a: &[f64], b: &[f64], window: usize, cost: PointCost) -> Vec<(usize, usize)> {
+    let w = window.max(a.len().abs_diff(b.len()));
+    backtrack_path(&dtw_cost_matrix(a, b, cost, |i, j| i.abs_diff(j) <= w))
+}
 
-    let mut path = Vec::new();
-    let mut i = n;
-    let mut j = m;
-    while i > 0 && j > 0 {
-        path.push((i - 1, j - 1));
-        let diag = cost_matrix[i - 1][j - 1];
-        let left = cost_matrix[i][j - 1];
-        let up = cost_matrix[i - 1][j];
-        if diag <= left && diag <= up {
-            i -= 1;
-            j -= 1;
-        } else if up <= left {
-            i -= 1;
-        } else {
-            j -= 1;
-        }
+/// DTW path under an Itakura parallelogram constraint.
+fn dtw_path_itakura(a: &[f64], b: &[f64], max_slope: f64, cost: PointCost) -> Vec<(usize, usize)> {
+    let (n, m) = (a.len(), b.len());
+    backtrack_path(&dtw_cost_matrix(a, b, cost, |i, j| {
+        in_itakura_band(i + 1, j + 1, n, m, max_slope)
+    }))
+}
+
+/// Accumulated cost along `path` divided by the number of steps on it.
+pub(crate) fn path_mean_cost(a: &[f64], b: &[f64], path: &[(usize, usize)], cost: PointCost) -> f64 {
+    if path.is_empty() {
+        return f64::INFINITY;
     }
-    path.reverse();
-    path
+    let total: f64 = path.iter().map(|&(i, j)| cost.cost(a[i], b[j])).sum();
+    total / path.len() as f64
 }
 
 /// Dispatch to the appropriate DTW variant.
-fn compute_dtw(a: &[f64], b: &[f64], method: &str, param: f64) -> f64 {
+///
+/// With `normalize_path` the distance is divided by the length of the
+/// warping path, which needs the full cost matrix.
+fn compute_dtw(a: &[f64], b: &[f64], method: &str, param: f64, cost: PointCost, normalize_path: bool) -> f64 {
+    if normalize_path {
+        return path_mean_cost(a, b, &compute_dtw_path(a, b, method, param, cost), cost);
+    }
     match method {
-        "standard" => dtw_distance(a, b),
-        "sakoe_chiba" => dtw_sakoe_chiba(a, b, param as usize),
-        "itakura" => dtw_itakura(a, b, param),
-        "fast" => fast_dtw(a, b, param as usize),
-        _ => dtw_distance(a, b),
+        "standard" => dtw_distance(a, b, cost),
+        "sakoe_chiba" => dtw_sakoe_chiba(a, b, param as usize, cost),
+        "itakura" => dtw_itakura(a, b, param, cost),
+        "fast" => fast_dtw(a, b, param as usize, cost),
+        _ => dtw_distance(a, b, cost),
     }
 }
 
-// ---------------------------------------------------------------------------
-// Pairwise wrapper
-// ---------------------------------------------------------------------------
+/// Dispatch to the warping-path extractor matching a DTW variant.
+fn compute_dtw_path(a: &[f64], b: &[f64], method: &str, param: f64, cost: PointCost) -> Vec<(usize, usize)> {
+    match method {
+        "standard" => dtw_full_path(a, b, cost),
+        "sakoe_chiba" => dtw_path_sakoe_chiba(a, b, param as usize, cost),
+        "itakura" => dtw_path_itakura(a, b, param, cost),
+        "fast" => fast_dtw_path(a, b, param as usize, cost),
+        _ => dtw_full_path(a, b, cost),
+    }
+}
 
-#[pyfunction]
-#[pyo3(signature = (input1, input2, method=None, param=None))]
-pub fn compute_pairwise_dtw(
-    input1: PyDataFrame,
-    input2: PyDataFrame,
-    method: Option<&str>,
-    param: Option<f64>,
-) -> PyResult<PyDataFrame> {
+/// Validate the DTW method name and fill in its default parameter.
+fn resolve_method(method: Option<&str>, param: Option<f64>) -> PyResult<(String, f64)> {
     let method = method.unwrap_or("standard");
     match method {
         "standard" | "sakoe_chiba" | "itakura" | "fast" => {}
         _ => {
-            return Err(pyo3::exceptions::PyValueError::new_err(
+            return Err(PyValueError::new_err(
                 format!("Unknown DTW method: '{}'. Expected one of: standard, sakoe_chiba, itakura, fast", method)
             ));
         }
@@ -313,9 +332,659 @@ pub fn compute_pairwise_dtw(
         "fast" => 5.0,
         _ => 0.0,
     });
+    Ok((method.to_string(), param))
+}
+
+// ---------------------------------------------------------------------------
+// Lower bounds and nearest-neighbour search
+// ---------------------------------------------------------------------------
+
+/// Upper and lower envelopes of `x` over a sliding window of ±`w` samples,
+/// computed in O(n) with Lemire's streaming min/max deques.
+fn envelope(x: &[f64], w: usize) -> (Vec<f64>, Vec<f64>) {
+    let n = x.len();
+    let mut upper = vec![0.0; n];
+    let mut lower = vec![0.0; n];
+    let mut max_q: VecDeque<usize> = VecDeque::new();
+    let mut min_q: VecDeque<usize> = VecDeque::new();
+
+    for i in 0..n + w {
+        if i < n {
+            while max_q.back().is_some_and(|&k| x[k] <= x[i]) {
+                max_q.pop_back();
+            }
+            max_q.push_back(i);
+            while min_q.back().is_some_and(|&k| x[k] >= x[i]) {
+                min_q.pop_back();
+            }
+            min_q.push_back(i);
+        }
+        if i >= w {
+            let p = i - w;
+            let lo = p.saturating_sub(w);
+            while max_q.front().is_some_and(|&k| k < lo) {
+                max_q.pop_front();
+            }
+            while min_q.front().is_some_and(|&k| k < lo) {
+                min_q.pop_front();
+            }
+            upper[p] = x[max_q[0]];
+            lower[p] = x[min_q[0]];
+        }
+    }
+    (upper, lower)
+}
+
+/// LB_Kim (first/last point variant): every warping path visits the first
+/// and the last cell, so their costs bound the distance from below.
+fn lb_kim(a: &[f64], b: &[f64]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let first = (a[0] - b[0]).abs();
+    if a.len() == 1 && b.len() == 1 {
+        return first;
+    }
+    first + (a[a.len() - 1] - b[b.len() - 1]).abs()
+}
+
+/// LB_Keogh: distance from `x` to the envelope of the other series.
+/// Only valid for equal-length series under a Sakoe-Chiba band of the envelope's width.
+fn lb_keogh(x: &[f64], upper: &[f64], lower: &[f64]) -> f64 {
+    x.iter()
+        .zip(upper.iter().zip(lower))
+        .map(|(&v, (&u, &l))| {
+            if v > u {
+                v - u
+            } else if v < l {
+                l - v
+            } else {
+                0.0
+            }
+        })
+        .sum()
+}
+
+/// LB_Improved (Lemire, 2009): LB_Keogh of `x` against the envelope of `q`,
+/// plus LB_Keogh of `q` against the envelope of `x` projected onto that envelope.
+/// Returns the first term alone once it already exceeds `best_so_far`.
+fn lb_improved(x: &[f64], q: &[f64], upper: &[f64], lower: &[f64], w: usize, best_so_far: f64) -> f64 {
+    let first = lb_keogh(x, upper, lower);
+    if first >= best_so_far {
+        return first;
+    }
+    let projection: Vec<f64> = x
+        .iter()
+        .zip(upper.iter().zip(lower))
+        .map(|(&v, (&u, &l))| v.clamp(l, u))
+        .collect();
+    let (proj_upper, proj_lower) = envelope(&projection, w);
+    first + lb_keogh(q, &proj_upper, &proj_lower)
+}
+
+/// Lower bounds applied, in order, before falling back to the full DP.
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
+enum LowerBound {
+    None,
+    Kim,
+    Keogh,
+    Improved,
+}
+
+impl LowerBound {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "none" => Ok(LowerBound::None),
+            "kim" => Ok(LowerBound::Kim),
+            "keogh" => Ok(LowerBound::Keogh),
+            "improved" => Ok(LowerBound::Improved),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown lower bound: '{name}'. Expected one of: none, kim, keogh, improved"
+            ))),
+        }
+    }
+}
+
+/// Find the `k` nearest `references` to `query` under Sakoe-Chiba DTW.
+///
+/// Candidates are ranked by their cheap bounds (LB_Kim, then LB_Keogh when
+/// lengths match) and visited in that order; the scan stops once a bound
+/// reaches the current k-th best distance. Survivors are checked against
+/// LB_Improved and finally run through the early-abandoning DP.
+///
+/// Returns `(reference_index, distance)` sorted by ascending distance. Ties
+/// go to the reference that comes first in `references`, whatever order the
+/// bounds visit them in: candidates compete on `(distance, index)`, so a
+/// later reference must be strictly closer to displace an earlier one.
+fn knn_dtw_single(
+    query: &[f64],
+    references: &[&[f64]],
+    k: usize,
+    window: Option<usize>,
+    bound: LowerBound,
+) -> Vec<(usize, f64)> {
+    let n = query.len();
+    let query_window = window.unwrap_or(n);
+    let query_env = (bound >= LowerBound::Keogh).then(|| envelope(query, query_window));
+
+    let mut candidates: Vec<(f64, usize)> = references
+        .iter()
+        .enumerate()
+        .map(|(idx, candidate)| {
+            let mut lb = 0.0;
+            if bound >= LowerBound::Kim {
+                lb = lb_kim(query, candidate);
+            }
+            if let Some((upper, lower)) = &query_env {
+                if candidate.len() == n {
+                    lb = lb.max(lb_keogh(candidate, upper, lower));
+                }
+            }
+            (lb, idx)
+        })
+        .collect();
+    candidates.sort_by(|x, y| x.0.total_cmp(&y.0).then(x.1.cmp(&y.1)));
+
+    let mut heap: BinaryHeap<(OrderedFloat<f64>, usize)> = BinaryHeap::with_capacity(k + 1);
+    for (lb, idx) in candidates {
+        // The current k-th best `(distance, index)`; a candidate must sort
+        // strictly before it to enter the heap.
+        let worst = if heap.len() < k {
+            None
+        } else {
+            heap.peek().map(|top| (top.0.into_inner(), top.1))
+        };
+        let best_so_far = worst.map_or(f64::INFINITY, |(d, _)| d);
+        let beats = |d: f64| worst.is_none_or(|(wd, widx)| d < wd || (d == wd && idx < widx));
+        if !beats(lb) {
+            break;
+        }
+        let candidate = references[idx];
+        let band = window.unwrap_or(n.max(candidate.len()));
+        if bound == LowerBound::Improved && candidate.len() == n {
+            if let Some((upper, lower)) = &query_env {
+                if !beats(lb_improved(candidate, query, upper, lower, band, best_so_far)) {
+                    continue;
+                }
+            }
+        }
+        let d = dtw_sakoe_chiba_abandon(query, candidate, band, PointCost::Abs, best_so_far);
+        if beats(d) {
+            heap.push((OrderedFloat(d), idx));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+    }
+
+    let mut neighbours: Vec<(usize, f64)> = heap
+        .into_iter()
+        .map(|(d, idx)| (idx, d.into_inner()))
+        .collect();
+    neighbours.sort_by(|x, y| x.1.total_cmp(&y.1).then(x.0.cmp(&y.0)));
+    neighbours
+}
 
-    let method_owned = method.to_string();
-    compute_pairwise(input1, input2, "dtw", move |a, b| {
-        compute_dtw(a, b, &method_owned, param)
+// ---------------------------------------------------------------------------
+// Subsequence search (SPRING)
+// ---------------------------------------------------------------------------
+
+/// Best open-begin, open-end DTW match of `query` ending at every position of `series`.
+///
+/// Streams over `series` one column at a time (SPRING, Sakurai et al. 2007),
+/// carrying for each query position the accumulated cost and the series
+/// position where the match started, so memory is O(len(query)). With a
+/// `window`, a cell is only reachable when the match stays within `window`
+/// steps of the diagonal that starts at its own start position.
+///
+/// Returns `(start, distance)` per end position; `distance` is infinite
+/// when no admissible match ends there.
+fn spring_matches(query: &[f64], series: &[f64], window: Option<usize>) -> Vec<(usize, f64)> {
+    let n = query.len();
+    let in_band = |i: usize, j: usize, start: usize| window.is_none_or(|w| (j - start).abs_diff(i) <= w);
+    // Columns j - 1 and j as (cost, start) per query position; index 0 stands
+    // for the virtual row where every match may begin
+    let mut prev: Vec<(f64, usize)> = vec![(f64::INFINITY, 0); n + 1];
+    let mut curr = prev.clone();
+    let mut ends = Vec::with_capacity(series.len());
+
+    for (j, &x) in series.iter().enumerate() {
+        for i in 1..=n {
+            // A predecessor in row 0 starts the match at the current position
+            let from_start = |cell: (f64, usize)| if i == 1 { (0.0, j) } else { cell };
+            let candidates = [from_start(prev[i - 1]), from_start(curr[i - 1]), prev[i]];
+            let best = candidates
+                .into_iter()
+                .filter(|&(d, start)| d.is_finite() && in_band(i - 1, j, start))
+                .min_by(|x, y| x.0.total_cmp(&y.0).then(y.1.cmp(&x.1)));
+            curr[i] = match best {
+                Some((d, start)) => (d + (query[i - 1] - x).abs(), start),
+                None => (f64::INFINITY, 0),
+            };
+        }
+        ends.push((curr[n].1, curr[n].0));
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    ends
+}
+
+/// Pick the `k` best matches as `(start, end, distance)` in ascending distance.
+///
+/// Candidates are the best match ending at each position; unless
+/// `allow_overlap`, a candidate is skipped when it shares any position with
+/// a match already picked.
+fn top_subsequence_matches(ends: &[(usize, f64)], k: usize, allow_overlap: bool) -> Vec<(usize, usize, f64)> {
+    let mut candidates: Vec<(usize, usize, f64)> = ends
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, d))| d.is_finite())
+        .map(|(end, &(start, d))| (start, end, d))
+        .collect();
+    candidates.sort_by(|x, y| x.2.total_cmp(&y.2).then(x.1.cmp(&y.1)));
+
+    let mut picked: Vec<(usize, usize, f64)> = Vec::with_capacity(k);
+    for candidate in candidates {
+        if picked.len() == k {
+            break;
+        }
+        let overlaps = picked.iter().any(|&(s, e, _)| candidate.0 <= e && s <= candidate.1);
+        if allow_overlap || !overlaps {
+            picked.push(candidate);
+        }
+    }
+    picked
+}
+
+// ---------------------------------------------------------------------------
+// Pairwise wrappers
+// ---------------------------------------------------------------------------
+
+/// Pairwise DTW distances.
+///
+/// `cost` is the local cost between two observations: `"abs"` (default),
+/// `"squared"` or `"huber(delta)"`. With `normalize_path` each distance is
+/// divided by the length of its warping path.
+#[pyfunction]
+#[pyo3(signature = (input1, input2, method=None, param=None, cost=None, normalize_path=false, **options))]
+pub fn compute_pairwise_dtw(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    method: Option<&str>,
+    param: Option<f64>,
+    cost: Option<&str>,
+    normalize_path: bool,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let (method_owned, param) = resolve_method(method, param)?;
+    let cost = PointCost::parse(cost, PointCost::Abs)?;
+    compute_pairwise(input1, input2, "dtw", &options, move |a, b| {
+        compute_dtw(a, b, &method_owned, param, cost, normalize_path)
     })
 }
+
+/// Compute the DTW warping path for every (id_1, id_2) pair.
+///
+/// Returns a long DataFrame with one row per aligned index pair:
+/// `id_1`, `id_2`, `idx_1` (position in the first series) and `idx_2`
+/// (position in the second series), ordered along the path.
+/// `cost` is the local cost, as in `compute_pairwise_dtw`.
+/// Accepts the input-related pairwise options (`id_col`, `target_col`, ...).
+#[pyfunction]
+#[pyo3(signature = (input1, input2, method=None, param=None, cost=None, **options))]
+pub fn compute_dtw_alignment(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    method: Option<&str>,
+    param: Option<f64>,
+    cost: Option<&str>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    options.ensure_default_output("compute_dtw_alignment")?;
+    let (method, param) = resolve_method(method, param)?;
+    let cost = PointCost::parse(cost, PointCost::Abs)?;
+    let input = prepare_univariate(input1, input2, &options)?;
+
+    let paths: Vec<_> = par_map_interruptible(
+        &options.job_pool()?,
+        &input.pairs(options.mode),
+        |&(left_key, left_series, right_key, right_series)| {
+            let path = compute_dtw_path(left_series, right_series, &method, param, cost);
+            (left_key, right_key, path)
+        },
+    )?;
+
+    let total: usize = paths.iter().map(|(_, _, p)| p.len()).sum();
+    let mut id1s: Vec<&str> = Vec::with_capacity(total);
+    let mut id2s: Vec<&str> = Vec::with_capacity(total);
+    let mut idx1s: Vec<i64> = Vec::with_capacity(total);
+    let mut idx2s: Vec<i64> = Vec::with_capacity(total);
+    for (left_key, right_key, path) in &paths {
+        for &(i, j) in path {
+            id1s.push(left_key.as_str());
+            id2s.push(right_key.as_str());
+            idx1s.push(i as i64);
+            idx2s.push(j as i64);
+        }
+    }
+
+    let out_df = DataFrame::new(vec![
+        Column::new("id_1".into(), id1s),
+        Column::new("id_2".into(), id2s),
+        Column::new("idx_1".into(), idx1s),
+        Column::new("idx_2".into(), idx2s),
+    ])
+    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+    cast_id_columns(out_df, &input.uid_a_dtype, &input.uid_b_dtype).map(PyDataFrame)
+}
+
+/// Exact k-nearest-neighbour search under DTW with lower-bound pruning.
+///
+/// For every series in `input1`, returns its `k` closest series in `input2`
+/// (excluding an identical id unless `mode="cross_full"`) as `id_1`, `id_2`,
+/// `rank` (1-based) and `dtw`.
+/// `window` is the Sakoe-Chiba band (unconstrained when omitted) and
+/// `lower_bound` selects how far the pruning cascade goes:
+/// `none`, `kim`, `keogh` or `improved` (default). Equidistant neighbours
+/// are ranked by ascending `id_2`, independently of the pruning order.
+/// Accepts the input-related pairwise options (`id_col`, `target_col`, ...).
+#[pyfunction]
+#[pyo3(signature = (input1, input2, k=1, window=None, lower_bound="improved", **options))]
+pub fn compute_knn_dtw(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    k: usize,
+    window: Option<usize>,
+    lower_bound: &str,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    if k < 1 {
+        return Err(PyValueError::new_err("k must be >= 1"));
+    }
+    let options = PairwiseOptions::from_kwargs(options)?;
+    options.ensure_default_output("compute_knn_dtw")?;
+    let bound = LowerBound::parse(lower_bound)?;
+    let input = prepare_univariate(input1, input2, &options)?;
+
+    let queries = input.series_a();
+    let (reference_ids, reference_series): (Vec<&String>, Vec<&[f64]>) = input
+        .series_b()
+        .into_iter()
+        .map(|(key, series)| (key, series.as_slice()))
+        .unzip();
+
+    let per_query = par_map_interruptible(&options.job_pool()?, &queries, |&(query_key, query)| {
+        let (ids, series): (Vec<&String>, Vec<&[f64]>) = reference_ids
+            .iter()
+            .zip(&reference_series)
+            .filter(|(key, _)| options.mode.keeps_same_id() || **key != query_key)
+            .map(|(key, s)| (*key, *s))
+            .unzip();
+        knn_dtw_single(query, &series, k, window, bound)
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (idx, d))| (query_key.clone(), ids[idx].clone(), rank as i64 + 1, d))
+            .collect::<Vec<_>>()
+    })?;
+    let results: Vec<(String, String, i64, f64)> = per_query.into_iter().flatten().collect();
+
+    build_knn_output_df(&results, "dtw", &input.uid_a_dtype, &input.uid_b_dtype)
+}
+
+/// Find where `query` occurs inside every series of `df` with subsequence DTW.
+///
+/// The match may start and end anywhere in the series (open-begin,
+/// open-end). Returns the `k` best matches per series as the id column,
+/// `start_idx`, `end_idx` (both inclusive) and `distance`, ordered by id and
+/// then ascending distance. `window` is a Sakoe-Chiba band around the
+/// diagonal of each match. Matches may not share positions unless
+/// `allow_overlap` is set.
+/// Accepts the input-related pairwise options (`id_col`, `target_col`, ...).
+#[pyfunction]
+#[pyo3(signature = (df, query, k=1, window=None, allow_overlap=false, **options))]
+pub fn compute_subsequence_dtw(
+    df: PyDataFrame,
+    query: Vec<f64>,
+    k: usize,
+    window: Option<usize>,
+    allow_overlap: bool,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    if query.is_empty() {
+        return Err(PyValueError::new_err("query must not be empty"));
+    }
+    if query.iter().any(|v| !v.is_finite()) {
+        return Err(PyValueError::new_err("query must only contain finite values"));
+    }
+    if k < 1 {
+        return Err(PyValueError::new_err("k must be >= 1"));
+    }
+    let options = PairwiseOptions::from_kwargs(options)?;
+    options.ensure_default_output("compute_subsequence_dtw")?;
+    let (map, ids, uid_dtype) = load_univariate(&df.into(), &options)?;
+
+    let found = par_map_interruptible(&options.job_pool()?, &ids, |id| {
+        let ends = spring_matches(&query, &map[id], window);
+        top_subsequence_matches(&ends, k, allow_overlap)
+    })?;
+    let matches: Vec<(&String, Vec<_>)> = ids.iter().zip(found).collect();
+
+    let mut id_values: Vec<&str> = Vec::new();
+    let mut starts: Vec<i64> = Vec::new();
+    let mut ends: Vec<i64> = Vec::new();
+    let mut distances: Vec<f64> = Vec::new();
+    for (id, found) in &matches {
+        for &(start, end, distance) in found {
+            id_values.push(id.as_str());
+            starts.push(start as i64);
+            ends.push(end as i64);
+            distances.push(distance);
+        }
+    }
+
+    let id_col = Column::new(options.columns.id_col.as_str().into(), id_values)
+        .cast(&uid_dtype)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    DataFrame::new(vec![
+        id_col,
+        Column::new("start_idx".into(), starts),
+        Column::new("end_idx".into(), ends),
+        Column::new("distance".into(), distances),
+    ])
+    .map(PyDataFrame)
+    .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path_cost(a: &[f64], b: &[f64], path: &[(usize, usize)]) -> f64 {
+        path.iter().map(|&(i, j)| (a[i] - b[j]).abs()).sum()
+    }
+
+    #[test]
+    fn test_full_path_matches_distance() {
+        let a = [0.0, 1.0, 2.0, 3.0, 2.0, 0.0];
+        let b = [0.0, 0.0, 1.0, 3.0, 1.0];
+        let path = dtw_full_path(&a, &b, PointCost::Abs);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.last(), Some(&(a.len() - 1, b.len() - 1)));
+        assert!((path_cost(&a, &b, &path) - dtw_distance(&a, &b, PointCost::Abs)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_path_is_monotone_and_continuous() {
+        let a: Vec<f64> = (0..20).map(|i| (i as f64 * 0.3).sin()).collect();
+        let b: Vec<f64> = (0..15).map(|i| (i as f64 * 0.4).sin()).collect();
+        for method in ["standard", "sakoe_chiba", "itakura", "fast"] {
+            let param = resolve_method(Some(method), None).unwrap().1;
+            let path = compute_dtw_path(&a, &b, method, param, PointCost::Abs);
+            assert_eq!(path.first(), Some(&(0, 0)), "{method}");
+            assert_eq!(path.last(), Some(&(19, 14)), "{method}");
+            for w in path.windows(2) {
+                let (di, dj) = (w[1].0 - w[0].0, w[1].1 - w[0].1);
+                assert!(di <= 1 && dj <= 1 && di + dj >= 1, "{method}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_banded_paths_match_distances() {
+        let a: Vec<f64> = (0..12).map(|i| (i as f64).cos()).collect();
+        let b: Vec<f64> = (0..12).map(|i| (i as f64 + 1.0).cos()).collect();
+        let sc = dtw_path_sakoe_chiba(&a, &b, 2, PointCost::Abs);
+        assert!(sc.iter().all(|&(i, j)| i.abs_diff(j) <= 2));
+        assert!((path_cost(&a, &b, &sc) - dtw_sakoe_chiba(&a, &b, 2, PointCost::Abs)).abs() < 1e-12);
+        let it = dtw_path_itakura(&a, &b, 2.0, PointCost::Abs);
+        assert!((path_cost(&a, &b, &it) - dtw_itakura(&a, &b, 2.0, PointCost::Abs)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cost_and_path_normalization() {
+        let a: Vec<f64> = (0..20).map(|i| (i as f64 * 0.3).sin()).collect();
+        let b: Vec<f64> = (0..15).map(|i| (i as f64 * 0.4).cos()).collect();
+        for method in ["standard", "sakoe_chiba", "itakura", "fast"] {
+            let param = resolve_method(Some(method), None).unwrap().1;
+            for cost in [PointCost::Abs, PointCost::Squared, PointCost::Huber(0.3)] {
+                let d = compute_dtw(&a, &b, method, param, cost, false);
+                let path = compute_dtw_path(&a, &b, method, param, cost);
+                let along: f64 = path.iter().map(|&(i, j)| cost.cost(a[i], b[j])).sum();
+                assert!((along - d).abs() < 1e-10, "{method} {cost:?}");
+                let normalized = compute_dtw(&a, &b, method, param, cost, true);
+                assert!((normalized * path.len() as f64 - d).abs() < 1e-10, "{method} {cost:?}");
+            }
+        }
+    }
+
+    fn lcg_series(seed: u64, len: usize) -> Vec<f64> {
+        let mut state = seed;
+        let mut level = 0.0;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                level += ((state >> 33) as f64 / (1u64 << 31) as f64) - 0.5;
+                level
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_envelope_matches_naive() {
+        let x = lcg_series(7, 30);
+        for w in [0, 1, 3, 40] {
+            let (upper, lower) = envelope(&x, w);
+            for p in 0..x.len() {
+                let win = &x[p.saturating_sub(w)..(p + w + 1).min(x.len())];
+                assert_eq!(upper[p], win.iter().cloned().fold(f64::MIN, f64::max));
+                assert_eq!(lower[p], win.iter().cloned().fold(f64::MAX, f64::min));
+            }
+        }
+    }
+
+    #[test]
+    fn test_lower_bounds_below_dtw() {
+        let w = 3;
+        for seed in 0..20 {
+            let a = lcg_series(seed, 25);
+            let b = lcg_series(seed + 100, 25);
+            let d = dtw_sakoe_chiba(&a, &b, w, PointCost::Abs);
+            let (upper, lower) = envelope(&a, w);
+            assert!(lb_kim(&a, &b) <= d + 1e-9);
+            assert!(lb_keogh(&b, &upper, &lower) <= d + 1e-9);
+            assert!(lb_improved(&b, &a, &upper, &lower, w, f64::INFINITY) <= d + 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_early_abandon() {
+        let a = lcg_series(1, 40);
+        let b = lcg_series(2, 40);
+        let d = dtw_sakoe_chiba(&a, &b, 5, PointCost::Abs);
+        assert_eq!(dtw_sakoe_chiba_abandon(&a, &b, 5, PointCost::Abs, d + 1.0), d);
+        assert_eq!(dtw_sakoe_chiba_abandon(&a, &b, 5, PointCost::Abs, d / 10.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_knn_matches_brute_force() {
+        let query = lcg_series(999, 32);
+        let pool: Vec<Vec<f64>> = (0..40).map(|s| lcg_series(s, if s % 5 == 0 { 28 } else { 32 })).collect();
+        let refs: Vec<&[f64]> = pool.iter().map(|s| s.as_slice()).collect();
+        for window in [Some(4), None] {
+            let band = |c: &[f64]| window.unwrap_or(query.len().max(c.len()));
+            let mut brute: Vec<(usize, f64)> = refs
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (i, dtw_sakoe_chiba(&query, c, band(c), PointCost::Abs)))
+                .collect();
+            brute.sort_by(|x, y| x.1.total_cmp(&y.1).then(x.0.cmp(&y.0)));
+            for bound in [LowerBound::None, LowerBound::Kim, LowerBound::Keogh, LowerBound::Improved] {
+                let knn = knn_dtw_single(&query, &refs, 3, window, bound);
+                assert_eq!(knn, brute[..3].to_vec());
+            }
+        }
+    }
+
+    #[test]
+    fn test_knn_ties_go_to_first_reference() {
+        // Both references are at distance 1, but LB_Kim visits `late_end`
+        // last, so a scan-order tie-break would flip with their positions.
+        let query = [0.0; 4];
+        let late_end = [0.0, 0.0, 0.0, 1.0];
+        let early_bump = [0.0, 1.0, 0.0, 0.0];
+        for refs in [[&late_end[..], &early_bump[..]], [&early_bump[..], &late_end[..]]] {
+            for bound in [LowerBound::None, LowerBound::Kim, LowerBound::Keogh, LowerBound::Improved] {
+                assert_eq!(knn_dtw_single(&query, &refs, 1, None, bound), vec![(0, 1.0)]);
+                assert_eq!(knn_dtw_single(&query, &refs, 2, None, bound), vec![(0, 1.0), (1, 1.0)]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_spring_matches_brute_force() {
+        let query = lcg_series(3, 5);
+        let series = lcg_series(9, 30);
+        let ends = spring_matches(&query, &series, None);
+        for (end, &(start, d)) in ends.iter().enumerate() {
+            let best = (0..=end)
+                .map(|s| dtw_distance(&query, &series[s..=end], PointCost::Abs))
+                .fold(f64::INFINITY, f64::min);
+            assert!((d - best).abs() < 1e-10, "end {end}");
+            assert!((dtw_distance(&query, &series[start..=end], PointCost::Abs) - d).abs() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_spring_band() {
+        let query = lcg_series(3, 5);
+        let series = lcg_series(9, 30);
+        let banded = spring_matches(&query, &series, Some(1));
+        let free = spring_matches(&query, &series, None);
+        for (end, (&(start, d), &(_, free_d))) in banded.iter().zip(&free).enumerate() {
+            assert!(d >= free_d);
+            if d.is_finite() {
+                // A band of 1 keeps the match length within 1 of the query length
+                assert!((end + 1 - start).abs_diff(query.len()) <= 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_subsequence_finds_planted_queries() {
+        let query = [0.0, 3.0, 6.0, 3.0, 0.0];
+        let mut series = vec![1.0; 60];
+        series[10..15].copy_from_slice(&query);
+        // A time-stretched copy
+        series[40..47].copy_from_slice(&[0.0, 3.0, 3.0, 6.0, 6.0, 3.0, 0.0]);
+        let ends = spring_matches(&query, &series, None);
+        let matches = top_subsequence_matches(&ends, 2, false);
+        assert_eq!(matches[0], (10, 14, 0.0));
+        assert_eq!(matches[1], (40, 46, 0.0));
+
+        // Overlapping candidates around the first match are suppressed only on request
+        let overlapping = top_subsequence_matches(&ends, 3, true);
+        assert!(overlapping.iter().any(|&(s, e, _)| s <= 14 && 10 <= e && (s, e) != (10, 14)));
+    }
+}
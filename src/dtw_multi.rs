@@ -1,60 +1,219 @@
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
 
-use crate::utils::compute_pairwise_multivariate;
+use crate::pointwise::PointMetric;
+use crate::utils::{prepare_multivariate, run_pairwise, PairwiseInput, PairwiseOptions};
 
-/// Distance metric for DTW cost calculations.
-#[derive(Clone, Copy)]
-enum DistanceMetric {
-    Manhattan,
-    Euclidean,
+/// How multivariate DTW warps the dimensions of a series
+/// (Shokoohi-Yekta et al., 2017).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strategy {
+    /// DTW_D: every dimension shares one warping path.
+    Dependent,
+    /// DTW_I: each dimension is warped on its own and the distances summed.
+    Independent,
+    /// DTW_A: per pair, DTW_I when `DTW_D / DTW_I` exceeds the threshold,
+    /// otherwise DTW_D.
+    Adaptive(f64),
 }
 
-/// Multivariate DTW distance using the specified metric. O(m) memory.
-fn dtw_distance_multivariate(a: &[Vec<f64>], b: &[Vec<f64>], metric: DistanceMetric) -> f64 {
-    let n = a.len();
-    let m = b.len();
+impl Strategy {
+    fn parse(name: Option<&str>, threshold: Option<f64>) -> PyResult<Self> {
+        let strategy = match name {
+            None | Some("dependent") => Strategy::Dependent,
+            Some("independent") => Strategy::Independent,
+            Some("adaptive") => Strategy::Adaptive(threshold.unwrap_or(1.0)),
+            Some(other) => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown strategy: '{other}'. Expected one of: dependent, independent, adaptive"
+                )))
+            }
+        };
+        if threshold.is_some() && !matches!(strategy, Strategy::Adaptive(_)) {
+            return Err(PyValueError::new_err("adaptive_threshold only applies to strategy='adaptive'"));
+        }
+        Ok(strategy)
+    }
+}
+
+/// DTW over an `n` x `m` grid with local cost `cost(i, j)` and an optional
+/// Sakoe-Chiba band, widened to `|n - m|`. O(m) memory.
+fn dtw_with_cost<F>(n: usize, m: usize, window: Option<usize>, cost: F) -> f64
+where
+    F: Fn(usize, usize) -> f64,
+{
+    let w = window.map_or(n.max(m), |w| w.max(n.abs_diff(m)));
     let mut prev = vec![f64::MAX; m + 1];
     let mut curr = vec![f64::MAX; m + 1];
     prev[0] = 0.0;
 
     for i in 1..=n {
         curr[0] = f64::MAX;
-        for j in 1..=m {
-            let cost: f64 = match metric {
-                DistanceMetric::Manhattan => {
-                    a[i - 1].iter().zip(b[j - 1].iter())
-                        .map(|(x, y)| (x - y).abs())
-                        .sum()
-                }
-                DistanceMetric::Euclidean => {
-                    let sum_sq: f64 = a[i - 1].iter().zip(b[j - 1].iter())
-                        .map(|(x, y)| (x - y).powi(2))
-                        .sum();
-                    sum_sq.sqrt()
-                }
-            };
+        let j_start = i.saturating_sub(w).max(1);
+        let j_end = (i + w).min(m);
+        if j_start > 1 {
+            curr[j_start - 1] = f64::MAX;
+        }
+        for j in j_start..=j_end {
             let min_prev = prev[j].min(curr[j - 1]).min(prev[j - 1]);
-            curr[j] = cost + min_prev;
+            curr[j] = cost(i - 1, j - 1) + min_prev;
         }
         std::mem::swap(&mut prev, &mut curr);
     }
     prev[m]
 }
 
+/// Multivariate DTW distance using the specified metric. O(m) memory.
+pub(crate) fn dtw_distance_multivariate(a: &[Vec<f64>], b: &[Vec<f64>], metric: PointMetric) -> f64 {
+    dtw_with_cost(a.len(), b.len(), None, |i, j| metric.distance(&a[i], &b[j]))
+}
+
+/// Dependent DTW (DTW_D) with optional per-dimension weights.
+fn dtw_dependent(
+    a: &[Vec<f64>],
+    b: &[Vec<f64>],
+    metric: PointMetric,
+    weights: Option<&[f64]>,
+    window: Option<usize>,
+) -> f64 {
+    match weights {
+        Some(w) => dtw_with_cost(a.len(), b.len(), window, |i, j| metric.weighted_distance(&a[i], &b[j], w)),
+        None => dtw_with_cost(a.len(), b.len(), window, |i, j| metric.distance(&a[i], &b[j])),
+    }
+}
+
+/// Independent DTW (DTW_I): the weighted sum of univariate DTW over each dimension.
+fn dtw_independent(
+    a: &[Vec<f64>],
+    b: &[Vec<f64>],
+    metric: PointMetric,
+    weights: Option<&[f64]>,
+    window: Option<usize>,
+) -> f64 {
+    let dims = a.first().or(b.first()).map_or(0, Vec::len);
+    (0..dims)
+        .map(|k| {
+            let dist = dtw_with_cost(a.len(), b.len(), window, |i, j| metric.distance(&a[i][k..=k], &b[j][k..=k]));
+            weights.map_or(dist, |w| w[k] * dist)
+        })
+        .sum()
+}
+
+fn dtw_strategy(
+    a: &[Vec<f64>],
+    b: &[Vec<f64>],
+    strategy: Strategy,
+    metric: PointMetric,
+    weights: Option<&[f64]>,
+    window: Option<usize>,
+) -> f64 {
+    match strategy {
+        Strategy::Dependent => dtw_dependent(a, b, metric, weights, window),
+        Strategy::Independent => dtw_independent(a, b, metric, weights, window),
+        Strategy::Adaptive(threshold) => {
+            let dependent = dtw_dependent(a, b, metric, weights, window);
+            let independent = dtw_independent(a, b, metric, weights, window);
+            if dependent / (independent + f64::EPSILON) > threshold {
+                independent
+            } else {
+                dependent
+            }
+        }
+    }
+}
+
+/// Check that `weights` are usable and have one entry per dimension of every series.
+fn check_weights(input: &PairwiseInput<Vec<Vec<f64>>>, weights: &[f64]) -> PyResult<()> {
+    if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+        return Err(PyValueError::new_err("weights must be finite and non-negative"));
+    }
+    match input.dimensions()? {
+        Some(dims) if dims != weights.len() => Err(PyValueError::new_err(format!(
+            "weights has {} entries but the input has {dims} dimensions",
+            weights.len()
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Pairwise multivariate DTW.
+///
+/// `strategy` is `"dependent"` (DTW_D, the default), `"independent"` (DTW_I)
+/// or `"adaptive"` (DTW_A, which compares `DTW_D / DTW_I` against
+/// `adaptive_threshold`, default 1.0; the paper learns it from training data).
+/// `weights` scale each dimension's contribution and `window` is a
+/// Sakoe-Chiba band.
 #[pyfunction]
-#[pyo3(signature = (input1, input2, metric=None))]
+#[pyo3(signature = (
+    input1, input2, metric=None, strategy=None, weights=None, window=None, adaptive_threshold=None, **options
+))]
+#[allow(clippy::too_many_arguments)]
 pub fn compute_pairwise_dtw_multi(
     input1: PyDataFrame,
     input2: PyDataFrame,
     metric: Option<String>,
+    strategy: Option<String>,
+    weights: Option<Vec<f64>>,
+    window: Option<usize>,
+    adaptive_threshold: Option<f64>,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<PyDataFrame> {
-    let distance_metric = match metric.as_deref() {
-        Some("euclidean") => DistanceMetric::Euclidean,
-        _ => DistanceMetric::Manhattan,
-    };
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let distance_metric = PointMetric::parse(metric.as_deref(), PointMetric::Manhattan)?;
+    let strategy = Strategy::parse(strategy.as_deref(), adaptive_threshold)?;
 
-    compute_pairwise_multivariate(input1, input2, "dtw_multi", move |a, b| {
-        dtw_distance_multivariate(a, b, distance_metric)
+    let input = prepare_multivariate(input1, input2, &options)?;
+    if let Some(weights) = &weights {
+        check_weights(&input, weights)?;
+    }
+    let weights = weights.as_deref();
+    run_pairwise(&input, "dtw_multi", &options, |a: &Vec<Vec<f64>>, b: &Vec<Vec<f64>>| {
+        dtw_strategy(a, b, strategy, distance_metric, weights, window)
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(points: &[[f64; 2]]) -> Vec<Vec<f64>> {
+        points.iter().map(|p| p.to_vec()).collect()
+    }
+
+    #[test]
+    fn test_independent_sums_univariate() {
+        // The spikes of `a` are two steps apart while `b` spikes in both
+        // dimensions at once, so only DTW_I aligns both
+        let a = series(&[[0.0, 0.0], [1.0, 0.0], [0.0, 0.0], [0.0, 1.0], [0.0, 0.0]]);
+        let b = series(&[[0.0, 0.0], [0.0, 0.0], [1.0, 1.0], [0.0, 0.0], [0.0, 0.0]]);
+        let m = PointMetric::Manhattan;
+        assert_eq!(dtw_independent(&a, &b, m, None, None), 0.0);
+        assert!(dtw_dependent(&a, &b, m, None, None) > 0.0);
+        assert_eq!(dtw_strategy(&a, &b, Strategy::Adaptive(1.0), m, None, None), 0.0);
+        assert!(dtw_strategy(&a, &b, Strategy::Adaptive(f64::INFINITY), m, None, None) > 0.0);
+    }
+
+    #[test]
+    fn test_weights_scale_dimensions() {
+        let a = series(&[[0.0, 0.0], [0.0, 0.0]]);
+        let b = series(&[[1.0, 2.0], [1.0, 2.0]]);
+        let m = PointMetric::Manhattan;
+        let w = [2.0, 0.0];
+        assert_eq!(dtw_dependent(&a, &b, m, Some(&w), None), 4.0);
+        assert_eq!(dtw_independent(&a, &b, m, Some(&w), None), 4.0);
+        assert_eq!(dtw_dependent(&a, &b, m, Some(&[1.0, 1.0]), None), dtw_dependent(&a, &b, m, None, None));
+    }
+
+    #[test]
+    fn test_band_restricts_warping() {
+        let a = series(&[[0.0, 0.0], [1.0, 0.0], [0.0, 0.0], [0.0, 0.0]]);
+        let b = series(&[[0.0, 0.0], [0.0, 0.0], [1.0, 0.0], [0.0, 0.0]]);
+        let m = PointMetric::Manhattan;
+        let full = dtw_dependent(&a, &b, m, None, None);
+        assert_eq!(full, dtw_dependent(&a, &b, m, None, Some(3)));
+        assert_eq!(full, 0.0);
+        assert_eq!(dtw_dependent(&a, &b, m, None, Some(0)), 2.0);
+    }
+}
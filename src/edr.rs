@@ -1,11 +1,15 @@
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
 
-use crate::utils::compute_pairwise;
+use crate::utils::{compute_pairwise, PairwiseOptions};
 
 /// Edit Distance on Real sequences (EDR) with epsilon threshold. O(m) memory.
 /// Returns the normalized EDR distance: edr_count / max(n, m).
-fn edr_distance(a: &[f64], b: &[f64], epsilon: f64) -> f64 {
+///
+/// With a `window`, only cells with `|i - j| <= window` are filled (Sakoe-Chiba
+/// band, widened to `|n - m|`).
+fn edr_distance(a: &[f64], b: &[f64], epsilon: f64, window: Option<usize>) -> f64 {
     let n = a.len();
     let m = b.len();
     if n == 0 {
@@ -14,25 +18,32 @@ fn edr_distance(a: &[f64], b: &[f64], epsilon: f64) -> f64 {
     if m == 0 {
         return 1.0;
     }
+    let w = window.map_or(n.max(m), |w| w.max(n.abs_diff(m)));
 
-    let mut prev = vec![0usize; m + 1];
-    let mut curr = vec![0usize; m + 1];
+    // Cells outside the band are unreachable (usize::MAX)
+    let mut prev = vec![usize::MAX; m + 1];
+    let mut curr = vec![usize::MAX; m + 1];
 
-    for (j, prev_j) in prev.iter_mut().enumerate() {
+    for (j, prev_j) in prev.iter_mut().enumerate().take(w + 1) {
         *prev_j = j;
     }
 
     for i in 1..=n {
-        curr[0] = i;
-        for j in 1..=m {
+        curr[0] = if i <= w { i } else { usize::MAX };
+        let j_start = i.saturating_sub(w).max(1);
+        let j_end = (i + w).min(m);
+        if j_start > 1 {
+            curr[j_start - 1] = usize::MAX;
+        }
+        for j in j_start..=j_end {
             let subcost = if (a[i - 1] - b[j - 1]).abs() <= epsilon {
                 0
             } else {
                 1
             };
-            curr[j] = (prev[j - 1] + subcost)
-                .min(prev[j] + 1)
-                .min(curr[j - 1] + 1);
+            curr[j] = prev[j - 1].saturating_add(subcost)
+                .min(prev[j].saturating_add(1))
+                .min(curr[j - 1].saturating_add(1));
         }
         std::mem::swap(&mut prev, &mut curr);
     }
@@ -41,14 +52,17 @@ fn edr_distance(a: &[f64], b: &[f64], epsilon: f64) -> f64 {
 }
 
 #[pyfunction]
-#[pyo3(signature = (input1, input2, epsilon=None))]
+#[pyo3(signature = (input1, input2, epsilon=None, window=None, **options))]
 pub fn compute_pairwise_edr(
     input1: PyDataFrame,
     input2: PyDataFrame,
     epsilon: Option<f64>,
+    window: Option<usize>,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
     let eps = epsilon.unwrap_or(0.1);
-    compute_pairwise(input1, input2, "edr", move |a, b| {
-        edr_distance(a, b, eps)
+    compute_pairwise(input1, input2, "edr", &options, move |a, b| {
+        edr_distance(a, b, eps, window)
     })
 }
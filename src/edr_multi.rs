@@ -0,0 +1,61 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_polars::PyDataFrame;
+
+use crate::pointwise::PointMetric;
+use crate::utils::{compute_pairwise_multivariate, PairwiseOptions};
+
+/// Multivariate EDR: substitution is free when two points are within
+/// `epsilon` of each other. Returns edr_count / max(n, m). O(m) memory;
+/// `window` is a Sakoe-Chiba band as in `edr.rs`.
+fn edr_distance(a: &[Vec<f64>], b: &[Vec<f64>], epsilon: f64, window: Option<usize>, metric: PointMetric) -> f64 {
+    let n = a.len();
+    let m = b.len();
+    if n == 0 || m == 0 {
+        return 1.0;
+    }
+    let w = window.map_or(n.max(m), |w| w.max(n.abs_diff(m)));
+
+    let mut prev = vec![usize::MAX; m + 1];
+    let mut curr = vec![usize::MAX; m + 1];
+
+    for (j, prev_j) in prev.iter_mut().enumerate().take(w + 1) {
+        *prev_j = j;
+    }
+
+    for i in 1..=n {
+        curr[0] = if i <= w { i } else { usize::MAX };
+        let j_start = i.saturating_sub(w).max(1);
+        let j_end = (i + w).min(m);
+        if j_start > 1 {
+            curr[j_start - 1] = usize::MAX;
+        }
+        for j in j_start..=j_end {
+            let subcost = usize::from(metric.distance(&a[i - 1], &b[j - 1]) > epsilon);
+            curr[j] = prev[j - 1].saturating_add(subcost)
+                .min(prev[j].saturating_add(1))
+                .min(curr[j - 1].saturating_add(1));
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    prev[m] as f64 / n.max(m) as f64
+}
+
+#[pyfunction]
+#[pyo3(signature = (input1, input2, epsilon=None, window=None, metric=None, **options))]
+pub fn compute_pairwise_edr_multi(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    epsilon: Option<f64>,
+    window: Option<usize>,
+    metric: Option<String>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let eps = epsilon.unwrap_or(0.1);
+    let metric = PointMetric::parse(metric.as_deref(), PointMetric::Manhattan)?;
+    compute_pairwise_multivariate(input1, input2, "edr_multi", &options, move |a, b| {
+        edr_distance(a, b, eps, window, metric)
+    })
+}
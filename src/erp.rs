@@ -1,25 +1,36 @@
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
 
-use crate::utils::compute_pairwise;
+use crate::utils::{compute_pairwise, PairwiseOptions};
 
 /// ERP (Edit Distance with Real Penalty) distance. O(m) memory.
-fn erp_distance(a: &[f64], b: &[f64], g: f64) -> f64 {
+///
+/// With a `window`, only cells with `|i - j| <= window` are filled (Sakoe-Chiba
+/// band); the band is widened to `|n - m|` so the last cell stays reachable.
+fn erp_distance(a: &[f64], b: &[f64], g: f64, window: Option<usize>) -> f64 {
     let n = a.len();
     let m = b.len();
+    let w = window.map_or(n.max(m), |w| w.max(n.abs_diff(m)));
 
-    let mut prev = vec![0.0_f64; m + 1];
-    let mut curr = vec![0.0_f64; m + 1];
+    let mut prev = vec![f64::INFINITY; m + 1];
+    let mut curr = vec![f64::INFINITY; m + 1];
 
-    for j in 1..=m {
+    prev[0] = 0.0;
+    for j in 1..=m.min(w) {
         prev[j] = prev[j - 1] + (b[j - 1] - g).abs();
     }
 
     let mut first_col_cost = 0.0_f64;
     for i in 1..=n {
         first_col_cost += (a[i - 1] - g).abs();
-        curr[0] = first_col_cost;
-        for j in 1..=m {
+        curr[0] = if i <= w { first_col_cost } else { f64::INFINITY };
+        let j_start = i.saturating_sub(w).max(1);
+        let j_end = (i + w).min(m);
+        if j_start > 1 {
+            curr[j_start - 1] = f64::INFINITY;
+        }
+        for j in j_start..=j_end {
             let d_match = prev[j - 1] + (a[i - 1] - b[j - 1]).abs();
             let d_delete = prev[j] + (a[i - 1] - g).abs();
             let d_insert = curr[j - 1] + (b[j - 1] - g).abs();
@@ -31,14 +42,17 @@ fn erp_distance(a: &[f64], b: &[f64], g: f64) -> f64 {
 }
 
 #[pyfunction]
-#[pyo3(signature = (input1, input2, g=None))]
+#[pyo3(signature = (input1, input2, g=None, window=None, **options))]
 pub fn compute_pairwise_erp(
     input1: PyDataFrame,
     input2: PyDataFrame,
     g: Option<f64>,
+    window: Option<usize>,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
     let g_value = g.unwrap_or(0.0);
-    compute_pairwise(input1, input2, "erp", move |a, b| {
-        erp_distance(a, b, g_value)
+    compute_pairwise(input1, input2, "erp", &options, move |a, b| {
+        erp_distance(a, b, g_value, window)
     })
 }
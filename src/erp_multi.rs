@@ -0,0 +1,60 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_polars::PyDataFrame;
+
+use crate::pointwise::PointMetric;
+use crate::utils::{compute_pairwise_multivariate, PairwiseOptions};
+
+/// Multivariate ERP distance. The gap is the point with every coordinate
+/// equal to `g`. O(m) memory; `window` is a Sakoe-Chiba band as in `erp.rs`.
+fn erp_distance(a: &[Vec<f64>], b: &[Vec<f64>], g: f64, window: Option<usize>, metric: PointMetric) -> f64 {
+    let n = a.len();
+    let m = b.len();
+    let w = window.map_or(n.max(m), |w| w.max(n.abs_diff(m)));
+
+    let mut prev = vec![f64::INFINITY; m + 1];
+    let mut curr = vec![f64::INFINITY; m + 1];
+
+    prev[0] = 0.0;
+    for j in 1..=m.min(w) {
+        prev[j] = prev[j - 1] + metric.distance_to_value(&b[j - 1], g);
+    }
+
+    let mut first_col_cost = 0.0_f64;
+    for i in 1..=n {
+        let gap_a = metric.distance_to_value(&a[i - 1], g);
+        first_col_cost += gap_a;
+        curr[0] = if i <= w { first_col_cost } else { f64::INFINITY };
+        let j_start = i.saturating_sub(w).max(1);
+        let j_end = (i + w).min(m);
+        if j_start > 1 {
+            curr[j_start - 1] = f64::INFINITY;
+        }
+        for j in j_start..=j_end {
+            let d_match = prev[j - 1] + metric.distance(&a[i - 1], &b[j - 1]);
+            let d_delete = prev[j] + gap_a;
+            let d_insert = curr[j - 1] + metric.distance_to_value(&b[j - 1], g);
+            curr[j] = d_match.min(d_delete).min(d_insert);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[m]
+}
+
+#[pyfunction]
+#[pyo3(signature = (input1, input2, g=None, window=None, metric=None, **options))]
+pub fn compute_pairwise_erp_multi(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    g: Option<f64>,
+    window: Option<usize>,
+    metric: Option<String>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let g_value = g.unwrap_or(0.0);
+    let metric = PointMetric::parse(metric.as_deref(), PointMetric::Manhattan)?;
+    compute_pairwise_multivariate(input1, input2, "erp_multi", &options, move |a, b| {
+        erp_distance(a, b, g_value, window, metric)
+    })
+}
@@ -1,7 +1,8 @@
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
 
-use crate::utils::compute_pairwise;
+use crate::utils::{compute_pairwise, PairwiseOptions};
 
 /// Discrete Frechet distance between two time series. Standard O(nm) DP.
 fn frechet_distance(a: &[f64], b: &[f64]) -> f64 {
@@ -32,9 +33,12 @@ fn frechet_distance(a: &[f64], b: &[f64]) -> f64 {
 }
 
 #[pyfunction]
+#[pyo3(signature = (input1, input2, **options))]
 pub fn compute_pairwise_frechet(
     input1: PyDataFrame,
     input2: PyDataFrame,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<PyDataFrame> {
-    compute_pairwise(input1, input2, "frechet", frechet_distance)
+    let options = PairwiseOptions::from_kwargs(options)?;
+    compute_pairwise(input1, input2, "frechet", &options, frechet_distance)
 }
@@ -0,0 +1,48 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_polars::PyDataFrame;
+
+use crate::pointwise::PointMetric;
+use crate::utils::{compute_pairwise_multivariate, PairwiseOptions};
+
+/// Discrete Frechet distance between two multivariate series. O(m) memory.
+fn frechet_distance(a: &[Vec<f64>], b: &[Vec<f64>], metric: PointMetric) -> f64 {
+    let n = a.len();
+    let m = b.len();
+    if n == 0 || m == 0 {
+        return f64::INFINITY;
+    }
+
+    let mut prev = vec![f64::NEG_INFINITY; m];
+    let mut curr = vec![f64::NEG_INFINITY; m];
+
+    prev[0] = metric.distance(&a[0], &b[0]);
+    for j in 1..m {
+        prev[j] = prev[j - 1].max(metric.distance(&a[0], &b[j]));
+    }
+
+    for ai in &a[1..] {
+        curr[0] = prev[0].max(metric.distance(ai, &b[0]));
+        for j in 1..m {
+            let min_prev = prev[j - 1].min(prev[j]).min(curr[j - 1]);
+            curr[j] = min_prev.max(metric.distance(ai, &b[j]));
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[m - 1]
+}
+
+#[pyfunction]
+#[pyo3(signature = (input1, input2, metric=None, **options))]
+pub fn compute_pairwise_frechet_multi(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    metric: Option<String>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let metric = PointMetric::parse(metric.as_deref(), PointMetric::Manhattan)?;
+    compute_pairwise_multivariate(input1, input2, "frechet_multi", &options, move |a, b| {
+        frechet_distance(a, b, metric)
+    })
+}
@@ -4,7 +4,8 @@
 //! swap algorithm in Rust, returning cluster assignments.
 
 use pyo3::prelude::*;
-use rayon::prelude::*;
+
+use crate::utils::JobPool;
 
 /// Run the PAM swap algorithm on a precomputed distance matrix.
 ///
@@ -14,9 +15,22 @@ use rayon::prelude::*;
 /// * `k` - Number of clusters
 /// * `max_iter` - Maximum swap iterations
 /// * `seed` - Random seed for initial medoid selection
+/// * `pool` - Threads the swap candidates are evaluated on
+/// * `check_interrupt` - Called before every swap iteration; an error aborts the run
 ///
 /// Returns (medoid_indices, cluster_assignments) as Vec<usize>.
-fn pam_swap(dist_flat: &[f64], n: usize, k: usize, max_iter: usize, seed: u64) -> (Vec<usize>, Vec<usize>) {
+fn pam_swap<C>(
+    dist_flat: &[f64],
+    n: usize,
+    k: usize,
+    max_iter: usize,
+    seed: u64,
+    pool: &JobPool,
+    mut check_interrupt: C,
+) -> PyResult<(Vec<usize>, Vec<usize>)>
+where
+    C: FnMut() -> PyResult<()>,
+{
     // Simple LCG for deterministic initial selection (no external dep needed)
     let mut rng_state = seed;
     let mut rand_next = || -> usize {
@@ -72,6 +86,7 @@ fn pam_swap(dist_flat: &[f64], n: usize, k: usize, max_iter: usize, seed: u64) -
 
     // PAM swap loop
     for _ in 0..max_iter {
+        check_interrupt()?;
         let mut improved = false;
 
         // Build all (medoid_index, candidate) swap pairs
@@ -81,9 +96,8 @@ fn pam_swap(dist_flat: &[f64], n: usize, k: usize, max_iter: usize, seed: u64) -
             .collect();
 
         // Evaluate all swaps in parallel
-        let best_swap: Option<(usize, usize, f64)> = swap_candidates
-            .par_iter()
-            .filter_map(|&(mi, candidate)| {
+        let best_swap: Option<(usize, usize, f64)> = pool
+            .map(&swap_candidates, |&(mi, candidate)| {
                 let mut new_medoids = medoids.clone();
                 new_medoids[mi] = candidate;
                 let new_cost = total_cost(&new_medoids);
@@ -93,6 +107,8 @@ fn pam_swap(dist_flat: &[f64], n: usize, k: usize, max_iter: usize, seed: u64) -
                     None
                 }
             })
+            .into_iter()
+            .flatten()
             .min_by(|a, b| a.2.partial_cmp(&b.2).unwrap_or(std::cmp::Ordering::Equal));
 
         if let Some((mi, candidate, new_cost)) = best_swap {
@@ -108,7 +124,7 @@ fn pam_swap(dist_flat: &[f64], n: usize, k: usize, max_iter: usize, seed: u64) -
         }
     }
 
-    (medoids, assignments)
+    Ok((medoids, assignments))
 }
 
 /// Rust-accelerated k-medoids PAM algorithm.
@@ -116,14 +132,21 @@ fn pam_swap(dist_flat: &[f64], n: usize, k: usize, max_iter: usize, seed: u64) -
 /// Accepts a flat distance matrix (list of n*n floats, row-major),
 /// the number of series n, clusters k, max iterations, and seed.
 /// Returns a list of cluster assignments (0-indexed) for each series.
+///
+/// The swap loop runs with the GIL released and checks for Python signals
+/// between iterations, so it can be interrupted with Ctrl-C. `n_jobs`
+/// limits the worker threads (rayon's global pool by default; 1 is
+/// sequential).
 #[pyfunction]
-#[pyo3(signature = (dist_flat, n, k, max_iter=100, seed=42))]
+#[pyo3(signature = (dist_flat, n, k, max_iter=100, seed=42, n_jobs=None))]
 pub fn kmedoids_pam(
+    py: Python<'_>,
     dist_flat: Vec<f64>,
     n: usize,
     k: usize,
     max_iter: usize,
     seed: u64,
+    n_jobs: Option<usize>,
 ) -> PyResult<(Vec<usize>, Vec<usize>)> {
     if k < 1 {
         return Err(pyo3::exceptions::PyValueError::new_err("k must be >= 1"));
@@ -141,14 +164,20 @@ pub fn kmedoids_pam(
         )));
     }
 
-    let (medoids, assignments) = pam_swap(&dist_flat, n, k, max_iter, seed);
-    Ok((medoids, assignments))
+    let pool = JobPool::new(n_jobs)?;
+    py.allow_threads(|| {
+        pam_swap(&dist_flat, n, k, max_iter, seed, &pool, || Python::with_gil(|py| py.check_signals()))
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn no_interrupt() -> PyResult<()> {
+        Ok(())
+    }
+
     fn make_dist_matrix(n: usize) -> Vec<f64> {
         // Simple distance: |i - j| as float
         let mut dist = vec![0.0; n * n];
@@ -163,7 +192,7 @@ mod tests {
     #[test]
     fn test_single_cluster() {
         let dist = make_dist_matrix(5);
-        let (medoids, assignments) = pam_swap(&dist, 5, 1, 100, 42);
+        let (medoids, assignments) = pam_swap(&dist, 5, 1, 100, 42, &JobPool::Global, no_interrupt).unwrap();
         assert_eq!(medoids.len(), 1);
         assert!(assignments.iter().all(|&a| a == 0));
     }
@@ -172,14 +201,14 @@ mod tests {
     fn test_two_clusters_separated() {
         // Two groups: 0,1,2 close together, 100,101,102 close together
         let n = 6;
-        let points = [0.0, 1.0, 2.0, 100.0, 101.0, 102.0];
+        let points: [f64; 6] = [0.0, 1.0, 2.0, 100.0, 101.0, 102.0];
         let mut dist = vec![0.0; n * n];
         for i in 0..n {
             for j in 0..n {
                 dist[i * n + j] = (points[i] - points[j]).abs();
             }
         }
-        let (_medoids, assignments) = pam_swap(&dist, n, 2, 100, 42);
+        let (_medoids, assignments) = pam_swap(&dist, n, 2, 100, 42, &JobPool::Global, no_interrupt).unwrap();
         // First 3 should be in same cluster, last 3 in another
         assert_eq!(assignments[0], assignments[1]);
         assert_eq!(assignments[1], assignments[2]);
@@ -191,7 +220,7 @@ mod tests {
     #[test]
     fn test_k_equals_n() {
         let dist = make_dist_matrix(3);
-        let (medoids, assignments) = pam_swap(&dist, 3, 3, 100, 42);
+        let (medoids, assignments) = pam_swap(&dist, 3, 3, 100, 42, &JobPool::Global, no_interrupt).unwrap();
         assert_eq!(medoids.len(), 3);
         // Each point is its own medoid
         let mut sorted_assignments: Vec<usize> = assignments.clone();
@@ -0,0 +1,245 @@
+//! k-Shape clustering (Paparrizos & Gravano, 2015).
+//!
+//! Series are z-normalized and zero-padded to a common length. Each iteration
+//! assigns every series to the centroid with the smallest Shape-Based
+//! Distance, then re-extracts each centroid: members are aligned to the
+//! current centroid with the optimal SBD shift and the new centroid is the
+//! dominant eigenvector of `Q^T S Q`, where `S = X^T X` stacks the aligned
+//! members and `Q` centres a vector. The eigenvector is found by power
+//! iteration without materializing the matrix. Assignment and alignment run
+//! in parallel on the threads chosen by `n_jobs`, with the GIL released.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use crate::sbd::sbd_with_shift;
+use crate::utils::JobPool;
+
+/// Power-iteration steps used for shape extraction.
+const POWER_ITER: usize = 100;
+
+/// Z-normalize `x`; a constant series becomes all zeros.
+fn zscore(x: &[f64]) -> Vec<f64> {
+    let n = x.len() as f64;
+    if x.is_empty() {
+        return Vec::new();
+    }
+    let mean = x.iter().sum::<f64>() / n;
+    let std = (x.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+    if std == 0.0 {
+        return vec![0.0; x.len()];
+    }
+    x.iter().map(|v| (v - mean) / std).collect()
+}
+
+/// Shift `x` right by `shift` positions (left when negative), filling with zeros.
+fn shift_series(x: &[f64], shift: isize) -> Vec<f64> {
+    let len = x.len() as isize;
+    (0..len)
+        .map(|i| {
+            let j = i - shift;
+            if (0..len).contains(&j) {
+                x[j as usize]
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+fn centre(v: &mut [f64]) {
+    let mean = v.iter().sum::<f64>() / v.len() as f64;
+    v.iter_mut().for_each(|x| *x -= mean);
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Extract the shape centroid of `members`, aligned against `centroid`.
+fn shape_extraction(members: &[&[f64]], centroid: &[f64], pool: &JobPool) -> Vec<f64> {
+    let len = centroid.len();
+    // An all-zero centroid (the initial state) carries no shape to align to
+    let align = centroid.iter().any(|&c| c != 0.0);
+    let aligned: Vec<Vec<f64>> = pool.map(members, |member| {
+        if align {
+            let (_, shift) = sbd_with_shift(centroid, member);
+            zscore(&shift_series(member, shift))
+        } else {
+            member.to_vec()
+        }
+    });
+
+    // Deterministic start vector from the same LCG as kmedoids
+    let mut state: u64 = 42;
+    let mut vec: Vec<f64> = (0..len)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (state >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+        })
+        .collect();
+
+    for _ in 0..POWER_ITER {
+        // M v = Q S Q v with S v = sum_x x (x . v) and Q v = v - mean(v)
+        let mut q = vec.clone();
+        centre(&mut q);
+        let mut next = vec![0.0; len];
+        for x in &aligned {
+            let w = dot(x, &q);
+            next.iter_mut().zip(x).for_each(|(n, xi)| *n += w * xi);
+        }
+        centre(&mut next);
+        let norm = dot(&next, &next).sqrt();
+        if norm == 0.0 {
+            return vec![0.0; len];
+        }
+        next.iter_mut().for_each(|n| *n /= norm);
+        let change: f64 = next.iter().zip(&vec).map(|(a, b)| (a - b).abs()).sum();
+        vec = next;
+        if change < 1e-12 {
+            break;
+        }
+    }
+
+    // The eigenvector is defined up to sign; keep the one closer to the members
+    let dist = |sign: f64| -> f64 {
+        aligned
+            .iter()
+            .map(|x| x.iter().zip(&vec).map(|(a, v)| (a - sign * v).powi(2)).sum::<f64>())
+            .sum()
+    };
+    if dist(-1.0) < dist(1.0) {
+        vec.iter_mut().for_each(|v| *v = -*v);
+    }
+    zscore(&vec)
+}
+
+/// k-Shape iterations over equal-length, z-normalized `series`.
+///
+/// Starts from round-robin assignments; a cluster that loses all its members
+/// keeps its centroid. `check_interrupt` runs before every iteration; an
+/// error aborts the run.
+fn kshape_loop<C>(
+    series: &[Vec<f64>],
+    k: usize,
+    max_iter: usize,
+    pool: &JobPool,
+    mut check_interrupt: C,
+) -> PyResult<(Vec<usize>, Vec<Vec<f64>>)>
+where
+    C: FnMut() -> PyResult<()>,
+{
+    let len = series.first().map_or(0, Vec::len);
+    let mut assignments: Vec<usize> = (0..series.len()).map(|i| i % k).collect();
+    let mut centroids = vec![vec![0.0; len]; k];
+
+    let update = |centroids: &[Vec<f64>], assignments: &[usize]| -> Vec<Vec<f64>> {
+        centroids
+            .iter()
+            .enumerate()
+            .map(|(ci, centroid)| {
+                let members: Vec<&[f64]> = series
+                    .iter()
+                    .zip(assignments)
+                    .filter(|(_, &a)| a == ci)
+                    .map(|(s, _)| s.as_slice())
+                    .collect();
+                if members.is_empty() {
+                    centroid.clone()
+                } else {
+                    shape_extraction(&members, centroid, pool)
+                }
+            })
+            .collect()
+    };
+    centroids = update(&centroids, &assignments);
+
+    for _ in 0..max_iter {
+        check_interrupt()?;
+        let updated: Vec<usize> = pool.map(series, |s| {
+            centroids
+                .iter()
+                .map(|c| sbd_with_shift(s, c).0)
+                .enumerate()
+                .min_by(|x, y| x.1.total_cmp(&y.1))
+                .map_or(0, |(ci, _)| ci)
+        });
+        if updated == assignments {
+            break;
+        }
+        assignments = updated;
+        centroids = update(&centroids, &assignments);
+    }
+    Ok((assignments, centroids))
+}
+
+/// k-Shape clustering of `series_list`.
+///
+/// Series are z-normalized and zero-padded to the longest length. The loop
+/// runs with the GIL released and checks for Python signals between
+/// iterations; `n_jobs` limits the worker threads (rayon's global pool by
+/// default; 1 is sequential). Returns `(labels, centroids)`.
+#[pyfunction]
+#[pyo3(signature = (series_list, k, max_iter=100, n_jobs=None))]
+pub fn kshape_fit(
+    py: Python<'_>,
+    series_list: Vec<Vec<f64>>,
+    k: usize,
+    max_iter: usize,
+    n_jobs: Option<usize>,
+) -> PyResult<(Vec<usize>, Vec<Vec<f64>>)> {
+    if k < 1 {
+        return Err(PyValueError::new_err("k must be >= 1"));
+    }
+    if k > series_list.len() {
+        return Err(PyValueError::new_err(format!(
+            "Cannot create {k} clusters from {} time series",
+            series_list.len()
+        )));
+    }
+    let len = series_list.iter().map(Vec::len).max().unwrap_or(0);
+    let series: Vec<Vec<f64>> = series_list
+        .iter()
+        .map(|s| {
+            let mut z = zscore(s);
+            z.resize(len, 0.0);
+            z
+        })
+        .collect();
+    let pool = JobPool::new(n_jobs)?;
+    py.allow_threads(|| kshape_loop(&series, k, max_iter, &pool, || Python::with_gil(|py| py.check_signals())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wave(f: fn(f64) -> f64, phase: f64) -> Vec<f64> {
+        zscore(&(0..32).map(|i| f(i as f64 * 0.4 + phase)).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_shift_series() {
+        assert_eq!(shift_series(&[1.0, 2.0, 3.0], 1), vec![0.0, 1.0, 2.0]);
+        assert_eq!(shift_series(&[1.0, 2.0, 3.0], -2), vec![3.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_shape_extraction_of_identical_members() {
+        let s = wave(f64::sin, 0.0);
+        let centroid = shape_extraction(&[&s, &s], &s, &JobPool::Global);
+        for (c, v) in centroid.iter().zip(&s) {
+            assert!((c - v).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_separates_shapes() {
+        let spike = |at: usize| zscore(&(0..32).map(|i| if i == at { 5.0 } else { 0.0 }).collect::<Vec<_>>());
+        let series = vec![wave(f64::sin, 0.0), spike(4), wave(f64::sin, 0.8), spike(20)];
+        let (labels, centroids) = kshape_loop(&series, 2, 20, &JobPool::Sequential, || Ok(())).unwrap();
+        assert_eq!(centroids.len(), 2);
+        assert_eq!(labels[0], labels[2]);
+        assert_eq!(labels[1], labels[3]);
+        assert_ne!(labels[0], labels[1]);
+    }
+}
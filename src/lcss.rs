@@ -1,21 +1,30 @@
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
 
-use crate::utils::compute_pairwise;
+use crate::utils::{compute_pairwise, PairwiseOptions};
 
 /// LCSS distance. Returns 1 - (LCSS_length / min(n, m)). O(m) memory.
-fn lcss_distance(a: &[f64], b: &[f64], epsilon: f64) -> f64 {
+///
+/// With a `window`, points can only be matched when `|i - j| <= window`
+/// (Sakoe-Chiba band, widened to `|n - m|`).
+fn lcss_distance(a: &[f64], b: &[f64], epsilon: f64, window: Option<usize>) -> f64 {
     let n = a.len();
     let m = b.len();
     if n == 0 || m == 0 {
         return 1.0;
     }
+    let w = window.map_or(n.max(m), |w| w.max(n.abs_diff(m)));
 
     let mut prev = vec![0_usize; m + 1];
     let mut curr = vec![0_usize; m + 1];
 
     for i in 1..=n {
-        for j in 1..=m {
+        let j_start = i.saturating_sub(w).max(1);
+        let j_end = (i + w).min(m);
+        // Cells left of the band hold no common subsequence
+        curr[j_start - 1] = 0;
+        for j in j_start..=j_end {
             if (a[i - 1] - b[j - 1]).abs() <= epsilon {
                 curr[j] = prev[j - 1] + 1;
             } else {
@@ -23,9 +32,6 @@ fn lcss_distance(a: &[f64], b: &[f64], epsilon: f64) -> f64 {
             }
         }
         std::mem::swap(&mut prev, &mut curr);
-        for item in curr.iter_mut().take(m + 1) {
-            *item = 0;
-        }
     }
 
     let lcss_len = prev[m] as f64;
@@ -34,14 +40,17 @@ fn lcss_distance(a: &[f64], b: &[f64], epsilon: f64) -> f64 {
 }
 
 #[pyfunction]
-#[pyo3(signature = (input1, input2, epsilon=None))]
+#[pyo3(signature = (input1, input2, epsilon=None, window=None, **options))]
 pub fn compute_pairwise_lcss(
     input1: PyDataFrame,
     input2: PyDataFrame,
     epsilon: Option<f64>,
+    window: Option<usize>,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
     let eps = epsilon.unwrap_or(1.0);
-    compute_pairwise(input1, input2, "lcss", move |a, b| {
-        lcss_distance(a, b, eps)
+    compute_pairwise(input1, input2, "lcss", &options, move |a, b| {
+        lcss_distance(a, b, eps, window)
     })
 }
@@ -0,0 +1,57 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_polars::PyDataFrame;
+
+use crate::pointwise::PointMetric;
+use crate::utils::{compute_pairwise_multivariate, PairwiseOptions};
+
+/// Multivariate LCSS distance: two points match when their distance is at
+/// most `epsilon`. Returns 1 - (LCSS_length / min(n, m)). O(m) memory;
+/// `window` is a Sakoe-Chiba band as in `lcss.rs`.
+fn lcss_distance(a: &[Vec<f64>], b: &[Vec<f64>], epsilon: f64, window: Option<usize>, metric: PointMetric) -> f64 {
+    let n = a.len();
+    let m = b.len();
+    if n == 0 || m == 0 {
+        return 1.0;
+    }
+    let w = window.map_or(n.max(m), |w| w.max(n.abs_diff(m)));
+
+    let mut prev = vec![0_usize; m + 1];
+    let mut curr = vec![0_usize; m + 1];
+
+    for i in 1..=n {
+        let j_start = i.saturating_sub(w).max(1);
+        let j_end = (i + w).min(m);
+        curr[j_start - 1] = 0;
+        for j in j_start..=j_end {
+            if metric.distance(&a[i - 1], &b[j - 1]) <= epsilon {
+                curr[j] = prev[j - 1] + 1;
+            } else {
+                curr[j] = prev[j].max(curr[j - 1]);
+            }
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+
+    let lcss_len = prev[m] as f64;
+    let min_len = n.min(m) as f64;
+    1.0 - (lcss_len / min_len)
+}
+
+#[pyfunction]
+#[pyo3(signature = (input1, input2, epsilon=None, window=None, metric=None, **options))]
+pub fn compute_pairwise_lcss_multi(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    epsilon: Option<f64>,
+    window: Option<usize>,
+    metric: Option<String>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let eps = epsilon.unwrap_or(1.0);
+    let metric = PointMetric::parse(metric.as_deref(), PointMetric::Manhattan)?;
+    compute_pairwise_multivariate(input1, input2, "lcss_multi", &options, move |a, b| {
+        lcss_distance(a, b, eps, window, metric)
+    })
+}
@@ -1,32 +1,66 @@
 use pyo3_polars::PolarsAllocator;
 use pyo3::prelude::*;
 use pyo3::wrap_pyfunction;
-use dtw::compute_pairwise_dtw;
+use dtw::{compute_dtw_alignment, compute_knn_dtw, compute_pairwise_dtw, compute_subsequence_dtw};
 use ddtw::compute_pairwise_ddtw;
 use wdtw::compute_pairwise_wdtw;
+use wddtw::compute_pairwise_wddtw;
+use adtw::compute_pairwise_adtw;
+use shapedtw::compute_pairwise_shapedtw;
+use gak::compute_pairwise_gak;
 use msm::compute_pairwise_msm;
 use dtw_multi::compute_pairwise_dtw_multi;
 use msm_multi::compute_pairwise_msm_multi;
+use erp_multi::compute_pairwise_erp_multi;
+use lcss_multi::compute_pairwise_lcss_multi;
+use edr_multi::compute_pairwise_edr_multi;
+use twe_multi::compute_pairwise_twe_multi;
+use wdtw_multi::compute_pairwise_wdtw_multi;
+use ddtw_multi::compute_pairwise_ddtw_multi;
+use frechet_multi::compute_pairwise_frechet_multi;
 use erp::compute_pairwise_erp;
 use lcss::compute_pairwise_lcss;
 use twe::compute_pairwise_twe;
 use sbd::compute_pairwise_sbd;
 use frechet::compute_pairwise_frechet;
 use edr::compute_pairwise_edr;
+use softdtw::{compute_pairwise_softdtw, softdtw_assign, softdtw_barycenter_grad, softdtw_grad, softdtw_value};
+use matrix_profile::{compute_discords, compute_matrix_profile, compute_motifs};
+use lockstep::{
+    compute_pairwise_chebyshev, compute_pairwise_cid, compute_pairwise_correlation, compute_pairwise_cosine,
+    compute_pairwise_euclidean, compute_pairwise_minkowski,
+};
 
 mod utils;
 mod dtw;
 mod dtw_multi;
 mod msm;
 mod msm_multi;
+mod pointwise;
+mod erp_multi;
+mod lcss_multi;
+mod edr_multi;
+mod twe_multi;
+mod wdtw_multi;
+mod ddtw_multi;
+mod frechet_multi;
 mod ddtw;
 mod wdtw;
+mod wddtw;
+mod adtw;
+mod shapedtw;
+mod gak;
 mod erp;
 mod lcss;
 mod twe;
 mod sbd;
 mod frechet;
 mod edr;
+mod softdtw;
+mod lockstep;
+mod dba;
+mod kshape;
+mod matrix_profile;
 mod mann_kendall;
 mod sens_slope;
 mod ets;
@@ -41,21 +75,52 @@ static ALLOC: PolarsAllocator = PolarsAllocator::new();
 #[pyo3(name = "polars_ts_rs")]
 fn polars_ts_rs(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(compute_pairwise_dtw, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_dtw_alignment, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_knn_dtw, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_subsequence_dtw, m)?)?;
     m.add_function(wrap_pyfunction!(compute_pairwise_msm, m)?)?;
     m.add_function(wrap_pyfunction!(compute_pairwise_ddtw, m)?)?;
     m.add_function(wrap_pyfunction!(compute_pairwise_wdtw, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_wddtw, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_adtw, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_shapedtw, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_gak, m)?)?;
     m.add_function(wrap_pyfunction!(compute_pairwise_dtw_multi, m)?)?;
     m.add_function(wrap_pyfunction!(compute_pairwise_msm_multi, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_erp_multi, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_lcss_multi, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_edr_multi, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_twe_multi, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_wdtw_multi, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_ddtw_multi, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_frechet_multi, m)?)?;
     m.add_function(wrap_pyfunction!(compute_pairwise_erp, m)?)?;
     m.add_function(wrap_pyfunction!(compute_pairwise_lcss, m)?)?;
     m.add_function(wrap_pyfunction!(compute_pairwise_twe, m)?)?;
     m.add_function(wrap_pyfunction!(compute_pairwise_sbd, m)?)?;
     m.add_function(wrap_pyfunction!(compute_pairwise_frechet, m)?)?;
     m.add_function(wrap_pyfunction!(compute_pairwise_edr, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_softdtw, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_euclidean, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_minkowski, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_chebyshev, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_correlation, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_cid, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_pairwise_cosine, m)?)?;
+    m.add_function(wrap_pyfunction!(softdtw_grad, m)?)?;
+    m.add_function(wrap_pyfunction!(softdtw_value, m)?)?;
+    m.add_function(wrap_pyfunction!(softdtw_assign, m)?)?;
+    m.add_function(wrap_pyfunction!(softdtw_barycenter_grad, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_matrix_profile, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_motifs, m)?)?;
+    m.add_function(wrap_pyfunction!(compute_discords, m)?)?;
     m.add_function(wrap_pyfunction!(ets::ets_ses, m)?)?;
     m.add_function(wrap_pyfunction!(ets::ets_holt, m)?)?;
     m.add_function(wrap_pyfunction!(ets::ets_holt_winters, m)?)?;
     m.add_function(wrap_pyfunction!(kmedoids::kmedoids_pam, m)?)?;
+    m.add_function(wrap_pyfunction!(dba::dba, m)?)?;
+    m.add_function(wrap_pyfunction!(dba::kmeans_dba_fit, m)?)?;
+    m.add_function(wrap_pyfunction!(kshape::kshape_fit, m)?)?;
     m.add_function(wrap_pyfunction!(pelt::pelt, m)?)?;
     Ok(())
 }
@@ -0,0 +1,301 @@
+//! Lock-step distances, which compare observation `i` of one series only
+//! with observation `i` of the other.
+//!
+//! All of them need equal-length series. By default a pair of inputs with
+//! different lengths is rejected; `unequal_length="resample"` instead
+//! linearly interpolates the shorter series of each pair onto the length of
+//! the longer one.
+
+use std::borrow::Cow;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_polars::PyDataFrame;
+
+use crate::utils::{prepare_univariate, run_pairwise, PairwiseInput, PairwiseOptions};
+
+/// How lock-step kernels treat series of different lengths.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum UnequalLength {
+    Error,
+    Resample,
+}
+
+impl UnequalLength {
+    fn parse(name: Option<&str>) -> PyResult<Self> {
+        match name {
+            None | Some("error") => Ok(UnequalLength::Error),
+            Some("resample") => Ok(UnequalLength::Resample),
+            Some(other) => Err(PyValueError::new_err(format!(
+                "Unknown unequal_length policy: '{other}'. Expected one of: error, resample"
+            ))),
+        }
+    }
+}
+
+/// Linearly interpolate `x` onto `len >= x.len()` evenly spaced positions
+/// spanning the same first and last observation.
+fn resample(x: &[f64], len: usize) -> Vec<f64> {
+    match x.len() {
+        0 => vec![0.0; len],
+        1 => vec![x[0]; len],
+        n => (0..len)
+            .map(|i| {
+                let t = i as f64 * (n - 1) as f64 / (len - 1) as f64;
+                let lo = (t.floor() as usize).min(n - 2);
+                let frac = t - lo as f64;
+                x[lo] + frac * (x[lo + 1] - x[lo])
+            })
+            .collect(),
+    }
+}
+
+/// Reject inputs whose series do not all share one length.
+fn check_equal_lengths(input: &PairwiseInput<Vec<f64>>) -> PyResult<()> {
+    let mut series = input
+        .ids_a
+        .iter()
+        .map(|id| (id, &input.map_a[id]))
+        .chain(input.ids_b.iter().map(|id| (id, &input.map_b[id])));
+    let Some((first_id, first)) = series.next() else {
+        return Ok(());
+    };
+    if let Some((id, other)) = series.find(|(_, s)| s.len() != first.len()) {
+        return Err(PyValueError::new_err(format!(
+            "Lock-step distances need equal-length series, but series {first_id} has {} observations \
+             and series {id} has {}. Pass unequal_length='resample' to interpolate the shorter series.",
+            first.len(),
+            other.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Shared entry point: prepare the inputs, apply the unequal-length policy
+/// and evaluate `kernel` on every pair.
+fn compute_lockstep<F>(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    distance_col: &str,
+    options: &PairwiseOptions,
+    unequal_length: Option<&str>,
+    kernel: F,
+) -> PyResult<PyDataFrame>
+where
+    F: Fn(&[f64], &[f64]) -> f64 + Send + Sync,
+{
+    let policy = UnequalLength::parse(unequal_length)?;
+    let input = prepare_univariate(input1, input2, options)?;
+    if policy == UnequalLength::Error {
+        check_equal_lengths(&input)?;
+    }
+    run_pairwise(&input, distance_col, options, |a: &Vec<f64>, b: &Vec<f64>| {
+        let len = a.len().max(b.len());
+        let a = if a.len() < len { Cow::Owned(resample(a, len)) } else { Cow::Borrowed(a.as_slice()) };
+        let b = if b.len() < len { Cow::Owned(resample(b, len)) } else { Cow::Borrowed(b.as_slice()) };
+        kernel(&a, &b)
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Kernels
+// ---------------------------------------------------------------------------
+
+fn euclidean_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt()
+}
+
+fn minkowski_distance(a: &[f64], b: &[f64], p: f64) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).abs().powf(p)).sum::<f64>().powf(1.0 / p)
+}
+
+fn chebyshev_distance(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| (x - y).abs()).fold(0.0, f64::max)
+}
+
+/// `1 - r` with `r` the Pearson correlation, in [0, 2]. A constant series
+/// has no defined correlation: two constant series are at distance 0 and a
+/// constant series is at distance 1 (uncorrelated) from any other.
+fn correlation_distance(a: &[f64], b: &[f64]) -> f64 {
+    let n = a.len() as f64;
+    let mean_a = a.iter().sum::<f64>() / n;
+    let mean_b = b.iter().sum::<f64>() / n;
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        let (da, db) = (x - mean_a, y - mean_b);
+        cov += da * db;
+        var_a += da * da;
+        var_b += db * db;
+    }
+    match (var_a == 0.0, var_b == 0.0) {
+        (true, true) => 0.0,
+        (true, false) | (false, true) => 1.0,
+        (false, false) => (1.0 - cov / (var_a * var_b).sqrt()).max(0.0),
+    }
+}
+
+/// Complexity estimate of Batista et al. (2014): the length of the line
+/// obtained by stretching the series out.
+fn complexity(x: &[f64]) -> f64 {
+    x.windows(2).map(|w| (w[1] - w[0]).powi(2)).sum::<f64>().sqrt()
+}
+
+/// Complexity-Invariant Distance: Euclidean distance scaled by
+/// `max(CE(a), CE(b)) / min(CE(a), CE(b))`. The factor is 1 when either
+/// series is constant, where it would otherwise be undefined.
+fn cid_distance(a: &[f64], b: &[f64]) -> f64 {
+    let (ce_a, ce_b) = (complexity(a), complexity(b));
+    let factor = if ce_a == 0.0 || ce_b == 0.0 { 1.0 } else { ce_a.max(ce_b) / ce_a.min(ce_b) };
+    euclidean_distance(a, b) * factor
+}
+
+/// `1 - cos(a, b)`, in [0, 2]. An all-zero series has no direction: two of
+/// them are at distance 0 and one is at distance 1 from any other series.
+fn cosine_distance(a: &[f64], b: &[f64]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f64>().sqrt();
+    match (norm_a == 0.0, norm_b == 0.0) {
+        (true, true) => 0.0,
+        (true, false) | (false, true) => 1.0,
+        (false, false) => (1.0 - dot / (norm_a * norm_b)).max(0.0),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Pairwise wrappers
+// ---------------------------------------------------------------------------
+
+/// Pairwise Euclidean distances.
+///
+/// `unequal_length` is `"error"` (default) or `"resample"`, as for every
+/// lock-step distance.
+#[pyfunction]
+#[pyo3(signature = (input1, input2, unequal_length=None, **options))]
+pub fn compute_pairwise_euclidean(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    unequal_length: Option<&str>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    compute_lockstep(input1, input2, "euclidean", &options, unequal_length, euclidean_distance)
+}
+
+/// Pairwise Minkowski distances of order `p` (default 2, Euclidean).
+#[pyfunction]
+#[pyo3(signature = (input1, input2, p=None, unequal_length=None, **options))]
+pub fn compute_pairwise_minkowski(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    p: Option<f64>,
+    unequal_length: Option<&str>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let p = p.unwrap_or(2.0);
+    if !(p >= 1.0 && p.is_finite()) {
+        return Err(PyValueError::new_err(
+            "p must be a finite number >= 1; use compute_pairwise_chebyshev for p = inf",
+        ));
+    }
+    compute_lockstep(input1, input2, "minkowski", &options, unequal_length, move |a, b| {
+        minkowski_distance(a, b, p)
+    })
+}
+
+/// Pairwise Chebyshev (maximum absolute difference) distances.
+#[pyfunction]
+#[pyo3(signature = (input1, input2, unequal_length=None, **options))]
+pub fn compute_pairwise_chebyshev(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    unequal_length: Option<&str>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    compute_lockstep(input1, input2, "chebyshev", &options, unequal_length, chebyshev_distance)
+}
+
+/// Pairwise Pearson-correlation distances, `1 - r`.
+#[pyfunction]
+#[pyo3(signature = (input1, input2, unequal_length=None, **options))]
+pub fn compute_pairwise_correlation(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    unequal_length: Option<&str>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    compute_lockstep(input1, input2, "correlation", &options, unequal_length, correlation_distance)
+}
+
+/// Pairwise Complexity-Invariant Distances.
+#[pyfunction]
+#[pyo3(signature = (input1, input2, unequal_length=None, **options))]
+pub fn compute_pairwise_cid(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    unequal_length: Option<&str>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    compute_lockstep(input1, input2, "cid", &options, unequal_length, cid_distance)
+}
+
+/// Pairwise cosine distances, `1 - cos(a, b)`.
+#[pyfunction]
+#[pyo3(signature = (input1, input2, unequal_length=None, **options))]
+pub fn compute_pairwise_cosine(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    unequal_length: Option<&str>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    compute_lockstep(input1, input2, "cosine", &options, unequal_length, cosine_distance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resample() {
+        assert_eq!(resample(&[0.0, 2.0], 5), vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+        assert_eq!(resample(&[1.0, 3.0, 2.0], 3), vec![1.0, 3.0, 2.0]);
+        assert_eq!(resample(&[4.0], 3), vec![4.0; 3]);
+    }
+
+    #[test]
+    fn test_kernels() {
+        let a = [0.0, 0.0, 0.0];
+        let b = [3.0, 4.0, 0.0];
+        assert_eq!(euclidean_distance(&a, &b), 5.0);
+        assert_eq!(minkowski_distance(&a, &b, 1.0), 7.0);
+        assert!((minkowski_distance(&a, &b, 2.0) - 5.0).abs() < 1e-12);
+        assert_eq!(chebyshev_distance(&a, &b), 4.0);
+        assert_eq!(cosine_distance(&[1.0, 0.0], &[0.0, 2.0]), 1.0);
+        assert!(cosine_distance(&[1.0, 1.0], &[2.0, 2.0]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_correlation() {
+        let a = [1.0, 2.0, 3.0];
+        assert!(correlation_distance(&a, &[2.0, 4.0, 6.0]).abs() < 1e-12);
+        assert!((correlation_distance(&a, &[3.0, 2.0, 1.0]) - 2.0).abs() < 1e-12);
+        assert_eq!(correlation_distance(&a, &[5.0, 5.0, 5.0]), 1.0);
+        assert_eq!(correlation_distance(&[5.0; 3], &[1.0; 3]), 0.0);
+    }
+
+    #[test]
+    fn test_cid_scales_by_complexity() {
+        let smooth = [0.0, 1.0, 2.0, 3.0];
+        let jagged = [0.0, 3.0, 0.0, 3.0];
+        let ed = euclidean_distance(&smooth, &jagged);
+        let factor = complexity(&jagged) / complexity(&smooth);
+        assert!((cid_distance(&smooth, &jagged) - ed * factor).abs() < 1e-12);
+        assert_eq!(cid_distance(&[1.0; 4], &smooth), euclidean_distance(&[1.0; 4], &smooth));
+    }
+}
@@ -0,0 +1,489 @@
+//! Matrix profile of long series for motif and discord discovery.
+//!
+//! For every length-`window` subsequence of a series the matrix profile holds
+//! the z-normalized Euclidean distance to its nearest neighbour, and the
+//! profile index holds where that neighbour starts. A self-join searches the
+//! series itself, skipping an exclusion zone around each subsequence so that
+//! trivial matches do not count; an AB-join searches the series with the same
+//! id in a second frame. Distances are computed with STOMP: the sliding dot
+//! products of row `i` are updated from row `i - 1` in O(1) each, so a join
+//! costs O(n_a * n_b). Rows are split into blocks that run in parallel on the
+//! `n_jobs` pool with the GIL released, each block seeding its first row
+//! directly.
+//!
+//! Motifs are the pairs with the smallest profile values and discords the
+//! subsequences with the largest; both are picked greedily, excluding the
+//! neighbourhood of every subsequence already reported.
+
+use polars::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_polars::PyDataFrame;
+use crate::utils::{load_univariate, par_map_interruptible, PairwiseOptions};
+
+/// Rows of the profile computed from one directly seeded dot-product row.
+const BLOCK_ROWS: usize = 256;
+
+/// Matrix profile of one series: nearest-neighbour distance and start index
+/// of every subsequence. Subsequences without any admissible neighbour keep
+/// an infinite distance and no index.
+struct Profile {
+    distances: Vec<f64>,
+    indices: Vec<Option<usize>>,
+}
+
+/// Mean and standard deviation of every length-`window` subsequence.
+fn sliding_stats(x: &[f64], window: usize) -> Vec<(f64, f64)> {
+    x.windows(window)
+        .map(|w| {
+            let mean = w.iter().sum::<f64>() / window as f64;
+            let std = (w.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / window as f64).sqrt();
+            // Rounding leaves a tiny spread on constant windows
+            (mean, if std < 1e-12 * (1.0 + mean.abs()) { 0.0 } else { std })
+        })
+        .collect()
+}
+
+/// z-normalized Euclidean distance from a dot product and window statistics.
+/// A constant window is at distance 0 from another constant window and at
+/// `sqrt(window)` from anything else.
+fn znorm_distance(qt: f64, (mean_a, std_a): (f64, f64), (mean_b, std_b): (f64, f64), window: usize) -> f64 {
+    let m = window as f64;
+    match (std_a == 0.0, std_b == 0.0) {
+        (true, true) => 0.0,
+        (true, false) | (false, true) => m.sqrt(),
+        (false, false) => {
+            let corr = (qt - m * mean_a * mean_b) / (m * std_a * std_b);
+            (2.0 * m * (1.0 - corr)).max(0.0).sqrt()
+        }
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+impl Profile {
+    /// Concatenate the rows of consecutive blocks.
+    fn from_blocks(blocks: impl IntoIterator<Item = Vec<(f64, Option<usize>)>>) -> Self {
+        let (distances, indices) = blocks.into_iter().flatten().unzip();
+        Profile { distances, indices }
+    }
+}
+
+/// STOMP join of the subsequences of `a` against those of `b`.
+///
+/// `exclusion` marks a self-join: neighbours with `|i - j| <= exclusion` are skipped.
+struct Join<'a> {
+    a: &'a [f64],
+    b: &'a [f64],
+    window: usize,
+    exclusion: Option<usize>,
+    stats_a: Vec<(f64, f64)>,
+    stats_b: Vec<(f64, f64)>,
+}
+
+impl<'a> Join<'a> {
+    fn new(a: &'a [f64], b: &'a [f64], window: usize, exclusion: Option<usize>) -> Self {
+        let stats_a = sliding_stats(a, window);
+        let stats_b = sliding_stats(b, window);
+        Join { a, b, window, exclusion, stats_a, stats_b }
+    }
+
+    /// First row of every block.
+    fn block_starts(&self) -> impl Iterator<Item = usize> {
+        (0..self.stats_a.len()).step_by(BLOCK_ROWS)
+    }
+
+    /// Nearest neighbour of the rows `start..start + BLOCK_ROWS`.
+    fn block(&self, start: usize) -> Vec<(f64, Option<usize>)> {
+        let (a, b, window) = (self.a, self.b, self.window);
+        let n_b = self.stats_b.len();
+        let end = (start + BLOCK_ROWS).min(self.stats_a.len());
+        let mut qt: Vec<f64> = (0..n_b).map(|j| dot(&a[start..start + window], &b[j..j + window])).collect();
+        let mut block = Vec::with_capacity(end - start);
+        for i in start..end {
+            if i > start {
+                for j in (1..n_b).rev() {
+                    qt[j] = qt[j - 1] - a[i - 1] * b[j - 1] + a[i + window - 1] * b[j + window - 1];
+                }
+                qt[0] = dot(&a[i..i + window], &b[..window]);
+            }
+            let mut best = (f64::INFINITY, None);
+            for (j, &q) in qt.iter().enumerate() {
+                if self.exclusion.is_some_and(|e| i.abs_diff(j) <= e) {
+                    continue;
+                }
+                let d = znorm_distance(q, self.stats_a[i], self.stats_b[j], window);
+                if d < best.0 {
+                    best = (d, Some(j));
+                }
+            }
+            block.push(best);
+        }
+        block
+    }
+}
+
+/// Mark the subsequences within `exclusion` of `at` as taken.
+fn exclude_around(excluded: &mut [bool], at: usize, exclusion: usize) {
+    let lo = at.saturating_sub(exclusion);
+    let hi = (at + exclusion + 1).min(excluded.len());
+    excluded[lo..hi].iter_mut().for_each(|e| *e = true);
+}
+
+/// Greedy motif pairs: `(idx, neighbor_idx, distance)` in increasing distance.
+fn top_motifs(profile: &Profile, k: usize, exclusion: usize, self_join: bool) -> Vec<(usize, usize, f64)> {
+    let mut excluded = vec![false; profile.distances.len()];
+    let mut motifs = Vec::with_capacity(k);
+    while motifs.len() < k {
+        let best = profile
+            .distances
+            .iter()
+            .zip(&profile.indices)
+            .enumerate()
+            .filter(|(i, (d, j))| !excluded[*i] && d.is_finite() && j.is_some())
+            .min_by(|x, y| x.1 .0.total_cmp(y.1 .0));
+        let Some((idx, (&distance, &Some(neighbor)))) = best else {
+            break;
+        };
+        motifs.push((idx, neighbor, distance));
+        exclude_around(&mut excluded, idx, exclusion);
+        if self_join {
+            exclude_around(&mut excluded, neighbor, exclusion);
+        }
+    }
+    motifs
+}
+
+/// Greedy discords: `(idx, distance)` in decreasing distance.
+fn top_discords(profile: &Profile, k: usize, exclusion: usize) -> Vec<(usize, f64)> {
+    let mut excluded = vec![false; profile.distances.len()];
+    let mut discords = Vec::with_capacity(k);
+    while discords.len() < k {
+        let best = profile
+            .distances
+            .iter()
+            .enumerate()
+            .filter(|(i, d)| !excluded[*i] && d.is_finite())
+            .max_by(|x, y| x.1.total_cmp(y.1));
+        let Some((idx, &distance)) = best else {
+            break;
+        };
+        discords.push((idx, distance));
+        exclude_around(&mut excluded, idx, exclusion);
+    }
+    discords
+}
+
+/// Per-series matrix profiles of `df`, in id order.
+struct Profiles {
+    profiles: Vec<(String, Profile)>,
+    uid_dtype: DataType,
+    id_col: String,
+    exclusion: usize,
+    self_join: bool,
+}
+
+fn compute_profiles(
+    df: PyDataFrame,
+    window: usize,
+    other: Option<PyDataFrame>,
+    exclusion_zone: Option<usize>,
+    options: &PairwiseOptions,
+) -> PyResult<Profiles> {
+    if window < 2 {
+        return Err(PyValueError::new_err(format!("window must be >= 2, got {window}")));
+    }
+    let exclusion = exclusion_zone.unwrap_or(window.div_ceil(4));
+    let (map_a, ids, uid_dtype) = load_univariate(&df.into(), options)?;
+    let map_b = other.map(|o| load_univariate(&o.into(), options)).transpose()?.map(|(map, _, _)| map);
+
+    let check_len = |id: &str, series: &[f64]| -> PyResult<()> {
+        if series.len() < window {
+            return Err(PyValueError::new_err(format!(
+                "Series {id} has {} observations, fewer than window {window}",
+                series.len()
+            )));
+        }
+        Ok(())
+    };
+
+    let joins = ids
+        .iter()
+        .map(|id| {
+            let a = &map_a[id];
+            check_len(id, a)?;
+            match &map_b {
+                Some(map_b) => {
+                    let b = map_b
+                        .get(id)
+                        .ok_or_else(|| PyValueError::new_err(format!("Series {id} is missing from other")))?;
+                    check_len(id, b)?;
+                    Ok(Join::new(a, b, window, None))
+                }
+                None => Ok(Join::new(a, a, window, Some(exclusion))),
+            }
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+
+    // Blocks of all series share one pool, so many short series parallelize
+    // as well as one long one
+    let blocks: Vec<(usize, usize)> = joins
+        .iter()
+        .enumerate()
+        .flat_map(|(series, join)| join.block_starts().map(move |start| (series, start)))
+        .collect();
+    let pool = options.job_pool()?;
+    let mut rows = par_map_interruptible(&pool, &blocks, |&(series, start)| joins[series].block(start))?.into_iter();
+    let profiles = ids
+        .iter()
+        .zip(&joins)
+        .map(|(id, join)| (id.clone(), Profile::from_blocks(rows.by_ref().take(join.block_starts().count()))))
+        .collect();
+
+    Ok(Profiles {
+        profiles,
+        uid_dtype,
+        id_col: options.columns.id_col.clone(),
+        self_join: map_b.is_none(),
+        exclusion,
+    })
+}
+
+/// Id column repeated per row, cast back to the original id dtype.
+fn id_column(name: &str, ids: Vec<&str>, dtype: &DataType) -> PyResult<Column> {
+    Column::new(name.into(), ids)
+        .cast(dtype)
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Matrix profile of every series in `df`.
+///
+/// Returns one row per subsequence with its start `idx`, the z-normalized
+/// Euclidean distance to its nearest neighbour (`profile`) and where that
+/// neighbour starts (`profile_index`, null when there is none). Without
+/// `other` this is a self-join that skips neighbours within `exclusion_zone`
+/// (default `ceil(window / 4)`) of each subsequence; with `other` each series
+/// is joined against the series with the same id in `other`.
+///
+/// Accepts the shared `id_col`, `target_col`, `time_col`, `normalize`,
+/// `missing` and `n_jobs` options; the pair-table options (`k`, `output`,
+/// `mode`, `sink_parquet`) raise a `ValueError`.
+#[pyfunction]
+#[pyo3(signature = (df, window, other=None, exclusion_zone=None, **options))]
+pub fn compute_matrix_profile(
+    df: PyDataFrame,
+    window: usize,
+    other: Option<PyDataFrame>,
+    exclusion_zone: Option<usize>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    options.ensure_default_output("compute_matrix_profile")?;
+    options.ensure_default_mode("compute_matrix_profile")?;
+    let result = compute_profiles(df, window, other, exclusion_zone, &options)?;
+
+    let mut ids = Vec::new();
+    let mut idx = Vec::new();
+    let mut distances = Vec::new();
+    let mut indices = Vec::new();
+    for (id, profile) in &result.profiles {
+        for (i, (&d, &j)) in profile.distances.iter().zip(&profile.indices).enumerate() {
+            ids.push(id.as_str());
+            idx.push(i as i64);
+            distances.push(d);
+            indices.push(j.map(|j| j as i64));
+        }
+    }
+
+    DataFrame::new(vec![
+        id_column(&result.id_col, ids, &result.uid_dtype)?,
+        Column::new("idx".into(), idx),
+        Column::new("profile".into(), distances),
+        Column::new("profile_index".into(), indices),
+    ])
+    .map(PyDataFrame)
+    .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Top-`k` motifs of every series in `df`.
+///
+/// Each row is a pair of closest subsequences: `idx` and `neighbor_idx` are
+/// their start indices and `distance` their z-normalized distance; `rank` is
+/// 1-based. After a motif is reported, subsequences within the exclusion zone
+/// of either member are no longer considered. Takes the same arguments as
+/// `compute_matrix_profile`.
+#[pyfunction]
+#[pyo3(signature = (df, window, k=3, other=None, exclusion_zone=None, **options))]
+pub fn compute_motifs(
+    df: PyDataFrame,
+    window: usize,
+    k: usize,
+    other: Option<PyDataFrame>,
+    exclusion_zone: Option<usize>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    options.ensure_default_output("compute_motifs")?;
+    options.ensure_default_mode("compute_motifs")?;
+    let result = compute_profiles(df, window, other, exclusion_zone, &options)?;
+
+    let (mut ids, mut ranks, mut idx, mut neighbors, mut distances) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for (id, profile) in &result.profiles {
+        for (rank, (i, j, d)) in top_motifs(profile, k, result.exclusion, result.self_join).into_iter().enumerate() {
+            ids.push(id.as_str());
+            ranks.push(rank as i64 + 1);
+            idx.push(i as i64);
+            neighbors.push(j as i64);
+            distances.push(d);
+        }
+    }
+
+    DataFrame::new(vec![
+        id_column(&result.id_col, ids, &result.uid_dtype)?,
+        Column::new("rank".into(), ranks),
+        Column::new("idx".into(), idx),
+        Column::new("neighbor_idx".into(), neighbors),
+        Column::new("distance".into(), distances),
+    ])
+    .map(PyDataFrame)
+    .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Top-`k` discords of every series in `df`.
+///
+/// Each row is a subsequence far from all others: `idx` is its start index
+/// and `distance` its matrix-profile value; `rank` is 1-based. Subsequences
+/// within the exclusion zone of a reported discord are no longer considered.
+/// Takes the same arguments as `compute_matrix_profile`.
+#[pyfunction]
+#[pyo3(signature = (df, window, k=1, other=None, exclusion_zone=None, **options))]
+pub fn compute_discords(
+    df: PyDataFrame,
+    window: usize,
+    k: usize,
+    other: Option<PyDataFrame>,
+    exclusion_zone: Option<usize>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    options.ensure_default_output("compute_discords")?;
+    options.ensure_default_mode("compute_discords")?;
+    let result = compute_profiles(df, window, other, exclusion_zone, &options)?;
+
+    let (mut ids, mut ranks, mut idx, mut distances) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for (id, profile) in &result.profiles {
+        for (rank, (i, d)) in top_discords(profile, k, result.exclusion).into_iter().enumerate() {
+            ids.push(id.as_str());
+            ranks.push(rank as i64 + 1);
+            idx.push(i as i64);
+            distances.push(d);
+        }
+    }
+
+    DataFrame::new(vec![
+        id_column(&result.id_col, ids, &result.uid_dtype)?,
+        Column::new("rank".into(), ranks),
+        Column::new("idx".into(), idx),
+        Column::new("distance".into(), distances),
+    ])
+    .map(PyDataFrame)
+    .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stomp(a: &[f64], b: &[f64], window: usize, exclusion: Option<usize>) -> Profile {
+        let join = Join::new(a, b, window, exclusion);
+        Profile::from_blocks(join.block_starts().map(|start| join.block(start)))
+    }
+
+    /// Brute-force z-normalized distance between two subsequences.
+    fn naive_distance(a: &[f64], b: &[f64]) -> f64 {
+        let z = |x: &[f64]| -> Vec<f64> {
+            let n = x.len() as f64;
+            let mean = x.iter().sum::<f64>() / n;
+            let std = (x.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+            x.iter().map(|v| (v - mean) / std).collect()
+        };
+        z(a).iter().zip(z(b)).map(|(x, y)| (x - y).powi(2)).sum::<f64>().sqrt()
+    }
+
+    fn noisy_series(n: usize) -> Vec<f64> {
+        let mut state: u64 = 7;
+        (0..n)
+            .map(|i| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (i as f64 * 0.3).sin() + (state >> 11) as f64 / (1u64 << 53) as f64
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_self_join_matches_brute_force() {
+        let x = noisy_series(600);
+        let window: usize = 16;
+        let exclusion: usize = 4;
+        let profile = stomp(&x, &x, window, Some(exclusion));
+        for i in [0usize, 100, 255, 256, 257, 584] {
+            let (mut best, mut best_j) = (f64::INFINITY, None);
+            for j in 0..=x.len() - window {
+                if i.abs_diff(j) <= exclusion {
+                    continue;
+                }
+                let d = naive_distance(&x[i..i + window], &x[j..j + window]);
+                if d < best {
+                    (best, best_j) = (d, Some(j));
+                }
+            }
+            assert!((profile.distances[i] - best).abs() < 1e-8, "row {i}");
+            assert_eq!(profile.indices[i], best_j);
+        }
+    }
+
+    #[test]
+    fn test_ab_join_finds_planted_pattern() {
+        let mut a = vec![0.0; 40];
+        let mut b = vec![1.0; 60];
+        let pattern = [0.0, 2.0, 5.0, 2.0, 0.0, -3.0];
+        a[10..16].copy_from_slice(&pattern);
+        b[33..39].copy_from_slice(&pattern.map(|v| 3.0 * v + 1.0));
+        let profile = stomp(&a, &b, 6, None);
+        assert!(profile.distances[10] < 1e-6);
+        assert_eq!(profile.indices[10], Some(33));
+    }
+
+    #[test]
+    fn test_motifs_and_discords() {
+        let mut x: Vec<f64> = (0..200).map(|i| (i as f64 * std::f64::consts::TAU / 20.0).sin()).collect();
+        // A spike is the obvious discord
+        x[120] += 4.0;
+        let window: usize = 10;
+        let exclusion = window.div_ceil(4);
+        let profile = stomp(&x, &x, window, Some(exclusion));
+
+        let discords = top_discords(&profile, 2, exclusion);
+        assert_eq!(discords.len(), 2);
+        assert!((111..=120).contains(&discords[0].0));
+        assert!(discords[0].1 >= discords[1].1);
+
+        let motifs = top_motifs(&profile, 3, exclusion, true);
+        assert_eq!(motifs.len(), 3);
+        assert!(motifs.windows(2).all(|w| w[0].2 <= w[1].2));
+        assert!(motifs[0].2 < 1e-3);
+    }
+
+    #[test]
+    fn test_constant_windows() {
+        let x = [1.0, 1.0, 1.0, 1.0, 2.0, 3.0, 1.0, 1.0, 1.0, 1.0];
+        let profile = stomp(&x, &x, 3, Some(1));
+        // The two flat stretches match each other exactly
+        assert_eq!(profile.distances[0], 0.0);
+        assert!(profile.indices[0].is_some_and(|j| j >= 6));
+    }
+}
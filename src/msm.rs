@@ -1,7 +1,8 @@
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
 
-use crate::utils::compute_pairwise;
+use crate::utils::{compute_pairwise, PairwiseOptions};
 
 /// Helper function to calculate the MSM cost.
 fn msm_cost(x: f64, y: f64, z: f64, c: f64) -> f64 {
@@ -12,24 +13,33 @@ fn msm_cost(x: f64, y: f64, z: f64, c: f64) -> f64 {
 }
 
 /// Optimized MSM distance using O(m) memory.
-fn msm_distance(a: &[f64], b: &[f64], c: f64) -> f64 {
+///
+/// With a `window`, only cells with `|i - j| <= window` are filled (Sakoe-Chiba
+/// band, widened to `|n - m|`).
+fn msm_distance(a: &[f64], b: &[f64], c: f64, window: Option<usize>) -> f64 {
     let n = a.len();
     let m = b.len();
     if n == 0 || m == 0 {
         return 0.0;
     }
+    let w = window.map_or(n.max(m), |w| w.max(n.abs_diff(m)));
 
     let mut prev = vec![f64::MAX; m];
     let mut curr = vec![f64::MAX; m];
     prev[0] = (a[0] - b[0]).abs();
 
-    for j in 1..m {
+    for j in 1..m.min(w + 1) {
         prev[j] = prev[j - 1] + msm_cost(b[j], a[0], b[j - 1], c);
     }
 
     for i in 1..n {
-        curr[0] = prev[0] + msm_cost(a[i], a[i - 1], b[0], c);
-        for j in 1..m {
+        curr[0] = if i <= w { prev[0] + msm_cost(a[i], a[i - 1], b[0], c) } else { f64::MAX };
+        let j_start = i.saturating_sub(w).max(1);
+        let j_end = (i + w).min(m - 1);
+        if j_start > 1 {
+            curr[j_start - 1] = f64::MAX;
+        }
+        for j in j_start..=j_end {
             let d1 = prev[j - 1] + (a[i] - b[j]).abs();
             let d2 = prev[j] + msm_cost(a[i], a[i - 1], b[j], c);
             let d3 = curr[j - 1] + msm_cost(b[j], a[i], b[j - 1], c);
@@ -41,14 +51,17 @@ fn msm_distance(a: &[f64], b: &[f64], c: f64) -> f64 {
 }
 
 #[pyfunction]
-#[pyo3(signature = (input1, input2, c=None))]
+#[pyo3(signature = (input1, input2, c=None, window=None, **options))]
 pub fn compute_pairwise_msm(
     input1: PyDataFrame,
     input2: PyDataFrame,
     c: Option<f64>,
+    window: Option<usize>,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
     let c_value = c.unwrap_or(1.0);
-    compute_pairwise(input1, input2, "msm", move |a, b| {
-        msm_distance(a, b, c_value)
+    compute_pairwise(input1, input2, "msm", &options, move |a, b| {
+        msm_distance(a, b, c_value, window)
     })
 }
@@ -1,7 +1,8 @@
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
 
-use crate::utils::compute_pairwise_multivariate;
+use crate::utils::{compute_pairwise_multivariate, PairwiseOptions};
 
 /// Compute Manhattan distance between two vectors.
 fn manhattan_distance(a: &[f64], b: &[f64]) -> f64 {
@@ -58,14 +59,16 @@ fn msm_distance(a: &[Vec<f64>], b: &[Vec<f64>], c: f64) -> f64 {
 }
 
 #[pyfunction]
-#[pyo3(signature = (input1, input2, c=None))]
+#[pyo3(signature = (input1, input2, c=None, **options))]
 pub fn compute_pairwise_msm_multi(
     input1: PyDataFrame,
     input2: PyDataFrame,
     c: Option<f64>,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
     let c_value = c.unwrap_or(1.0);
-    compute_pairwise_multivariate(input1, input2, "msm_multi", move |a, b| {
+    compute_pairwise_multivariate(input1, input2, "msm_multi", &options, move |a, b| {
         msm_distance(a, b, c_value)
     })
 }
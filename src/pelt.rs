@@ -7,7 +7,8 @@
 use polars::prelude::*;
 use pyo3::prelude::*;
 use pyo3_polars::PyDataFrame;
-use rayon::prelude::*;
+
+use crate::utils::{par_map_interruptible, JobPool, MissingPolicy};
 
 /// Precomputed cumulative sums for O(1) segment cost evaluation.
 struct CumStats {
@@ -160,7 +161,10 @@ fn pelt_single(data: &[f64], cost_fn: CostFn, penalty: f64, min_size: usize) ->
 }
 
 #[pyfunction]
-#[pyo3(signature = (input, cost="mean", pen=None, min_size=2, id_col="unique_id", target_col="y"))]
+#[pyo3(signature = (
+    input, cost="mean", pen=None, min_size=2, id_col="unique_id", target_col="y", missing="error", n_jobs=None
+))]
+#[allow(clippy::too_many_arguments)]
 pub fn pelt(
     input: PyDataFrame,
     cost: &str,
@@ -168,8 +172,12 @@ pub fn pelt(
     min_size: usize,
     id_col: &str,
     target_col: &str,
+    missing: &str,
+    n_jobs: Option<usize>,
 ) -> PyResult<PyDataFrame> {
     let cost_fn = get_cost_fn(cost)?;
+    let missing = MissingPolicy::parse(missing)?;
+    let pool = JobPool::new(n_jobs)?;
     let df = input.0;
 
     let id_col_series = df
@@ -193,23 +201,34 @@ pub fn pelt(
         })
         .collect();
 
-    // Build groups: map group_id -> Vec<f64>
-    let mut group_map: std::collections::BTreeMap<String, Vec<f64>> = std::collections::BTreeMap::new();
+    // Build groups: map group_id -> raw values, nulls kept for the missing policy
+    let mut group_map: std::collections::BTreeMap<String, Vec<Option<f64>>> = std::collections::BTreeMap::new();
     for (i, gid) in id_strs.iter().enumerate() {
-        let val = target_ca.get(i).unwrap_or(f64::NAN);
-        group_map.entry(gid.clone()).or_default().push(val);
+        group_map.entry(gid.clone()).or_default().push(target_ca.get(i));
     }
-    let groups: Vec<(String, Vec<f64>)> = group_map.into_iter().collect();
-
-    // Process each group in parallel with rayon
-    let results: Vec<(String, Vec<i64>)> = groups
-        .into_par_iter()
-        .map(|(gid, data)| {
-            let penalty = pen.unwrap_or_else(|| 2.0 * (data.len() as f64).ln());
-            let cps = pelt_single(&data, cost_fn, penalty, min_size);
-            (gid, cps)
-        })
-        .collect();
+    let groups: Vec<(String, Vec<Option<f64>>)> = group_map.into_iter().collect();
+
+    // Process each group in parallel on the `n_jobs` pool, with the GIL released
+    let results: Vec<(String, Vec<i64>)> = par_map_interruptible(&pool, &groups, |(gid, raw)| {
+        let data = missing.resolve(gid, raw)?;
+        let penalty = pen.unwrap_or_else(|| 2.0 * (data.len() as f64).ln());
+        let mut cps = pelt_single(&data, cost_fn, penalty, min_size);
+        if missing == MissingPolicy::Drop {
+            // Report changepoints as positions in the original rows
+            let kept: Vec<usize> = raw
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| v.is_some_and(|x| !x.is_nan()))
+                .map(|(i, _)| i)
+                .collect();
+            for cp in cps.iter_mut() {
+                *cp = kept[*cp as usize] as i64;
+            }
+        }
+        Ok((gid.clone(), cps))
+    })?
+    .into_iter()
+    .collect::<PyResult<_>>()?;
 
     // Build output DataFrame
     let mut id_vals: Vec<String> = Vec::new();
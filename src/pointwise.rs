@@ -0,0 +1,158 @@
+//! Local costs between the points of two series.
+//!
+//! Every multivariate kernel measures the local cost between two time steps
+//! with a [`PointMetric`] selected through its `metric` argument. With a
+//! single dimension Manhattan and Euclidean both reduce to `|x - y|` and
+//! squared Euclidean to `(x - y)^2`. The univariate DTW family selects a
+//! [`PointCost`] through its `cost` argument instead.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Pointwise distance between two points of the same dimension.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PointMetric {
+    Manhattan,
+    Euclidean,
+    SquaredEuclidean,
+}
+
+impl PointMetric {
+    /// Parse the `metric` argument, falling back to `default` when omitted.
+    pub fn parse(name: Option<&str>, default: PointMetric) -> PyResult<Self> {
+        match name {
+            None => Ok(default),
+            Some("manhattan") => Ok(PointMetric::Manhattan),
+            Some("euclidean") => Ok(PointMetric::Euclidean),
+            Some("squared_euclidean") => Ok(PointMetric::SquaredEuclidean),
+            Some(other) => Err(PyValueError::new_err(format!(
+                "Unknown metric: '{other}'. Expected one of: manhattan, euclidean, squared_euclidean"
+            ))),
+        }
+    }
+
+    /// Distance between points `a` and `b`.
+    #[inline]
+    pub fn distance(self, a: &[f64], b: &[f64]) -> f64 {
+        self.reduce(a.iter().zip(b).map(|(x, y)| x - y))
+    }
+
+    /// Distance between point `a` and the point with every coordinate equal to `value`.
+    #[inline]
+    pub fn distance_to_value(self, a: &[f64], value: f64) -> f64 {
+        self.reduce(a.iter().map(|x| x - value))
+    }
+
+    /// Distance between points `a` and `b` with each dimension's contribution
+    /// scaled by `weights`: `sum w|d|`, `sqrt(sum w d^2)` or `sum w d^2`.
+    #[inline]
+    pub fn weighted_distance(self, a: &[f64], b: &[f64], weights: &[f64]) -> f64 {
+        let terms = a.iter().zip(b).zip(weights);
+        match self {
+            PointMetric::Manhattan => terms.map(|((x, y), w)| w * (x - y).abs()).sum(),
+            PointMetric::Euclidean => terms.map(|((x, y), w)| w * (x - y).powi(2)).sum::<f64>().sqrt(),
+            PointMetric::SquaredEuclidean => terms.map(|((x, y), w)| w * (x - y).powi(2)).sum(),
+        }
+    }
+
+    fn reduce(self, diffs: impl Iterator<Item = f64>) -> f64 {
+        match self {
+            PointMetric::Manhattan => diffs.map(f64::abs).sum(),
+            PointMetric::Euclidean => diffs.map(|d| d * d).sum::<f64>().sqrt(),
+            PointMetric::SquaredEuclidean => diffs.map(|d| d * d).sum(),
+        }
+    }
+}
+
+/// Cost of matching two scalar observations in the univariate DTW family.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PointCost {
+    /// `|x - y|`
+    Abs,
+    /// `(x - y)^2`
+    Squared,
+    /// Quadratic within `delta` of zero and linear beyond it.
+    Huber(f64),
+}
+
+impl PointCost {
+    /// Parse the `cost` argument (`"abs"`, `"squared"`, `"huber"` or
+    /// `"huber(delta)"`, with `delta` defaulting to 1.0), falling back to
+    /// `default` when omitted.
+    pub fn parse(name: Option<&str>, default: PointCost) -> PyResult<Self> {
+        let Some(name) = name else {
+            return Ok(default);
+        };
+        match name.trim() {
+            "abs" => return Ok(PointCost::Abs),
+            "squared" => return Ok(PointCost::Squared),
+            "huber" => return Ok(PointCost::Huber(1.0)),
+            _ => {}
+        }
+        let delta = name
+            .trim()
+            .strip_prefix("huber(")
+            .and_then(|rest| rest.strip_suffix(')'))
+            .map(|delta| delta.trim().parse::<f64>());
+        match delta {
+            Some(Ok(delta)) if delta > 0.0 && delta.is_finite() => Ok(PointCost::Huber(delta)),
+            Some(_) => Err(PyValueError::new_err(format!(
+                "Invalid Huber cost: '{name}'. delta must be a positive number, e.g. 'huber(1.5)'"
+            ))),
+            None => Err(PyValueError::new_err(format!(
+                "Unknown cost: '{name}'. Expected one of: abs, squared, huber(delta)"
+            ))),
+        }
+    }
+
+    /// Cost of matching `a` with `b`.
+    #[inline]
+    pub fn cost(self, a: f64, b: f64) -> f64 {
+        let d = (a - b).abs();
+        match self {
+            PointCost::Abs => d,
+            PointCost::Squared => d * d,
+            PointCost::Huber(delta) if d <= delta => 0.5 * d * d,
+            PointCost::Huber(delta) => delta * (d - 0.5 * delta),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics() {
+        let a = [1.0, 2.0];
+        let b = [4.0, 6.0];
+        assert_eq!(PointMetric::Manhattan.distance(&a, &b), 7.0);
+        assert_eq!(PointMetric::Euclidean.distance(&a, &b), 5.0);
+        assert_eq!(PointMetric::SquaredEuclidean.distance(&a, &b), 25.0);
+        assert_eq!(PointMetric::Euclidean.distance_to_value(&[3.0, 4.0], 0.0), 5.0);
+    }
+
+    #[test]
+    fn test_single_dimension_reduces_to_abs() {
+        for metric in [PointMetric::Manhattan, PointMetric::Euclidean] {
+            assert_eq!(metric.distance(&[1.5], &[-2.0]), 3.5);
+        }
+        assert_eq!(PointMetric::SquaredEuclidean.distance(&[1.5], &[-2.0]), 12.25);
+    }
+
+    #[test]
+    fn test_point_costs() {
+        assert_eq!(PointCost::Abs.cost(1.0, 4.0), 3.0);
+        assert_eq!(PointCost::Squared.cost(1.0, 4.0), 9.0);
+        assert_eq!(PointCost::Huber(1.0).cost(1.0, 1.5), 0.125);
+        assert_eq!(PointCost::Huber(1.0).cost(1.0, 4.0), 2.5);
+    }
+
+    #[test]
+    fn test_parse_cost() {
+        assert_eq!(PointCost::parse(None, PointCost::Squared).unwrap(), PointCost::Squared);
+        assert_eq!(PointCost::parse(Some("abs"), PointCost::Squared).unwrap(), PointCost::Abs);
+        assert_eq!(PointCost::parse(Some("huber"), PointCost::Abs).unwrap(), PointCost::Huber(1.0));
+        assert_eq!(PointCost::parse(Some("huber(2.5)"), PointCost::Abs).unwrap(), PointCost::Huber(2.5));
+    }
+}
@@ -1,10 +1,27 @@
+use std::cell::RefCell;
+
+use polars::prelude::*;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+
+use crate::utils::{
+    cast_id_columns, compute_pairwise, par_map_interruptible, prepare_univariate, PairwiseOptions,
+};
 
-use crate::utils::compute_pairwise;
+thread_local! {
+    // Plans are cached by the planner, so keep one per rayon worker.
+    static PLANNER: RefCell<FftPlanner<f64>> = RefCell::new(FftPlanner::new());
+}
 
 /// Compute the normalized cross-correlation (NCC) sequence between two series.
-/// Returns a vector of length n+m-1 containing the cross-correlation at each lag.
+/// Returns a vector of length n+m-1 containing the cross-correlation at each lag;
+/// entry `k` is the correlation when `b` is shifted by `k - (m - 1)` positions.
+///
+/// Uses zero-padded FFTs, so the cost is O((n + m) log(n + m)).
 fn normalized_cross_correlation(a: &[f64], b: &[f64]) -> Vec<f64> {
     let n = a.len();
     let m = b.len();
@@ -18,36 +35,153 @@ fn normalized_cross_correlation(a: &[f64], b: &[f64]) -> Vec<f64> {
         return vec![0.0; len];
     }
 
-    // Direct cross-correlation computation
-    let mut ncc = vec![0.0; len];
-    for (k, ncc_k) in ncc.iter_mut().enumerate() {
-        let mut sum = 0.0;
-        let shift = k as isize - (m as isize - 1);
-        for (i, &ai) in a.iter().enumerate() {
-            let j = i as isize - shift;
-            if j >= 0 && (j as usize) < m {
-                sum += ai * b[j as usize];
-            }
+    let size = len.next_power_of_two();
+    let pad = |x: &[f64]| -> Vec<Complex<f64>> {
+        let mut buf: Vec<Complex<f64>> = x.iter().map(|&v| Complex::new(v, 0.0)).collect();
+        buf.resize(size, Complex::new(0.0, 0.0));
+        buf
+    };
+    let mut fa = pad(a);
+    let mut fb = pad(b);
+    PLANNER.with(|planner| {
+        let mut planner = planner.borrow_mut();
+        let forward = planner.plan_fft_forward(size);
+        forward.process(&mut fa);
+        forward.process(&mut fb);
+        for (x, y) in fa.iter_mut().zip(&fb) {
+            *x *= y.conj();
         }
-        *ncc_k = sum / denom;
+        planner.plan_fft_inverse(size).process(&mut fa);
+    });
+
+    // Circular correlation: non-negative shifts sit at the front,
+    // negative shifts wrap around to the end of the buffer.
+    let scale = denom * size as f64;
+    (0..len)
+        .map(|k| {
+            let shift = k as isize - (m as isize - 1);
+            let idx = if shift >= 0 { shift as usize } else { (size as isize + shift) as usize };
+            fa[idx].re / scale
+        })
+        .collect()
+}
+
+/// Shape-Based Distance and the shift of `b` that achieves it.
+///
+/// Shifting `b` right by the returned amount (left when negative) best
+/// aligns it with `a`.
+pub(crate) fn sbd_with_shift(a: &[f64], b: &[f64]) -> (f64, isize) {
+    if a.is_empty() || b.is_empty() {
+        return (2.0, 0);
     }
-    ncc
+
+    let ncc = normalized_cross_correlation(a, b);
+    let (best, max_ncc) = ncc
+        .iter()
+        .enumerate()
+        .fold((0, f64::NEG_INFINITY), |(bi, bv), (i, &v)| if v > bv { (i, v) } else { (bi, bv) });
+    (1.0 - max_ncc, best as isize - (b.len() as isize - 1))
 }
 
 /// Shape-Based Distance (SBD) between two time series.
 /// SBD = 1 - max(NCC(a, b)), where NCC is the normalized cross-correlation.
 /// Range: [0, 2].
 fn sbd_distance(a: &[f64], b: &[f64]) -> f64 {
-    if a.is_empty() || b.is_empty() {
-        return 2.0;
+    sbd_with_shift(a, b).0
+}
+
+/// Pairwise Shape-Based Distance.
+///
+/// With `return_shift=True` the long output gains an Int64 `shift` column:
+/// the number of positions the `id_2` series must be shifted (right when
+/// positive) to best align with the `id_1` series. That mode only supports
+/// the default long output.
+#[pyfunction]
+#[pyo3(signature = (input1, input2, return_shift=false, **options))]
+pub fn compute_pairwise_sbd(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    return_shift: bool,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    if !return_shift {
+        return compute_pairwise(input1, input2, "sbd", &options, sbd_distance);
     }
 
-    let ncc = normalized_cross_correlation(a, b);
-    let max_ncc = ncc.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-    1.0 - max_ncc
+    options.ensure_default_output("compute_pairwise_sbd(return_shift=True)")?;
+    let input = prepare_univariate(input1, input2, &options)?;
+    let results: Vec<(&String, &String, f64, i64)> = par_map_interruptible(
+        &options.job_pool()?,
+        &input.pairs(options.mode),
+        |&(left_key, left_series, right_key, right_series)| {
+            let (distance, shift) = sbd_with_shift(left_series, right_series);
+            (left_key, right_key, distance, shift as i64)
+        },
+    )?;
+
+    let out_df = DataFrame::new(vec![
+        Column::new("id_1".into(), results.iter().map(|r| r.0.as_str()).collect::<Vec<_>>()),
+        Column::new("id_2".into(), results.iter().map(|r| r.1.as_str()).collect::<Vec<_>>()),
+        Column::new("sbd".into(), results.iter().map(|r| r.2).collect::<Vec<_>>()),
+        Column::new("shift".into(), results.iter().map(|r| r.3).collect::<Vec<_>>()),
+    ])
+    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+    cast_id_columns(out_df, &input.uid_a_dtype, &input.uid_b_dtype).map(PyDataFrame)
 }
 
-#[pyfunction]
-pub fn compute_pairwise_sbd(input1: PyDataFrame, input2: PyDataFrame) -> PyResult<PyDataFrame> {
-    compute_pairwise(input1, input2, "sbd", sbd_distance)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Direct O(n·m) cross-correlation, the reference for the FFT version.
+    fn direct_ncc(a: &[f64], b: &[f64]) -> Vec<f64> {
+        let m = b.len();
+        let denom = a.iter().map(|x| x * x).sum::<f64>().sqrt() * b.iter().map(|x| x * x).sum::<f64>().sqrt();
+        (0..a.len() + m - 1)
+            .map(|k| {
+                let shift = k as isize - (m as isize - 1);
+                let sum: f64 = a
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, &ai)| {
+                        let j = i as isize - shift;
+                        (j >= 0 && (j as usize) < m).then(|| ai * b[j as usize])
+                    })
+                    .sum();
+                sum / denom
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fft_matches_direct() {
+        let a = [0.3, -1.0, 2.0, 0.7, 1.1, -0.4, 0.0];
+        let b = [0.0, 1.0, 1.5, -0.5, 2.0];
+        for (x, y) in [(&a[..], &b[..]), (&b[..], &a[..])] {
+            let fft = normalized_cross_correlation(x, y);
+            let direct = direct_ncc(x, y);
+            assert_eq!(fft.len(), direct.len());
+            for (f, d) in fft.iter().zip(&direct) {
+                assert!((f - d).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_shift_recovers_offset() {
+        let a = [0.0, 0.0, 1.0, 3.0, 1.0, 0.0, 0.0, 0.0];
+        let b = [1.0, 3.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let (distance, shift) = sbd_with_shift(&a, &b);
+        assert!(distance.abs() < 1e-12);
+        assert_eq!(shift, 2);
+        assert_eq!(sbd_with_shift(&b, &a).1, -2);
+    }
+
+    #[test]
+    fn test_zero_series() {
+        assert_eq!(sbd_with_shift(&[0.0, 0.0], &[1.0, 2.0]).0, 1.0);
+        assert_eq!(sbd_distance(&[], &[1.0]), 2.0);
+    }
 }
@@ -0,0 +1,180 @@
+//! shapeDTW (Zhao & Itti, 2018).
+//!
+//! Every observation is described by the subsequence of `2 * reach + 1`
+//! observations centred on it, with the series edges padded by repeating the
+//! first and last value. DTW aligns the two sequences of descriptors under
+//! the squared Euclidean distance, and the shapeDTW distance is the
+//! accumulated `cost` between the raw observations along that alignment.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_polars::PyDataFrame;
+
+use crate::dtw::backtrack_path;
+use crate::pointwise::{PointCost, PointMetric};
+use crate::utils::{compute_pairwise, PairwiseOptions};
+
+/// Shape descriptor computed from each subsequence.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Descriptor {
+    /// The subsequence itself.
+    Raw,
+    /// Piecewise aggregate approximation: the mean of each segment.
+    Paa(usize),
+    /// The least-squares slope of each segment.
+    Slope(usize),
+}
+
+impl Descriptor {
+    fn parse(name: Option<&str>, segments: Option<usize>, reach: usize) -> PyResult<Self> {
+        let segments_value = segments.unwrap_or(5);
+        let descriptor = match name {
+            None | Some("raw") => Descriptor::Raw,
+            Some("paa") => Descriptor::Paa(segments_value),
+            Some("slope") => Descriptor::Slope(segments_value),
+            Some(other) => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown descriptor: '{other}'. Expected one of: raw, paa, slope"
+                )))
+            }
+        };
+        if descriptor == Descriptor::Raw && segments.is_some() {
+            return Err(PyValueError::new_err("segments only applies to the 'paa' and 'slope' descriptors"));
+        }
+        let window = 2 * reach + 1;
+        if descriptor != Descriptor::Raw && !(1..=window).contains(&segments_value) {
+            return Err(PyValueError::new_err(format!(
+                "segments must be between 1 and the subsequence length 2 * reach + 1 = {window}, got {segments_value}"
+            )));
+        }
+        Ok(descriptor)
+    }
+
+    fn describe(self, subsequence: &[f64]) -> Vec<f64> {
+        match self {
+            Descriptor::Raw => subsequence.to_vec(),
+            Descriptor::Paa(segments) => split(subsequence, segments)
+                .map(|seg| seg.iter().sum::<f64>() / seg.len() as f64)
+                .collect(),
+            Descriptor::Slope(segments) => split(subsequence, segments).map(slope).collect(),
+        }
+    }
+}
+
+/// Split `x` into `segments` contiguous, non-empty parts of near-equal length.
+fn split(x: &[f64], segments: usize) -> impl Iterator<Item = &[f64]> {
+    let len = x.len();
+    (0..segments).map(move |s| &x[s * len / segments..(s + 1) * len / segments])
+}
+
+/// Least-squares slope of `y` against its index; 0 for a single point.
+fn slope(y: &[f64]) -> f64 {
+    let n = y.len() as f64;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = y.iter().sum::<f64>() / n;
+    let (mut num, mut den) = (0.0, 0.0);
+    for (i, v) in y.iter().enumerate() {
+        let dx = i as f64 - mean_x;
+        num += dx * (v - mean_y);
+        den += dx * dx;
+    }
+    if den == 0.0 {
+        0.0
+    } else {
+        num / den
+    }
+}
+
+/// The descriptor of every observation of `x`.
+fn descriptors(x: &[f64], reach: usize, descriptor: Descriptor) -> Vec<Vec<f64>> {
+    let last = x.len() as isize - 1;
+    let mut subsequence = vec![0.0; 2 * reach + 1];
+    (0..x.len())
+        .map(|i| {
+            for (k, v) in subsequence.iter_mut().enumerate() {
+                let idx = (i as isize + k as isize - reach as isize).clamp(0, last);
+                *v = x[idx as usize];
+            }
+            descriptor.describe(&subsequence)
+        })
+        .collect()
+}
+
+fn shapedtw_distance(a: &[f64], b: &[f64], reach: usize, descriptor: Descriptor, cost: PointCost) -> f64 {
+    let n = a.len();
+    let m = b.len();
+    if n == 0 || m == 0 {
+        return f64::INFINITY;
+    }
+    let da = descriptors(a, reach, descriptor);
+    let db = descriptors(b, reach, descriptor);
+
+    let mut cost_matrix = vec![vec![f64::MAX; m + 1]; n + 1];
+    cost_matrix[0][0] = 0.0;
+    for i in 1..=n {
+        for j in 1..=m {
+            let min_prev = cost_matrix[i - 1][j]
+                .min(cost_matrix[i][j - 1])
+                .min(cost_matrix[i - 1][j - 1]);
+            cost_matrix[i][j] = PointMetric::SquaredEuclidean.distance(&da[i - 1], &db[j - 1]) + min_prev;
+        }
+    }
+    backtrack_path(&cost_matrix)
+        .iter()
+        .map(|&(i, j)| cost.cost(a[i], b[j]))
+        .sum()
+}
+
+/// Pairwise shapeDTW distances.
+///
+/// `reach` sets the subsequence length `2 * reach + 1` (default 15);
+/// `descriptor` is `"raw"` (default), `"paa"` or `"slope"`, the latter two
+/// over `segments` segments (default 5). `cost` is the local cost between
+/// raw observations along the alignment, `"squared"` by default.
+#[pyfunction]
+#[pyo3(signature = (input1, input2, reach=15, descriptor=None, segments=None, cost=None, **options))]
+pub fn compute_pairwise_shapedtw(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    reach: usize,
+    descriptor: Option<&str>,
+    segments: Option<usize>,
+    cost: Option<&str>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let descriptor = Descriptor::parse(descriptor, segments, reach)?;
+    let cost = PointCost::parse(cost, PointCost::Squared)?;
+    compute_pairwise(input1, input2, "shapedtw", &options, move |a, b| {
+        shapedtw_distance(a, b, reach, descriptor, cost)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dtw::dtw_distance;
+
+    #[test]
+    fn test_descriptors_pad_edges() {
+        let d = descriptors(&[1.0, 2.0, 3.0], 1, Descriptor::Raw);
+        assert_eq!(d, vec![vec![1.0, 1.0, 2.0], vec![1.0, 2.0, 3.0], vec![2.0, 3.0, 3.0]]);
+    }
+
+    #[test]
+    fn test_paa_and_slope() {
+        let x = [0.0, 2.0, 4.0, 5.0, 5.0, 5.0];
+        assert_eq!(Descriptor::Paa(2).describe(&x), vec![2.0, 5.0]);
+        assert_eq!(Descriptor::Slope(2).describe(&x), vec![2.0, 0.0]);
+        assert_eq!(split(&[1.0; 5], 2).map(<[f64]>::len).collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn test_zero_reach_is_dtw() {
+        let a = [0.0, 1.0, 3.0, 2.0, 0.0];
+        let b = [0.0, 0.0, 1.0, 3.0, 2.0, 1.0];
+        let d = shapedtw_distance(&a, &b, 0, Descriptor::Raw, PointCost::Squared);
+        assert!((d - dtw_distance(&a, &b, PointCost::Squared)).abs() < 1e-12);
+    }
+}
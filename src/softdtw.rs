@@ -0,0 +1,264 @@
+//! Soft-DTW (Cuturi & Blondel, 2017), a differentiable relaxation of DTW.
+//!
+//! The hard `min` of the DTW recursion is replaced by the soft minimum
+//! `-gamma * log(sum(exp(-x / gamma)))` over squared Euclidean costs, which
+//! makes the distance smooth in both inputs. As `gamma -> 0` it recovers DTW
+//! with squared costs. The gradient with respect to the first series is
+//! obtained from the expected alignment matrix computed in a backward pass,
+//! which is what soft-DTW barycenter averaging needs.
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_polars::PyDataFrame;
+
+use crate::utils::{compute_pairwise, par_map_interruptible, JobPool, PairwiseOptions};
+
+/// Numerically stable soft minimum of three values.
+fn softmin(a: f64, b: f64, c: f64, gamma: f64) -> f64 {
+    let min = a.min(b).min(c);
+    if min.is_infinite() {
+        return min;
+    }
+    let sum = (-(a - min) / gamma).exp() + (-(b - min) / gamma).exp() + (-(c - min) / gamma).exp();
+    min - gamma * sum.ln()
+}
+
+/// Soft-DTW value using O(m) memory (two-row approach).
+fn soft_dtw(a: &[f64], b: &[f64], gamma: f64) -> f64 {
+    let n = a.len();
+    let m = b.len();
+    if n == 0 || m == 0 {
+        return f64::INFINITY;
+    }
+    let mut prev = vec![f64::INFINITY; m + 1];
+    let mut curr = vec![f64::INFINITY; m + 1];
+    prev[0] = 0.0;
+    for i in 1..=n {
+        curr[0] = f64::INFINITY;
+        for j in 1..=m {
+            let cost = (a[i - 1] - b[j - 1]).powi(2);
+            curr[j] = cost + softmin(prev[j - 1], prev[j], curr[j - 1], gamma);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[m]
+}
+
+/// Soft-DTW value and its gradient with respect to `a`.
+///
+/// Runs the forward recursion on a padded `(n + 2) x (m + 2)` matrix, then the
+/// backward recursion for the expected alignment `E`, so that
+/// `d/da_i = sum_j E[i][j] * 2 * (a_i - b_j)`.
+fn soft_dtw_grad(a: &[f64], b: &[f64], gamma: f64) -> (f64, Vec<f64>) {
+    let n = a.len();
+    let m = b.len();
+    if n == 0 || m == 0 {
+        return (f64::INFINITY, vec![0.0; n]);
+    }
+    let cols = m + 2;
+    let idx = |i: usize, j: usize| i * cols + j;
+
+    // Padded squared costs: d[idx(i, j)] = (a_{i-1} - b_{j-1})^2 for 1 <= i <= n, 1 <= j <= m
+    let mut d = vec![0.0; (n + 2) * cols];
+    for i in 1..=n {
+        for j in 1..=m {
+            d[idx(i, j)] = (a[i - 1] - b[j - 1]).powi(2);
+        }
+    }
+
+    let mut r = vec![f64::INFINITY; (n + 2) * cols];
+    r[idx(0, 0)] = 0.0;
+    for i in 1..=n {
+        for j in 1..=m {
+            r[idx(i, j)] = d[idx(i, j)] + softmin(r[idx(i - 1, j - 1)], r[idx(i - 1, j)], r[idx(i, j - 1)], gamma);
+        }
+    }
+    let value = r[idx(n, m)];
+
+    // Backward pass: the border beyond (n, m) must not contribute
+    for i in 1..=n {
+        r[idx(i, m + 1)] = f64::NEG_INFINITY;
+    }
+    for j in 1..=m {
+        r[idx(n + 1, j)] = f64::NEG_INFINITY;
+    }
+    r[idx(n + 1, m + 1)] = value;
+
+    let mut e = vec![0.0; (n + 2) * cols];
+    e[idx(n + 1, m + 1)] = 1.0;
+    for j in (1..=m).rev() {
+        for i in (1..=n).rev() {
+            let here = r[idx(i, j)];
+            let down = ((r[idx(i + 1, j)] - here - d[idx(i + 1, j)]) / gamma).exp();
+            let right = ((r[idx(i, j + 1)] - here - d[idx(i, j + 1)]) / gamma).exp();
+            let diag = ((r[idx(i + 1, j + 1)] - here - d[idx(i + 1, j + 1)]) / gamma).exp();
+            e[idx(i, j)] = e[idx(i + 1, j)] * down + e[idx(i, j + 1)] * right + e[idx(i + 1, j + 1)] * diag;
+        }
+    }
+
+    let grad = (1..=n)
+        .map(|i| (1..=m).map(|j| e[idx(i, j)] * 2.0 * (a[i - 1] - b[j - 1])).sum())
+        .collect();
+    (value, grad)
+}
+
+fn validate_gamma(gamma: f64) -> PyResult<()> {
+    if gamma <= 0.0 || !gamma.is_finite() {
+        return Err(PyValueError::new_err(format!("gamma must be a positive number, got {gamma}")));
+    }
+    Ok(())
+}
+
+#[pyfunction]
+#[pyo3(signature = (input1, input2, gamma=None, **options))]
+pub fn compute_pairwise_softdtw(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    gamma: Option<f64>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let gamma = gamma.unwrap_or(1.0);
+    validate_gamma(gamma)?;
+    compute_pairwise(input1, input2, "softdtw", &options, |a, b| soft_dtw(a, b, gamma))
+}
+
+/// Soft-DTW between `x` and `y`, without the gradient.
+#[pyfunction]
+#[pyo3(signature = (x, y, gamma=1.0))]
+pub fn softdtw_value(x: Vec<f64>, y: Vec<f64>, gamma: f64) -> PyResult<f64> {
+    validate_gamma(gamma)?;
+    Ok(soft_dtw(&x, &y, gamma))
+}
+
+/// Index of the centroid with the smallest soft-DTW value for every series;
+/// ties go to the first centroid.
+///
+/// Series are evaluated in parallel with the GIL released. `n_jobs` limits
+/// the worker threads (rayon's global pool by default; 1 is sequential).
+#[pyfunction]
+#[pyo3(signature = (series, centroids, gamma=1.0, n_jobs=None))]
+pub fn softdtw_assign(
+    series: Vec<Vec<f64>>,
+    centroids: Vec<Vec<f64>>,
+    gamma: f64,
+    n_jobs: Option<usize>,
+) -> PyResult<Vec<usize>> {
+    validate_gamma(gamma)?;
+    if centroids.is_empty() {
+        return Err(PyValueError::new_err("centroids must contain at least one series"));
+    }
+    let pool = JobPool::new(n_jobs)?;
+    par_map_interruptible(&pool, &series, |s| {
+        centroids
+            .iter()
+            .map(|c| soft_dtw(s, c, gamma))
+            .enumerate()
+            .min_by(|x, y| x.1.total_cmp(&y.1))
+            .map_or(0, |(ci, _)| ci)
+    })
+}
+
+/// Soft-DTW between `x` and `y` and its gradient with respect to `x`.
+///
+/// Returns `(value, gradient)` where the gradient has the length of `x`.
+#[pyfunction]
+#[pyo3(signature = (x, y, gamma=1.0))]
+pub fn softdtw_grad(x: Vec<f64>, y: Vec<f64>, gamma: f64) -> PyResult<(f64, Vec<f64>)> {
+    validate_gamma(gamma)?;
+    Ok(soft_dtw_grad(&x, &y, gamma))
+}
+
+/// Soft-DTW barycenter objective `sum_k w_k * softdtw(z, series_k)` and its
+/// gradient with respect to `z`, evaluated over `series` in parallel with
+/// the GIL released.
+///
+/// `weights` defaults to uniform weights `1 / len(series)`. `n_jobs` limits
+/// the worker threads (rayon's global pool by default; 1 is sequential).
+#[pyfunction]
+#[pyo3(signature = (z, series, gamma=1.0, weights=None, n_jobs=None))]
+pub fn softdtw_barycenter_grad(
+    py: Python<'_>,
+    z: Vec<f64>,
+    series: Vec<Vec<f64>>,
+    gamma: f64,
+    weights: Option<Vec<f64>>,
+    n_jobs: Option<usize>,
+) -> PyResult<(f64, Vec<f64>)> {
+    validate_gamma(gamma)?;
+    if series.is_empty() {
+        return Err(PyValueError::new_err("series must contain at least one series"));
+    }
+    let weights = weights.unwrap_or_else(|| vec![1.0 / series.len() as f64; series.len()]);
+    if weights.len() != series.len() {
+        return Err(PyValueError::new_err(format!(
+            "weights has length {} but there are {} series",
+            weights.len(),
+            series.len()
+        )));
+    }
+
+    let pool = JobPool::new(n_jobs)?;
+    let parts = py.allow_threads(|| pool.map(&series, |s| soft_dtw_grad(&z, s, gamma)));
+    let mut value = 0.0;
+    let mut grad = vec![0.0; z.len()];
+    for ((v, g), w) in parts.into_iter().zip(&weights) {
+        value += w * v;
+        grad.iter_mut().zip(g).for_each(|(acc, x)| *acc += w * x);
+    }
+    Ok((value, grad))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dtw_squared(a: &[f64], b: &[f64]) -> f64 {
+        let m = b.len();
+        let mut prev = vec![f64::INFINITY; m + 1];
+        prev[0] = 0.0;
+        for &x in a {
+            let mut curr = vec![f64::INFINITY; m + 1];
+            for j in 1..=m {
+                curr[j] = (x - b[j - 1]).powi(2) + prev[j - 1].min(prev[j]).min(curr[j - 1]);
+            }
+            prev = curr;
+        }
+        prev[m]
+    }
+
+    #[test]
+    fn test_value_matches_full_matrix() {
+        let a = [0.0, 1.0, 3.0, 2.0, 0.5];
+        let b = [0.2, 1.5, 2.5, 0.0];
+        let (value, _) = soft_dtw_grad(&a, &b, 0.7);
+        assert!((value - soft_dtw(&a, &b, 0.7)).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_small_gamma_approaches_dtw() {
+        let a = [0.0, 1.0, 3.0, 2.0, 0.5];
+        let b = [0.2, 1.5, 2.5, 0.0];
+        assert!((soft_dtw(&a, &b, 1e-4) - dtw_squared(&a, &b)).abs() < 1e-2);
+        // The soft minimum never exceeds the hard one
+        assert!(soft_dtw(&a, &b, 1.0) <= dtw_squared(&a, &b));
+    }
+
+    #[test]
+    fn test_gradient_matches_finite_differences() {
+        let a = [0.3, -1.0, 2.0, 0.7, 1.1];
+        let b = [0.0, 1.0, 1.5, -0.5];
+        let gamma = 0.5;
+        let (_, grad) = soft_dtw_grad(&a, &b, gamma);
+        let h = 1e-6;
+        for i in 0..a.len() {
+            let mut up = a.to_vec();
+            let mut down = a.to_vec();
+            up[i] += h;
+            down[i] -= h;
+            let numeric = (soft_dtw(&up, &b, gamma) - soft_dtw(&down, &b, gamma)) / (2.0 * h);
+            assert!((numeric - grad[i]).abs() < 1e-5, "index {i}: {numeric} vs {}", grad[i]);
+        }
+    }
+}
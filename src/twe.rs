@@ -1,30 +1,40 @@
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
 
-use crate::utils::compute_pairwise;
+use crate::utils::{compute_pairwise, PairwiseOptions};
 
 /// TWE (Time Warp Edit) distance. O(m) memory.
-fn twe_distance(a: &[f64], b: &[f64], nu: f64, lambda: f64) -> f64 {
+///
+/// With a `window`, only cells with `|i - j| <= window` are filled (Sakoe-Chiba
+/// band, widened to `|n - m|`).
+fn twe_distance(a: &[f64], b: &[f64], nu: f64, lambda: f64, window: Option<usize>) -> f64 {
     let n = a.len();
     let m = b.len();
     if n == 0 || m == 0 {
         return 0.0;
     }
+    let w = window.map_or(n.max(m), |w| w.max(n.abs_diff(m)));
 
     let mut prev = vec![f64::MAX; m + 1];
     let mut curr = vec![f64::MAX; m + 1];
 
     prev[0] = 0.0;
-    for j in 1..=m {
+    for j in 1..=m.min(w) {
         prev[j] = prev[j - 1] + (b[j - 1] - if j > 1 { b[j - 2] } else { 0.0 }).abs() + nu + lambda;
     }
 
     for i in 1..=n {
         let a_i = a[i - 1];
         let a_prev = if i > 1 { a[i - 2] } else { 0.0 };
-        curr[0] = prev[0] + (a_i - a_prev).abs() + nu + lambda;
+        curr[0] = if i <= w { prev[0] + (a_i - a_prev).abs() + nu + lambda } else { f64::MAX };
+        let j_start = i.saturating_sub(w).max(1);
+        let j_end = (i + w).min(m);
+        if j_start > 1 {
+            curr[j_start - 1] = f64::MAX;
+        }
 
-        for j in 1..=m {
+        for j in j_start..=j_end {
             let b_j = b[j - 1];
             let b_prev = if j > 1 { b[j - 2] } else { 0.0 };
 
@@ -43,16 +53,19 @@ fn twe_distance(a: &[f64], b: &[f64], nu: f64, lambda: f64) -> f64 {
 }
 
 #[pyfunction]
-#[pyo3(signature = (input1, input2, nu=None, lambda=None))]
+#[pyo3(signature = (input1, input2, nu=None, lambda=None, window=None, **options))]
 pub fn compute_pairwise_twe(
     input1: PyDataFrame,
     input2: PyDataFrame,
     nu: Option<f64>,
     lambda: Option<f64>,
+    window: Option<usize>,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
     let nu_value = nu.unwrap_or(0.001);
     let lambda_value = lambda.unwrap_or(1.0);
-    compute_pairwise(input1, input2, "twe", move |a, b| {
-        twe_distance(a, b, nu_value, lambda_value)
+    compute_pairwise(input1, input2, "twe", &options, move |a, b| {
+        twe_distance(a, b, nu_value, lambda_value, window)
     })
 }
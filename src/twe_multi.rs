@@ -0,0 +1,87 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_polars::PyDataFrame;
+
+use crate::pointwise::PointMetric;
+use crate::utils::{compute_pairwise_multivariate, PairwiseOptions};
+
+/// Point before 1-based time step `k` of `s`, or `origin` for the first step.
+fn point_before<'a>(s: &'a [Vec<f64>], k: usize, origin: &'a [f64]) -> &'a [f64] {
+    if k > 1 { &s[k - 2] } else { origin }
+}
+
+/// Multivariate TWE distance. The point before the first time step is the
+/// origin, as in `twe.rs`. O(m) memory; `window` is a Sakoe-Chiba band.
+fn twe_distance(
+    a: &[Vec<f64>],
+    b: &[Vec<f64>],
+    nu: f64,
+    lambda: f64,
+    window: Option<usize>,
+    metric: PointMetric,
+) -> f64 {
+    let n = a.len();
+    let m = b.len();
+    if n == 0 || m == 0 {
+        return 0.0;
+    }
+    let w = window.map_or(n.max(m), |w| w.max(n.abs_diff(m)));
+    let origin_a = vec![0.0; a[0].len()];
+    let origin_b = vec![0.0; b[0].len()];
+
+    let mut prev = vec![f64::MAX; m + 1];
+    let mut curr = vec![f64::MAX; m + 1];
+
+    prev[0] = 0.0;
+    for j in 1..=m.min(w) {
+        prev[j] = prev[j - 1] + metric.distance(&b[j - 1], point_before(b, j, &origin_b)) + nu + lambda;
+    }
+
+    for i in 1..=n {
+        let a_i = &a[i - 1];
+        let a_prev = point_before(a, i, &origin_a);
+        let delete_cost = metric.distance(a_i, a_prev) + nu + lambda;
+        curr[0] = if i <= w { prev[0] + delete_cost } else { f64::MAX };
+        let j_start = i.saturating_sub(w).max(1);
+        let j_end = (i + w).min(m);
+        if j_start > 1 {
+            curr[j_start - 1] = f64::MAX;
+        }
+
+        for j in j_start..=j_end {
+            let b_j = &b[j - 1];
+            let b_prev = point_before(b, j, &origin_b);
+
+            let d_match = prev[j - 1]
+                + metric.distance(a_i, b_j)
+                + metric.distance(a_prev, b_prev)
+                + nu * ((i as f64 - j as f64).abs()).min(2.0 * nu);
+            let d_delete = prev[j] + delete_cost;
+            let d_insert = curr[j - 1] + metric.distance(b_j, b_prev) + nu + lambda;
+
+            curr[j] = d_match.min(d_delete).min(d_insert);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[m]
+}
+
+#[pyfunction]
+#[pyo3(signature = (input1, input2, nu=None, lambda=None, window=None, metric=None, **options))]
+pub fn compute_pairwise_twe_multi(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    nu: Option<f64>,
+    lambda: Option<f64>,
+    window: Option<usize>,
+    metric: Option<String>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let nu_value = nu.unwrap_or(0.001);
+    let lambda_value = lambda.unwrap_or(1.0);
+    let metric = PointMetric::parse(metric.as_deref(), PointMetric::Manhattan)?;
+    compute_pairwise_multivariate(input1, input2, "twe_multi", &options, move |a, b| {
+        twe_distance(a, b, nu_value, lambda_value, window, metric)
+    })
+}
@@ -1,9 +1,12 @@
 use polars::prelude::*;
 use pyo3::prelude::*;
-use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::exceptions::{PyIOError, PyKeyError, PyTypeError, PyValueError};
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
-use std::collections::HashMap;
-use std::sync::Arc;
+use ordered_float::OrderedFloat;
+use itertools::Itertools;
+use std::collections::{BinaryHeap, HashMap};
+use std::time::{Duration, Instant};
 use rayon::prelude::*;
 
 /// Cast a column in-place to the given DataType, returning a new DataFrame.
@@ -24,51 +27,150 @@ fn validate_column_exists(df: &DataFrame, col_name: &str) -> PyResult<()> {
     Ok(())
 }
 
-/// Groups a DataFrame by "unique_id" and aggregates the "y" column into lists.
-/// Returns a DataFrame with columns: unique_id (String), y (List<f64>).
-pub fn get_groups(df: &DataFrame) -> Result<DataFrame, PolarsError> {
-    // Cast columns first
-    let df = cast_column(df, "unique_id", DataType::String)?;
-    let df = cast_column(&df, "y", DataType::Float64)?;
+/// Names of the id, target and (optional) time columns of a long-format input.
+#[derive(Clone, Debug)]
+pub struct SeriesColumns {
+    pub id_col: String,
+    pub target_col: String,
+    pub time_col: TimeColumn,
+}
+
+impl Default for SeriesColumns {
+    fn default() -> Self {
+        SeriesColumns {
+            id_col: "unique_id".to_string(),
+            target_col: "y".to_string(),
+            time_col: TimeColumn::Auto,
+        }
+    }
+}
 
-    // Select only the columns we need
-    let df = df.select(["unique_id", "y"])?;
+/// How observations are ordered within each series.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimeColumn {
+    /// Sort by `ds` when the input has such a column, otherwise keep row order.
+    Auto,
+    /// Sort by the named column, which must exist.
+    Named(String),
+    /// Keep the input row order.
+    Disabled,
+}
 
-    // Group by unique_id and aggregate y into lists via lazy API
-    df.lazy()
-        .group_by([col("unique_id")])
-        .agg([col("y")])
+impl SeriesColumns {
+    /// Name of the time column to sort by, if any.
+    pub fn time_col(&self) -> Option<&str> {
+        match &self.time_col {
+            TimeColumn::Named(name) => Some(name.as_str()),
+            TimeColumn::Auto | TimeColumn::Disabled => None,
+        }
+    }
+
+    /// Resolve `TimeColumn::Auto` against a concrete input and validate the time column.
+    fn resolve(&self, df: &DataFrame) -> PyResult<SeriesColumns> {
+        let time_col = match &self.time_col {
+            TimeColumn::Auto if df.column("ds").is_ok() => TimeColumn::Named("ds".to_string()),
+            TimeColumn::Auto => TimeColumn::Disabled,
+            other => other.clone(),
+        };
+        let resolved = SeriesColumns { time_col, ..self.clone() };
+        if let Some(time_col) = resolved.time_col() {
+            validate_column_exists(df, time_col)?;
+        }
+        Ok(resolved)
+    }
+}
+
+/// Reject inputs where a series has two observations at the same time.
+fn validate_unique_timestamps(df: &DataFrame, id_col: &str, time_col: &str) -> PyResult<()> {
+    let duplicates = df
+        .clone()
+        .lazy()
+        .group_by([col(id_col), col(time_col)])
+        .agg([len().alias("__count")])
+        .filter(col("__count").gt(lit(1)))
+        .limit(1)
         .collect()
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    if duplicates.height() > 0 {
+        let id = duplicates.column(id_col).and_then(|c| c.get(0).map(|v| v.into_static()));
+        let ts = duplicates.column(time_col).and_then(|c| c.get(0).map(|v| v.into_static()));
+        let (id, ts) = (
+            id.map_err(|e| PyValueError::new_err(e.to_string()))?,
+            ts.map_err(|e| PyValueError::new_err(e.to_string()))?,
+        );
+        return Err(PyValueError::new_err(format!(
+            "Duplicate timestamp {ts} in column '{time_col}' for series {id}"
+        )));
+    }
+    Ok(())
+}
+
+/// Groups a DataFrame by the id column and aggregates the target column into lists.
+/// When a time column is set, each list is ordered by it.
+/// Returns a DataFrame with columns: id_col (String), target_col (List<f64>).
+pub fn get_groups(df: &DataFrame, columns: &SeriesColumns) -> Result<DataFrame, PolarsError> {
+    let id_col = columns.id_col.as_str();
+    let target_col = columns.target_col.as_str();
+
+    // Cast columns first
+    let df = cast_column(df, id_col, DataType::String)?;
+    let df = cast_column(&df, target_col, DataType::Float64)?;
+
+    // Select only the columns we need and group by id via lazy API
+    match columns.time_col() {
+        Some(time_col) => df
+            .select([id_col, time_col, target_col])?
+            .lazy()
+            .group_by([col(id_col)])
+            .agg([col(target_col).sort_by([col(time_col)], SortMultipleOptions::default())])
+            .collect(),
+        None => df
+            .select([id_col, target_col])?
+            .lazy()
+            .group_by([col(id_col)])
+            .agg([col(target_col)])
+            .collect(),
+    }
 }
 
 /// Optimized conversion of a grouped DataFrame into a HashMap mapping id -> Vec<f64>.
-pub fn df_to_hashmap(df: &DataFrame) -> PyResult<HashMap<String, Vec<f64>>> {
-    let unique_id_col = df.column("unique_id")
-        .map_err(|e| PyKeyError::new_err(format!("Missing column 'unique_id': {e}")))?;
-    let y_col = df.column("y")
-        .map_err(|e| PyKeyError::new_err(format!("Missing column 'y': {e}")))?;
+/// Missing observations are resolved per series according to `missing`.
+pub fn df_to_hashmap(
+    df: &DataFrame,
+    columns: &SeriesColumns,
+    missing: MissingPolicy,
+) -> PyResult<HashMap<String, Vec<f64>>> {
+    let id_col = columns.id_col.as_str();
+    let target_col = columns.target_col.as_str();
+    let unique_id_col = df.column(id_col)
+        .map_err(|e| PyKeyError::new_err(format!("Missing column '{id_col}': {e}")))?;
+    let y_col = df.column(target_col)
+        .map_err(|e| PyKeyError::new_err(format!("Missing column '{target_col}': {e}")))?;
 
     let unique_ids: Vec<String> = unique_id_col
         .str()
-        .map_err(|e| PyValueError::new_err(format!("Column 'unique_id' must be string type: {e}")))?
+        .map_err(|e| PyValueError::new_err(format!("Column '{id_col}' must be string type: {e}")))?
         .into_no_null_iter()
         .map(|s| s.to_string())
         .collect();
 
-    let y_lists: Vec<Vec<f64>> = y_col
+    let y_lists: Vec<Vec<Option<f64>>> = y_col
         .list()
-        .map_err(|e| PyValueError::new_err(format!("Column 'y' must be list type: {e}")))?
+        .map_err(|e| PyValueError::new_err(format!("Column '{target_col}' must be list type: {e}")))?
         .into_iter()
-        .map(|opt_series| {
+        .zip(&unique_ids)
+        .map(|(opt_series, id)| {
             let series = opt_series.ok_or_else(|| {
-                PyValueError::new_err("Null entry found in 'y' list column. Ensure no null values in 'y'.")
+                PyValueError::new_err(format!(
+                    "Null entry found in '{target_col}' list column for series {id}. Ensure no null values in '{target_col}'."
+                ))
             })?;
             let chunked = series.f64().map_err(|e| {
-                PyValueError::new_err(format!("Values in 'y' column must be f64: {e}"))
+                PyValueError::new_err(format!("Values in '{target_col}' column must be f64: {e}"))
             })?;
-            Ok(chunked.into_no_null_iter().collect::<Vec<f64>>())
+            Ok(chunked.into_iter().collect::<Vec<Option<f64>>>())
         })
-        .collect::<PyResult<Vec<Vec<f64>>>>()?;
+        .collect::<PyResult<Vec<Vec<Option<f64>>>>>()?;
 
     if unique_ids.len() != y_lists.len() {
         return Err(PyValueError::new_err(format!(
@@ -78,157 +180,762 @@ pub fn df_to_hashmap(df: &DataFrame) -> PyResult<HashMap<String, Vec<f64>>> {
         )));
     }
 
-    let hashmap: HashMap<String, Vec<f64>> = unique_ids
+    unique_ids
         .into_iter()
         .zip(y_lists)
-        .collect();
-    Ok(hashmap)
+        .map(|(id, values)| {
+            let series = missing.resolve(&id, &values)?;
+            Ok((id, series))
+        })
+        .collect()
 }
 
-/// Groups a DataFrame by "unique_id" and aggregates all dimension columns into lists.
-pub fn get_groups_multivariate(df: &DataFrame) -> Result<DataFrame, PolarsError> {
-    let dims: Vec<String> = df.get_column_names()
+/// Dimension columns of a multivariate input: everything except the id and time columns.
+fn dimension_columns(df: &DataFrame, columns: &SeriesColumns) -> Vec<String> {
+    df.get_column_names()
         .iter()
-        .filter(|s| s.as_str() != "unique_id")
+        .filter(|s| s.as_str() != columns.id_col && Some(s.as_str()) != columns.time_col())
         .map(|s| s.to_string())
-        .collect();
+        .collect()
+}
 
-    // Cast unique_id to String and dimension columns to Float64
-    let mut df = cast_column(df, "unique_id", DataType::String)?;
+/// Groups a DataFrame by the id column and aggregates all dimension columns into lists.
+pub fn get_groups_multivariate(df: &DataFrame, columns: &SeriesColumns) -> Result<DataFrame, PolarsError> {
+    let id_col = columns.id_col.as_str();
+    let dims = dimension_columns(df, columns);
+
+    // Cast the id to String and dimension columns to Float64
+    let mut df = cast_column(df, id_col, DataType::String)?;
     for dim in &dims {
         df = cast_column(&df, dim.as_str(), DataType::Float64)?;
     }
 
-    // Group by unique_id and aggregate all dimension columns into lists via lazy API
-    let agg_exprs: Vec<Expr> = dims.iter().map(|d| col(d.as_str())).collect();
+    // Group by id and aggregate all dimension columns into lists via lazy API,
+    // ordered by the time column when there is one
+    let agg_exprs: Vec<Expr> = dims
+        .iter()
+        .map(|d| match columns.time_col() {
+            Some(time_col) => col(d.as_str()).sort_by([col(time_col)], SortMultipleOptions::default()),
+            None => col(d.as_str()),
+        })
+        .collect();
     df.lazy()
-        .group_by([col("unique_id")])
+        .group_by([col(id_col)])
         .agg(agg_exprs)
         .collect()
 }
 
-/// Converts a grouped DataFrame into a HashMap mapping unique_id -> multivariate time series.
-pub fn df_to_hashmap_multivariate(df: &DataFrame) -> PyResult<HashMap<String, Vec<Vec<f64>>>> {
-    let unique_id_col = df.column("unique_id")
-        .map_err(|e| PyKeyError::new_err(format!("Missing column 'unique_id': {e}")))?;
+/// Converts a grouped DataFrame into a HashMap mapping id -> multivariate time series.
+/// Missing observations are resolved per series according to `missing`.
+pub fn df_to_hashmap_multivariate(
+    df: &DataFrame,
+    columns: &SeriesColumns,
+    missing: MissingPolicy,
+) -> PyResult<HashMap<String, Vec<Vec<f64>>>> {
+    let id_col = columns.id_col.as_str();
+    let unique_id_col = df.column(id_col)
+        .map_err(|e| PyKeyError::new_err(format!("Missing column '{id_col}': {e}")))?;
     let unique_ids: Vec<String> = unique_id_col
         .str()
-        .map_err(|e| PyValueError::new_err(format!("Column 'unique_id' must be string type: {e}")))?
+        .map_err(|e| PyValueError::new_err(format!("Column '{id_col}' must be string type: {e}")))?
         .into_no_null_iter()
         .map(|s| s.to_string())
         .collect();
 
-    let dims: Vec<&str> = df.get_column_names()
-        .iter()
-        .filter(|s| s.as_str() != "unique_id")
-        .map(|s| s.as_str())
-        .collect();
+    let dims = dimension_columns(df, columns);
 
-    let mut dims_data: Vec<Vec<Vec<f64>>> = Vec::with_capacity(dims.len());
+    let mut dims_data: Vec<Vec<Vec<Option<f64>>>> = Vec::with_capacity(dims.len());
     for d in dims.iter() {
-        let col_series = df.column(d)
+        let col_series = df.column(d.as_str())
             .map_err(|e| PyKeyError::new_err(format!("Missing dimension column '{d}': {e}")))?;
-        let lists: Vec<Vec<f64>> = col_series
+        let lists: Vec<Vec<Option<f64>>> = col_series
             .list()
             .map_err(|e| PyValueError::new_err(format!("Column '{d}' must be list type: {e}")))?
             .into_iter()
-            .map(|opt_series| {
+            .zip(&unique_ids)
+            .map(|(opt_series, id)| {
                 let series = opt_series.ok_or_else(|| {
-                    PyValueError::new_err(format!("Null entry in dimension column '{d}'"))
+                    PyValueError::new_err(format!("Null entry in dimension column '{d}' for series {id}"))
                 })?;
                 let chunked = series.f64().map_err(|e| {
                     PyValueError::new_err(format!("Values in column '{d}' must be f64: {e}"))
                 })?;
-                Ok(chunked.into_no_null_iter().collect::<Vec<f64>>())
+                Ok(chunked.into_iter().collect::<Vec<Option<f64>>>())
             })
-            .collect::<PyResult<Vec<Vec<f64>>>>()?;
+            .collect::<PyResult<Vec<Vec<Option<f64>>>>>()?;
         dims_data.push(lists);
     }
 
     let mut hashmap = HashMap::new();
-    let num_series = unique_ids.len();
-    for i in 0..num_series {
-        let series_len = dims_data[0][i].len();
+    for (i, id) in unique_ids.iter().enumerate() {
+        let raw: Vec<&[Option<f64>]> = dims_data.iter().map(|dim| dim[i].as_slice()).collect();
+        let resolved = missing.resolve_multivariate(id, &raw)?;
+        let series_len = resolved.first().map_or(0, |dim| dim.len());
         let mut series: Vec<Vec<f64>> = Vec::with_capacity(series_len);
         for t in 0..series_len {
-            let point: Vec<f64> = dims_data.iter().map(|dim| dim[i][t]).collect();
+            let point: Vec<f64> = resolved.iter().map(|dim| dim[t]).collect();
             series.push(point);
         }
-        hashmap.insert(unique_ids[i].clone(), series);
+        hashmap.insert(id.clone(), series);
     }
     Ok(hashmap)
 }
 
-/// Generic pairwise computation for univariate distance functions.
+/// Grouped series from both inputs, keyed by id, plus the original id dtypes.
 ///
-/// Handles: column validation, casting, grouping, HashMap construction,
-/// Arc wrapping, parallel pairwise iteration with deduplication, output assembly.
-pub fn compute_pairwise<F>(
-    input1: PyDataFrame,
-    input2: PyDataFrame,
-    distance_col: &str,
-    distance_fn: F,
-) -> PyResult<PyDataFrame>
-where
-    F: Fn(&[f64], &[f64]) -> f64 + Send + Sync,
-{
-    let df_1: DataFrame = input1.into();
-    let df_2: DataFrame = input2.into();
+/// `ids_a` and `ids_b` list the ids of each side in ascending order of their
+/// original value, so numeric ids sort numerically. Every iteration goes
+/// through them rather than the maps, which keeps the output order stable.
+pub struct PairwiseInput<T> {
+    pub map_a: HashMap<String, T>,
+    pub map_b: HashMap<String, T>,
+    pub ids_a: Vec<String>,
+    pub ids_b: Vec<String>,
+    pub uid_a_dtype: DataType,
+    pub uid_b_dtype: DataType,
+}
+
+impl<T> PairwiseInput<T> {
+    fn check_mode(&self, mode: PairMode) -> PyResult<()> {
+        if mode == PairMode::SelfSymmetric && !self.same_ids() {
+            return Err(PyValueError::new_err(
+                "mode='self_symmetric' requires input1 and input2 to contain the same ids",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Left-side series in id order.
+    pub fn series_a(&self) -> Vec<(&String, &T)> {
+        self.ids_a.iter().map(|id| (id, &self.map_a[id])).collect()
+    }
+
+    /// Right-side series in id order.
+    pub fn series_b(&self) -> Vec<(&String, &T)> {
+        self.ids_b.iter().map(|id| (id, &self.map_b[id])).collect()
+    }
 
-    validate_column_exists(&df_1, "unique_id")?;
-    validate_column_exists(&df_1, "y")?;
-    validate_column_exists(&df_2, "unique_id")?;
-    validate_column_exists(&df_2, "y")?;
+    /// Whether both inputs contain exactly the same ids.
+    pub fn same_ids(&self) -> bool {
+        self.ids_a.len() == self.ids_b.len() && self.ids_a.iter().all(|id| self.map_b.contains_key(id))
+    }
+
+    /// Position of every left-side id in `ids_a`.
+    fn id_positions(&self) -> HashMap<&String, usize> {
+        self.ids_a.iter().enumerate().map(|(i, id)| (id, i)).collect()
+    }
+
+    /// Positions in `ids_b` of the right-side series paired with the left
+    /// series at `left_pos` of `ids_a`, in order.
+    ///
+    /// With [`PairMode::CrossFull`] every pair is kept. Otherwise self-pairs are
+    /// skipped, and when both ids exist on both sides only the pair whose left
+    /// id comes first in `ids_a` is kept.
+    fn partners<'a>(
+        &'a self,
+        mode: PairMode,
+        position: &'a HashMap<&String, usize>,
+        left_pos: usize,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let left_key = &self.ids_a[left_pos];
+        let left_shared = self.map_b.contains_key(left_key);
+        self.ids_b
+            .iter()
+            .enumerate()
+            .filter(move |&(_, right_key)| {
+                if mode.keeps_same_id() {
+                    return true;
+                }
+                left_key != right_key
+                    && !(left_shared && position.get(right_key).is_some_and(|&right_pos| left_pos >= right_pos))
+            })
+            .map(|(right_pos, _)| right_pos)
+    }
+
+    /// Enumerate the (left, right) series pairs to evaluate, row-major in id
+    /// order. See [`Self::partners`] for which pairs each mode keeps.
+    pub fn pairs(&self, mode: PairMode) -> Vec<(&String, &T, &String, &T)> {
+        let position = self.id_positions();
+        let right = self.series_b();
+        let mut pairs = Vec::new();
+        for (left_pos, (left_key, left_series)) in self.series_a().into_iter().enumerate() {
+            for right_pos in self.partners(mode, &position, left_pos) {
+                let (right_key, right_series) = right[right_pos];
+                pairs.push((left_key, left_series, right_key, right_series));
+            }
+        }
+        pairs
+    }
+
+    /// Self-pairs of the ids present in both inputs, in `ids_a` order.
+    pub fn self_pairs(&self) -> Vec<(&String, &T, &String, &T)> {
+        self.series_a()
+            .into_iter()
+            .filter_map(|(id, left)| self.map_b.get_key_value(id).map(|(right_id, right)| (id, left, right_id, right)))
+            .collect()
+    }
+}
 
-    let uid_a_dtype = df_1.column("unique_id")
+/// Unique values of the id column in ascending order of the original dtype, as strings.
+fn ordered_ids(df: &DataFrame, id_col: &str) -> PyResult<Vec<String>> {
+    let ids = df
+        .column(id_col)
         .map_err(|e| PyKeyError::new_err(e.to_string()))?
-        .dtype().clone();
-    let uid_b_dtype = df_2.column("unique_id")
+        .as_materialized_series()
+        .unique()
+        .and_then(|s| s.sort(SortOptions::default()))
+        .and_then(|s| s.cast(&DataType::String))
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let ids = ids
+        .str()
+        .map_err(|e| PyValueError::new_err(e.to_string()))?
+        .into_iter()
+        .flatten()
+        .map(|s| s.to_string())
+        .collect();
+    Ok(ids)
+}
+
+/// Read the dtype and the ordered ids of the id column, cast it to String
+/// and resolve the time column.
+fn prepare_ids(df: &DataFrame, columns: &SeriesColumns) -> PyResult<(DataFrame, DataType, Vec<String>, SeriesColumns)> {
+    let id_col = columns.id_col.as_str();
+    validate_column_exists(df, id_col)?;
+    let uid_dtype = df.column(id_col)
         .map_err(|e| PyKeyError::new_err(e.to_string()))?
         .dtype().clone();
-
-    let df_a = cast_column(&df_1, "unique_id", DataType::String)
+    let ids = ordered_ids(df, id_col)?;
+    let df = cast_column(df, id_col, DataType::String)
         .map_err(|e| PyValueError::new_err(e.to_string()))?;
-    let df_b = cast_column(&df_2, "unique_id", DataType::String)
+    let columns = columns.resolve(&df)?;
+    if let Some(time_col) = columns.time_col() {
+        validate_unique_timestamps(&df, id_col, time_col)?;
+    }
+    Ok((df, uid_dtype, ids, columns))
+}
+
+/// A univariate input loaded by `load_univariate`: id -> series map, ordered ids and original id dtype.
+pub type UnivariateInput = (HashMap<String, Vec<f64>>, Vec<String>, DataType);
+
+/// Validate, group and convert one univariate input into an id -> series map.
+pub fn load_univariate(df: &DataFrame, options: &PairwiseOptions) -> PyResult<UnivariateInput> {
+    validate_column_exists(df, &options.columns.target_col)?;
+    let (df, uid_dtype, ids, columns) = prepare_ids(df, &options.columns)?;
+    let grouped = get_groups(&df, &columns)
         .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let mut map = df_to_hashmap(&grouped, &columns, options.missing)?;
+    if options.normalize != Normalization::None {
+        for series in map.values_mut() {
+            options.normalize.apply(series);
+        }
+    }
+    Ok((map, ids, uid_dtype))
+}
+
+/// Validate, group and convert both univariate inputs into id -> series maps.
+pub fn prepare_univariate(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    options: &PairwiseOptions,
+) -> PyResult<PairwiseInput<Vec<f64>>> {
+    let (map_a, ids_a, uid_a_dtype) = load_univariate(&input1.into(), options)?;
+    let (map_b, ids_b, uid_b_dtype) = load_univariate(&input2.into(), options)?;
+
+    let input = PairwiseInput {
+        map_a,
+        map_b,
+        ids_a,
+        ids_b,
+        uid_a_dtype,
+        uid_b_dtype,
+    };
+    input.check_mode(options.mode)?;
+    Ok(input)
+}
+
+/// Validate, group and convert both multivariate inputs into id -> series maps.
+pub fn prepare_multivariate(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    options: &PairwiseOptions,
+) -> PyResult<PairwiseInput<Vec<Vec<f64>>>> {
+    let columns = &options.columns;
+    let df_1: DataFrame = input1.into();
+    let df_2: DataFrame = input2.into();
 
-    let grouped_a = get_groups(&df_a)
+    let (df_a, uid_a_dtype, ids_a, columns_a) = prepare_ids(&df_1, columns)?;
+    let (df_b, uid_b_dtype, ids_b, columns_b) = prepare_ids(&df_2, columns)?;
+
+    let grouped_a = get_groups_multivariate(&df_a, &columns_a)
         .map_err(|e| PyValueError::new_err(e.to_string()))?;
-    let grouped_b = get_groups(&df_b)
+    let grouped_b = get_groups_multivariate(&df_b, &columns_b)
         .map_err(|e| PyValueError::new_err(e.to_string()))?;
 
-    let raw_map_a = df_to_hashmap(&grouped_a)?;
-    let raw_map_b = df_to_hashmap(&grouped_b)?;
-
-    let map_a = Arc::new(raw_map_a);
-    let map_b = Arc::new(raw_map_b);
-
-    let left_series_by_key: Vec<(&String, &Vec<f64>)> = map_a.iter().collect();
-    let right_series_by_key: Vec<(&String, &Vec<f64>)> = map_b.iter().collect();
-
-    let distance_fn = Arc::new(distance_fn);
-    let results: Vec<(String, String, f64)> = left_series_by_key
-        .par_iter()
-        .flat_map(|&(left_key, left_series)| {
-            let map_a = Arc::clone(&map_a);
-            let map_b = Arc::clone(&map_b);
-            let distance_fn = Arc::clone(&distance_fn);
-            right_series_by_key
-                .par_iter()
-                .filter_map(move |&(right_key, right_series)| {
-                    if left_key == right_key {
-                        return None;
+    let mut map_a = df_to_hashmap_multivariate(&grouped_a, &columns_a, options.missing)?;
+    let mut map_b = df_to_hashmap_multivariate(&grouped_b, &columns_b, options.missing)?;
+    if options.normalize != Normalization::None {
+        for series in map_a.values_mut().chain(map_b.values_mut()) {
+            options.normalize.apply_multivariate(series);
+        }
+    }
+
+    let input = PairwiseInput {
+        map_a,
+        map_b,
+        ids_a,
+        ids_b,
+        uid_a_dtype,
+        uid_b_dtype,
+    };
+    input.check_mode(options.mode)?;
+    input.dimensions()?;
+    Ok(input)
+}
+
+impl PairwiseInput<Vec<Vec<f64>>> {
+    /// The number of dimensions shared by every non-empty series of both
+    /// inputs, or `None` when all series are empty.
+    pub fn dimensions(&self) -> PyResult<Option<usize>> {
+        let mut dims: Option<(usize, &String)> = None;
+        for (id, series) in self.series_a().into_iter().chain(self.series_b()) {
+            let Some(point) = series.first() else { continue };
+            match dims {
+                None => dims = Some((point.len(), id)),
+                Some((expected, first_id)) if expected != point.len() => {
+                    return Err(PyValueError::new_err(format!(
+                        "All series must have the same number of dimensions: \
+                         series {first_id} has {expected} but series {id} has {}",
+                        point.len()
+                    )));
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(dims.map(|(d, _)| d))
+    }
+}
+
+/// How null and NaN observations inside a series are handled before they
+/// reach a kernel. NaN would otherwise poison every DP cell it touches.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MissingPolicy {
+    /// Reject the series, naming its id and the first missing position.
+    #[default]
+    Error,
+    /// Remove missing observations, shortening the series.
+    Drop,
+    /// Fill interior gaps linearly by position; leading and trailing gaps
+    /// take the nearest observed value.
+    LinearInterpolate,
+    /// Carry the last observed value forward; leading gaps take the first
+    /// observed value.
+    Ffill,
+}
+
+impl MissingPolicy {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "error" => Ok(MissingPolicy::Error),
+            "drop" => Ok(MissingPolicy::Drop),
+            "linear_interpolate" => Ok(MissingPolicy::LinearInterpolate),
+            "ffill" => Ok(MissingPolicy::Ffill),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown missing policy: '{name}'. Expected one of: error, drop, linear_interpolate, ffill"
+            ))),
+        }
+    }
+
+    /// Resolve the missing observations of series `id`.
+    pub fn resolve(self, id: &str, values: &[Option<f64>]) -> PyResult<Vec<f64>> {
+        let observed = |v: &Option<f64>| v.filter(|x| !x.is_nan());
+        let Some(first) = values.iter().position(|v| observed(v).is_none()) else {
+            return Ok(values.iter().map(|v| v.unwrap_or(f64::NAN)).collect());
+        };
+        if self == MissingPolicy::Error {
+            return Err(PyValueError::new_err(format!(
+                "Series {id} has a missing value (null or NaN) at position {first}. \
+                 Pass missing='drop', 'linear_interpolate' or 'ffill' to handle it."
+            )));
+        }
+        let known: Vec<(usize, f64)> = values
+            .iter()
+            .enumerate()
+            .filter_map(|(i, v)| observed(v).map(|x| (i, x)))
+            .collect();
+        if known.is_empty() {
+            return Err(PyValueError::new_err(format!("Series {id} has no observed values")));
+        }
+        let filled = match self {
+            MissingPolicy::Error => unreachable!(),
+            MissingPolicy::Drop => known.into_iter().map(|(_, x)| x).collect(),
+            MissingPolicy::Ffill => {
+                let mut last = known[0].1;
+                values
+                    .iter()
+                    .map(|v| {
+                        if let Some(x) = observed(v) {
+                            last = x;
+                        }
+                        last
+                    })
+                    .collect()
+            }
+            MissingPolicy::LinearInterpolate => {
+                // `next` indexes the first known point at or after position i
+                let mut next = 0;
+                (0..values.len())
+                    .map(|i| {
+                        while next < known.len() && known[next].0 < i {
+                            next += 1;
+                        }
+                        match (next.checked_sub(1).map(|p| known[p]), known.get(next)) {
+                            (_, Some(&(j, x))) if j == i => x,
+                            (Some((i0, x0)), Some(&(i1, x1))) => {
+                                x0 + (x1 - x0) * (i - i0) as f64 / (i1 - i0) as f64
+                            }
+                            (Some((_, x0)), None) => x0,
+                            (None, Some(&(_, x1))) => x1,
+                            (None, None) => unreachable!(),
+                        }
+                    })
+                    .collect()
+            }
+        };
+        Ok(filled)
+    }
+
+    /// Resolve the missing observations of a multivariate series `id` given
+    /// as one slice per dimension. `drop` removes a time step when any of its
+    /// dimensions is missing so the dimensions stay aligned; the other
+    /// policies fill each dimension independently.
+    pub fn resolve_multivariate(self, id: &str, dims: &[&[Option<f64>]]) -> PyResult<Vec<Vec<f64>>> {
+        if self != MissingPolicy::Drop {
+            return dims.iter().map(|dim| self.resolve(id, dim)).collect();
+        }
+        let len = dims.first().map_or(0, |dim| dim.len());
+        let complete: Vec<usize> = (0..len)
+            .filter(|&t| dims.iter().all(|dim| dim[t].is_some_and(|x| !x.is_nan())))
+            .collect();
+        if complete.is_empty() && len > 0 {
+            return Err(PyValueError::new_err(format!("Series {id} has no observed values")));
+        }
+        Ok(dims
+            .iter()
+            .map(|dim| complete.iter().map(|&t| dim[t].unwrap_or(f64::NAN)).collect())
+            .collect())
+    }
+}
+
+/// Per-series rescaling applied once to every series before any distance is computed.
+///
+/// Constant series (zero spread) are only centred, which maps them to zeros.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Normalization {
+    #[default]
+    None,
+    /// `(x - mean) / std`, population standard deviation.
+    ZScore,
+    /// `(x - min) / (max - min)`, into [0, 1].
+    MinMax,
+    /// `(x - mean) / (max - min)`.
+    Mean,
+    /// `(x - median) / MAD`, with MAD the median absolute deviation.
+    Robust,
+}
+
+impl Normalization {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "none" => Ok(Normalization::None),
+            "zscore" => Ok(Normalization::ZScore),
+            "minmax" => Ok(Normalization::MinMax),
+            "mean" => Ok(Normalization::Mean),
+            "robust" => Ok(Normalization::Robust),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown normalization: '{name}'. Expected one of: none, zscore, minmax, mean, robust"
+            ))),
+        }
+    }
+
+    /// Rescale a univariate series in place.
+    pub fn apply(self, values: &mut [f64]) {
+        if values.is_empty() {
+            return;
+        }
+        let (center, scale) = match self {
+            Normalization::None => return,
+            Normalization::ZScore => {
+                let mean = mean(values);
+                let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+                (mean, var.sqrt())
+            }
+            Normalization::MinMax => {
+                let (min, max) = min_max(values);
+                (min, max - min)
+            }
+            Normalization::Mean => {
+                let (min, max) = min_max(values);
+                (mean(values), max - min)
+            }
+            Normalization::Robust => {
+                let median = median(values);
+                let deviations: Vec<f64> = values.iter().map(|v| (v - median).abs()).collect();
+                (median, self::median(&deviations))
+            }
+        };
+        let scale = if scale > 0.0 { scale } else { 1.0 };
+        for v in values.iter_mut() {
+            *v = (*v - center) / scale;
+        }
+    }
+
+    /// Rescale each dimension of a multivariate series independently.
+    pub fn apply_multivariate(self, series: &mut [Vec<f64>]) {
+        let n_dims = series.first().map_or(0, |point| point.len());
+        for d in 0..n_dims {
+            let mut values: Vec<f64> = series.iter().map(|point| point[d]).collect();
+            self.apply(&mut values);
+            for (point, v) in series.iter_mut().zip(values) {
+                point[d] = v;
+            }
+        }
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn min_max(values: &[f64]) -> (f64, f64) {
+    values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| (lo.min(v), hi.max(v)))
+}
+
+fn median(values: &[f64]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+/// Options shared by every `compute_pairwise_*` entry point.
+///
+/// They are passed from Python as keyword arguments after the
+/// metric-specific parameters:
+/// - `k`: keep only the `k` nearest `input2` series of each `input1` id,
+///   returned with a 1-based `rank` column.
+/// - `output`: `"long"` (default), `"matrix"` or `"condensed"`, see [`OutputFormat`].
+/// - `id_col`, `target_col`, `time_col`: input column names, see [`SeriesColumns`].
+///   Observations are sorted by `time_col` (default: `ds` when present;
+///   pass `None` to keep the row order) and duplicate timestamps are rejected.
+///   `target_col` is ignored by multivariate metrics, which use every column
+///   other than the id and time columns as a dimension.
+/// - `normalize`: per-series rescaling applied before any distance is computed,
+///   see [`Normalization`]. Multivariate series are rescaled per dimension.
+/// - `mode`: `"cross_dedup"` (default), `"cross_full"` or `"self_symmetric"`,
+///   see [`PairMode`].
+/// - `missing`: `"error"` (default), `"drop"`, `"linear_interpolate"` or
+///   `"ffill"`, see [`MissingPolicy`]. Applied before `normalize`.
+/// - `n_jobs`: number of worker threads, see [`JobPool`]. Defaults to
+///   rayon's global pool; `1` runs sequentially.
+/// - `sink_parquet`: path of a Parquet file the long output is streamed to
+///   block by block instead of being returned, for inputs whose full
+///   distance table does not fit in memory.
+/// - `memory_budget`: approximate bytes of results held in memory at once
+///   with `sink_parquet` (default 256 MiB); the inputs are not counted.
+#[derive(Clone, Debug, Default)]
+pub struct PairwiseOptions {
+    pub k: Option<usize>,
+    pub output: OutputFormat,
+    pub columns: SeriesColumns,
+    pub normalize: Normalization,
+    pub mode: PairMode,
+    pub missing: MissingPolicy,
+    pub n_jobs: Option<usize>,
+    pub sink_parquet: Option<String>,
+    pub memory_budget: Option<usize>,
+}
+
+/// Shape of the DataFrame returned by the pairwise engine.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OutputFormat {
+    /// One `id_1`, `id_2`, distance row per evaluated pair.
+    #[default]
+    Long,
+    /// Dense wide matrix: an id column with the `input1` ids in order,
+    /// followed by one Float64 column per `input2` id.
+    Matrix,
+    /// Upper triangle of the symmetric matrix as `id_1`, `id_2`, distance
+    /// rows in row-major order (the layout of `scipy.spatial.distance.pdist`).
+    Condensed,
+}
+
+impl OutputFormat {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "long" => Ok(OutputFormat::Long),
+            "matrix" => Ok(OutputFormat::Matrix),
+            "condensed" => Ok(OutputFormat::Condensed),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown output format: '{name}'. Expected one of: long, matrix, condensed"
+            ))),
+        }
+    }
+}
+
+/// Which `(input1, input2)` pairs the pairwise engine evaluates.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PairMode {
+    /// Every pair except same-id pairs; when an id pair exists in both
+    /// directions it is evaluated once, in `input1` id order.
+    #[default]
+    CrossDedup,
+    /// The full rectangular table, including pairs of series that share an id.
+    /// Use this when `input1` is a query set and `input2` a reference set.
+    CrossFull,
+    /// Upper triangle of a self-distance matrix. Both inputs must contain
+    /// the same ids.
+    SelfSymmetric,
+}
+
+impl PairMode {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "cross_dedup" => Ok(PairMode::CrossDedup),
+            "cross_full" => Ok(PairMode::CrossFull),
+            "self_symmetric" => Ok(PairMode::SelfSymmetric),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown mode: '{name}'. Expected one of: self_symmetric, cross_full, cross_dedup"
+            ))),
+        }
+    }
+
+    /// Whether a left and a right series with the same id are compared.
+    pub fn keeps_same_id(self) -> bool {
+        self == PairMode::CrossFull
+    }
+}
+
+impl PairwiseOptions {
+    /// Parse the `**options` keyword arguments of a pairwise entry point.
+    pub fn from_kwargs(kwargs: Option<&Bound<'_, PyDict>>) -> PyResult<Self> {
+        let mut options = PairwiseOptions::default();
+        let Some(kwargs) = kwargs else {
+            return Ok(options);
+        };
+        for (key, value) in kwargs.iter() {
+            let key: String = key.extract()?;
+            match key.as_str() {
+                "k" => {
+                    let k: Option<usize> = value.extract()?;
+                    if k == Some(0) {
+                        return Err(PyValueError::new_err("k must be >= 1"));
                     }
-                    if map_b.contains_key(left_key) && map_a.contains_key(right_key) && left_key >= right_key {
-                        return None;
+                    options.k = k;
+                }
+                "output" => {
+                    let output: String = value.extract()?;
+                    options.output = OutputFormat::parse(&output)?;
+                }
+                "id_col" => options.columns.id_col = value.extract()?,
+                "target_col" => options.columns.target_col = value.extract()?,
+                "time_col" => {
+                    let time_col: Option<String> = value.extract()?;
+                    options.columns.time_col = time_col.map_or(TimeColumn::Disabled, TimeColumn::Named);
+                }
+                "normalize" => {
+                    let normalize: String = value.extract()?;
+                    options.normalize = Normalization::parse(&normalize)?;
+                }
+                "mode" => {
+                    let mode: String = value.extract()?;
+                    options.mode = PairMode::parse(&mode)?;
+                }
+                "missing" => {
+                    let missing: String = value.extract()?;
+                    options.missing = MissingPolicy::parse(&missing)?;
+                }
+                "n_jobs" => {
+                    let n_jobs: Option<usize> = value.extract()?;
+                    JobPool::validate(n_jobs)?;
+                    options.n_jobs = n_jobs;
+                }
+                "sink_parquet" => options.sink_parquet = value.extract()?,
+                "memory_budget" => {
+                    let memory_budget: Option<usize> = value.extract()?;
+                    if memory_budget == Some(0) {
+                        return Err(PyValueError::new_err("memory_budget must be a positive number of bytes"));
                     }
-                    let distance = distance_fn(left_series, right_series);
-                    Some((left_key.clone(), right_key.clone(), distance))
-                })
-        })
-        .collect();
+                    options.memory_budget = memory_budget;
+                }
+                _ => {
+                    return Err(PyTypeError::new_err(format!(
+                        "Unexpected keyword argument '{key}'. \
+                         Valid options: k, output, id_col, target_col, time_col, normalize, mode, missing, n_jobs, \
+                         sink_parquet, memory_budget"
+                    )));
+                }
+            }
+        }
+        if options.k.is_some() && options.output != OutputFormat::Long {
+            return Err(PyValueError::new_err("k can only be combined with output='long'"));
+        }
+        if options.sink_parquet.is_some() && (options.k.is_some() || options.output != OutputFormat::Long) {
+            return Err(PyValueError::new_err("sink_parquet only supports output='long' without k"));
+        }
+        if options.memory_budget.is_some() && options.sink_parquet.is_none() {
+            return Err(PyValueError::new_err("memory_budget only applies together with sink_parquet"));
+        }
+        Ok(options)
+    }
 
-    build_output_df(&results, distance_col, &uid_a_dtype, &uid_b_dtype)
+    /// The thread pool selected by `n_jobs`.
+    pub(crate) fn job_pool(&self) -> PyResult<JobPool> {
+        JobPool::new(self.n_jobs)
+    }
+
+    /// Reject the options that only apply to the generic distance table,
+    /// for entry points that produce their own output shape.
+    pub fn ensure_default_output(&self, entry_point: &str) -> PyResult<()> {
+        if self.k.is_some() || self.output != OutputFormat::Long || self.sink_parquet.is_some() {
+            return Err(PyValueError::new_err(format!(
+                "{entry_point} does not support the 'k', 'output' or 'sink_parquet' options"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reject a non-default `mode`, for entry points that do not enumerate
+    /// id pairs.
+    pub fn ensure_default_mode(&self, entry_point: &str) -> PyResult<()> {
+        if self.mode != PairMode::default() {
+            return Err(PyValueError::new_err(format!("{entry_point} does not support the 'mode' option")));
+        }
+        Ok(())
+    }
+}
+
+/// Generic pairwise computation for univariate distance functions.
+///
+/// Handles: column validation, casting, grouping, HashMap construction,
+/// parallel pairwise iteration with deduplication, output assembly.
+pub fn compute_pairwise<F>(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    distance_col: &str,
+    options: &PairwiseOptions,
+    distance_fn: F,
+) -> PyResult<PyDataFrame>
+where
+    F: Fn(&[f64], &[f64]) -> f64 + Send + Sync,
+{
+    let input = prepare_univariate(input1, input2, options)?;
+    run_pairwise(&input, distance_col, options, |a: &Vec<f64>, b: &Vec<f64>| distance_fn(a, b))
 }
 
 /// Generic pairwise computation for multivariate distance functions.
@@ -236,66 +943,336 @@ pub fn compute_pairwise_multivariate<F>(
     input1: PyDataFrame,
     input2: PyDataFrame,
     distance_col: &str,
+    options: &PairwiseOptions,
     distance_fn: F,
 ) -> PyResult<PyDataFrame>
 where
     F: Fn(&[Vec<f64>], &[Vec<f64>]) -> f64 + Send + Sync,
 {
-    let df_1: DataFrame = input1.into();
-    let df_2: DataFrame = input2.into();
+    let input = prepare_multivariate(input1, input2, options)?;
+    run_pairwise(&input, distance_col, options, |a: &Vec<Vec<f64>>, b: &Vec<Vec<f64>>| distance_fn(a, b))
+}
+
+/// Evaluate `distance_fn` over the prepared inputs according to `options`.
+///
+/// Work items are enumerated in id order and rayon's indexed `collect`
+/// preserves that order, so the output is identical from run to run.
+pub(crate) fn run_pairwise<T, F>(
+    input: &PairwiseInput<T>,
+    distance_col: &str,
+    options: &PairwiseOptions,
+    distance_fn: F,
+) -> PyResult<PyDataFrame>
+where
+    T: Sync,
+    F: Fn(&T, &T) -> f64 + Send + Sync,
+{
+    let pool = options.job_pool()?;
+    if let Some(path) = &options.sink_parquet {
+        let memory_budget = options.memory_budget.unwrap_or(DEFAULT_MEMORY_BUDGET);
+        return sink_parquet(&pool, input, distance_col, options.mode, path, memory_budget, &distance_fn);
+    }
+    if let Some(k) = options.k {
+        let results = nearest_neighbours(&pool, input, k, options.mode, &distance_fn)?;
+        return build_knn_output_df(&results, distance_col, &input.uid_a_dtype, &input.uid_b_dtype);
+    }
 
-    validate_column_exists(&df_1, "unique_id")?;
-    validate_column_exists(&df_2, "unique_id")?;
+    let mut pairs = input.pairs(options.mode);
+    if options.output == OutputFormat::Matrix && !options.mode.keeps_same_id() {
+        // The diagonal holds each metric's own self-value: zero for distances,
+        // but not for soft-DTW or a kernel such as GAK
+        pairs.extend(input.self_pairs());
+    }
+    let results: Vec<(String, String, f64)> = par_map_interruptible(
+        &pool,
+        &pairs,
+        |&(left_key, left_series, right_key, right_series)| {
+            let distance = distance_fn(left_series, right_series);
+            (left_key.clone(), right_key.clone(), distance)
+        },
+    )?;
 
-    let uid_a_dtype = df_1.column("unique_id")
-        .map_err(|e| PyKeyError::new_err(e.to_string()))?
-        .dtype().clone();
-    let uid_b_dtype = df_2.column("unique_id")
-        .map_err(|e| PyKeyError::new_err(e.to_string()))?
-        .dtype().clone();
+    match options.output {
+        OutputFormat::Long => build_output_df(&results, distance_col, &input.uid_a_dtype, &input.uid_b_dtype),
+        OutputFormat::Matrix => build_matrix_df(input, &results, &options.columns.id_col),
+        OutputFormat::Condensed => build_condensed_df(input, &results, distance_col),
+    }
+}
 
-    let df_a = cast_column(&df_1, "unique_id", DataType::String)
-        .map_err(|e| PyValueError::new_err(e.to_string()))?;
-    let df_b = cast_column(&df_2, "unique_id", DataType::String)
-        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+/// The threads an entry point runs its parallel work on, chosen by its
+/// `n_jobs` argument.
+///
+/// `None` uses rayon's global pool; `Some(n)` builds a pool of exactly `n`
+/// threads for the duration of the call, so callers that already
+/// parallelize (multiprocessing, Dask workers) can avoid oversubscribing
+/// cores. `Some(1)` evaluates everything in order on the calling thread.
+pub(crate) enum JobPool {
+    Global,
+    Sequential,
+    Scoped(rayon::ThreadPool),
+}
 
-    let grouped_a = get_groups_multivariate(&df_a)
-        .map_err(|e| PyValueError::new_err(e.to_string()))?;
-    let grouped_b = get_groups_multivariate(&df_b)
+impl JobPool {
+    pub(crate) fn validate(n_jobs: Option<usize>) -> PyResult<()> {
+        if n_jobs == Some(0) {
+            return Err(PyValueError::new_err("n_jobs must be >= 1"));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn new(n_jobs: Option<usize>) -> PyResult<Self> {
+        Self::validate(n_jobs)?;
+        match n_jobs {
+            None => Ok(JobPool::Global),
+            Some(1) => Ok(JobPool::Sequential),
+            Some(n) => rayon::ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build()
+                .map(JobPool::Scoped)
+                .map_err(|e| PyValueError::new_err(format!("Could not start {n} worker threads: {e}"))),
+        }
+    }
+
+    fn num_threads(&self) -> usize {
+        match self {
+            JobPool::Global => rayon::current_num_threads(),
+            JobPool::Sequential => 1,
+            JobPool::Scoped(pool) => pool.current_num_threads(),
+        }
+    }
+
+    /// Map `f` over `items` on this pool, preserving their order.
+    pub(crate) fn map<I, T, F>(&self, items: &[I], f: F) -> Vec<T>
+    where
+        I: Sync,
+        T: Send,
+        F: Fn(&I) -> T + Sync,
+    {
+        match self {
+            JobPool::Global => items.par_iter().map(&f).collect(),
+            JobPool::Sequential => items.iter().map(f).collect(),
+            JobPool::Scoped(pool) => pool.install(|| items.par_iter().map(&f).collect()),
+        }
+    }
+}
+
+/// Target wall time of one GIL-released batch in [`par_map_interruptible`].
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Map `f` over `items` on `pool` with the GIL released.
+///
+/// Items are processed in batches, re-acquiring the GIL between batches to
+/// run `Python::check_signals`, so a Ctrl-C surfaces as `KeyboardInterrupt`
+/// within roughly [`SIGNAL_CHECK_INTERVAL`] instead of after the whole job.
+/// Batches start at one item per thread and double while they finish within
+/// the interval, keeping the overhead negligible for cheap items. Results
+/// are in the order of `items`.
+pub(crate) fn par_map_interruptible<I, T, F>(pool: &JobPool, items: &[I], f: F) -> PyResult<Vec<T>>
+where
+    I: Sync,
+    T: Send,
+    F: Fn(&I) -> T + Sync,
+{
+    Python::with_gil(|py| {
+        let mut results = Vec::with_capacity(items.len());
+        let mut batch = pool.num_threads().max(1);
+        let mut start = 0;
+        while start < items.len() {
+            let end = (start + batch).min(items.len());
+            let started = Instant::now();
+            let chunk = py.allow_threads(|| pool.map(&items[start..end], &f));
+            results.extend(chunk);
+            py.check_signals()?;
+            if started.elapsed() < SIGNAL_CHECK_INTERVAL {
+                batch *= 2;
+            }
+            start = end;
+        }
+        Ok(results)
+    })
+}
+
+/// Default `memory_budget` for `sink_parquet`.
+const DEFAULT_MEMORY_BUDGET: usize = 256 << 20;
+
+/// Evaluate the pairs block by block and stream them to the Parquet file
+/// at `path`, so the full distance table never has to fit in memory.
+///
+/// Pairs are enumerated lazily in the same row-major order as
+/// [`PairwiseInput::pairs`] and cut into blocks whose estimated size stays
+/// within `memory_budget` bytes. Each block is computed on `pool` and
+/// written as one Parquet row group with the long-output columns. Returns a
+/// one-row summary with the `path`, the number of `pairs` written and the
+/// number of `blocks`.
+fn sink_parquet<T, F>(
+    pool: &JobPool,
+    input: &PairwiseInput<T>,
+    distance_col: &str,
+    mode: PairMode,
+    path: &str,
+    memory_budget: usize,
+    distance_fn: &F,
+) -> PyResult<PyDataFrame>
+where
+    T: Sync,
+    F: Fn(&T, &T) -> f64 + Sync,
+{
+    let to_py = |e: PolarsError| PyValueError::new_err(e.to_string());
+    let left = input.series_a();
+    let right = input.series_b();
+    let position = input.id_positions();
+
+    // Per pair: its two positions, the distance, and both ids with their
+    // Arrow offsets.
+    let mean_id_len = |ids: &[String]| ids.iter().map(String::len).sum::<usize>() / ids.len().max(1);
+    let pair_bytes =
+        2 * size_of::<usize>() + 3 * size_of::<f64>() + mean_id_len(&input.ids_a) + mean_id_len(&input.ids_b);
+    let block_size = (memory_budget / pair_bytes).max(1);
+
+    let schema = Schema::from_iter([
+        Field::new("id_1".into(), input.uid_a_dtype.clone()),
+        Field::new("id_2".into(), input.uid_b_dtype.clone()),
+        Field::new(distance_col.into(), DataType::Float64),
+    ]);
+    let file = std::fs::File::create(path).map_err(|e| PyIOError::new_err(format!("Cannot create '{path}': {e}")))?;
+    let mut writer = ParquetWriter::new(file).batched(&schema).map_err(to_py)?;
+
+    let all_pairs = (0..left.len())
+        .flat_map(|left_pos| input.partners(mode, &position, left_pos).map(move |right_pos| (left_pos, right_pos)));
+    let (mut pairs, mut blocks) = (0u64, 0u64);
+    for chunk in &all_pairs.chunks(block_size) {
+        let block: Vec<(usize, usize)> = chunk.collect();
+        let distances = par_map_interruptible(pool, &block, |&(i, j)| distance_fn(left[i].1, right[j].1))?;
+        let df = DataFrame::new(vec![
+            Column::new("id_1".into(), block.iter().map(|&(i, _)| left[i].0.as_str()).collect::<Vec<_>>()),
+            Column::new("id_2".into(), block.iter().map(|&(_, j)| right[j].0.as_str()).collect::<Vec<_>>()),
+            Column::new(distance_col.into(), distances),
+        ])
+        .map_err(to_py)?;
+        let mut df = cast_id_columns(df, &input.uid_a_dtype, &input.uid_b_dtype)?;
+        writer.write_batch(df.align_chunks()).map_err(to_py)?;
+        pairs += block.len() as u64;
+        blocks += 1;
+    }
+    writer.finish().map_err(to_py)?;
+
+    DataFrame::new(vec![
+        Column::new("path".into(), [path]),
+        Column::new("pairs".into(), [pairs]),
+        Column::new("blocks".into(), [blocks]),
+    ])
+    .map(PyDataFrame)
+    .map_err(to_py)
+}
+
+/// Look up the distance between two ids from the evaluated pair results.
+///
+/// A deduplicated pair fills both directions unless the mirrored pair was
+/// evaluated as well.
+fn pair_lookup(results: &[(String, String, f64)]) -> HashMap<(&str, &str), f64> {
+    let mut lookup = HashMap::with_capacity(results.len() * 2);
+    for (id1, id2, d) in results {
+        lookup.insert((id1.as_str(), id2.as_str()), *d);
+    }
+    for (id1, id2, d) in results {
+        lookup.entry((id2.as_str(), id1.as_str())).or_insert(*d);
+    }
+    lookup
+}
+
+fn lookup_distance(lookup: &HashMap<(&str, &str), f64>, id1: &str, id2: &str) -> f64 {
+    lookup.get(&(id1, id2)).copied().unwrap_or(f64::NAN)
+}
+
+/// Assemble the dense wide distance matrix: an `id_col` column with the
+/// `input1` ids, then one column per `input2` id.
+fn build_matrix_df<T>(
+    input: &PairwiseInput<T>,
+    results: &[(String, String, f64)],
+    id_col: &str,
+) -> PyResult<PyDataFrame> {
+    let lookup = pair_lookup(results);
+    let row_ids: Vec<&str> = input.ids_a.iter().map(String::as_str).collect();
+    let col_ids: Vec<&str> = input.ids_b.iter().map(String::as_str).collect();
+
+    let id_column = Column::new(id_col.into(), &row_ids)
+        .cast(&input.uid_a_dtype)
         .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let mut columns = Vec::with_capacity(col_ids.len() + 1);
+    columns.push(id_column);
+    for &col_id in &col_ids {
+        let values: Vec<f64> = row_ids
+            .iter()
+            .map(|&row_id| lookup_distance(&lookup, row_id, col_id))
+            .collect();
+        columns.push(Column::new(col_id.into(), values));
+    }
 
-    let raw_map_a = df_to_hashmap_multivariate(&grouped_a)?;
-    let raw_map_b = df_to_hashmap_multivariate(&grouped_b)?;
-
-    let map_a = Arc::new(raw_map_a);
-    let map_b = Arc::new(raw_map_b);
-
-    let left_series_by_key: Vec<(&String, &Vec<Vec<f64>>)> = map_a.iter().collect();
-    let right_series_by_key: Vec<(&String, &Vec<Vec<f64>>)> = map_b.iter().collect();
-
-    let distance_fn = Arc::new(distance_fn);
-    let results: Vec<(String, String, f64)> = left_series_by_key
-        .par_iter()
-        .flat_map(|&(left_key, left_series)| {
-            let map_a = Arc::clone(&map_a);
-            let map_b = Arc::clone(&map_b);
-            let distance_fn = Arc::clone(&distance_fn);
-            right_series_by_key
-                .par_iter()
-                .filter_map(move |&(right_key, right_series)| {
-                    if left_key == right_key {
-                        return None;
-                    }
-                    if map_b.contains_key(left_key) && map_a.contains_key(right_key) && left_key >= right_key {
-                        return None;
-                    }
-                    let distance = distance_fn(left_series, right_series);
-                    Some((left_key.clone(), right_key.clone(), distance))
-                })
-        })
-        .collect();
+    DataFrame::new(columns)
+        .map(PyDataFrame)
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Assemble the condensed upper triangle of a self-distance matrix.
+fn build_condensed_df<T>(
+    input: &PairwiseInput<T>,
+    results: &[(String, String, f64)],
+    distance_col: &str,
+) -> PyResult<PyDataFrame> {
+    if !input.same_ids() {
+        return Err(PyValueError::new_err(
+            "output='condensed' requires input1 and input2 to contain the same ids",
+        ));
+    }
+    let ids: Vec<&str> = input.ids_a.iter().map(String::as_str).collect();
+    let lookup = pair_lookup(results);
+    let mut condensed = Vec::with_capacity(ids.len() * ids.len().saturating_sub(1) / 2);
+    for (i, &id1) in ids.iter().enumerate() {
+        for &id2 in &ids[i + 1..] {
+            condensed.push((id1.to_string(), id2.to_string(), lookup_distance(&lookup, id1, id2)));
+        }
+    }
+    build_output_df(&condensed, distance_col, &input.uid_a_dtype, &input.uid_b_dtype)
+}
+
+/// For every left-side id, keep the `k` closest right-side series in a
+/// bounded max-heap instead of materializing all pairs.
+///
+/// Returns `(query_id, neighbour_id, rank, distance)` rows, ranks 1-based.
+fn nearest_neighbours<T, F>(
+    pool: &JobPool,
+    input: &PairwiseInput<T>,
+    k: usize,
+    mode: PairMode,
+    distance_fn: &F,
+) -> PyResult<Vec<(String, String, i64, f64)>>
+where
+    T: Sync,
+    F: Fn(&T, &T) -> f64 + Sync,
+{
+    let left = input.series_a();
+    let right = input.series_b();
 
-    build_output_df(&results, distance_col, &uid_a_dtype, &uid_b_dtype)
+    let per_query = par_map_interruptible(pool, &left, |&(left_key, left_series)| {
+        let mut heap: BinaryHeap<(OrderedFloat<f64>, usize)> = BinaryHeap::with_capacity(k + 1);
+        for (idx, &(right_key, right_series)) in right.iter().enumerate() {
+            if left_key == right_key && !mode.keeps_same_id() {
+                continue;
+            }
+            heap.push((OrderedFloat(distance_fn(left_series, right_series)), idx));
+            if heap.len() > k {
+                heap.pop();
+            }
+        }
+        let mut neighbours = heap.into_vec();
+        neighbours.sort_unstable();
+        neighbours
+            .into_iter()
+            .enumerate()
+            .map(|(rank, (d, idx))| (left_key.clone(), right[idx].0.clone(), rank as i64 + 1, d.into_inner()))
+            .collect::<Vec<_>>()
+    })?;
+    Ok(per_query.into_iter().flatten().collect())
 }
 
 /// Build the output DataFrame from pairwise results, casting IDs back to original dtypes.
@@ -314,9 +1291,42 @@ fn build_output_df(
         Column::new("id_2".into(), id2s),
         Column::new(distance_col.into(), vals),
     ];
-    let mut out_df = DataFrame::new(columns)
+    let out_df = DataFrame::new(columns)
         .map_err(|e| PyValueError::new_err(e.to_string()))?;
 
+    cast_id_columns(out_df, uid_a_dtype, uid_b_dtype).map(PyDataFrame)
+}
+
+/// Build the output DataFrame of a nearest-neighbour query: `id_1` (query),
+/// `id_2` (neighbour), `rank` (1-based) and the distance column.
+pub fn build_knn_output_df(
+    results: &[(String, String, i64, f64)],
+    distance_col: &str,
+    uid_a_dtype: &DataType,
+    uid_b_dtype: &DataType,
+) -> PyResult<PyDataFrame> {
+    let id1s: Vec<&str> = results.iter().map(|(id1, _, _, _)| id1.as_str()).collect();
+    let id2s: Vec<&str> = results.iter().map(|(_, id2, _, _)| id2.as_str()).collect();
+    let ranks: Vec<i64> = results.iter().map(|(_, _, r, _)| *r).collect();
+    let vals: Vec<f64> = results.iter().map(|(_, _, _, v)| *v).collect();
+
+    let out_df = DataFrame::new(vec![
+        Column::new("id_1".into(), id1s),
+        Column::new("id_2".into(), id2s),
+        Column::new("rank".into(), ranks),
+        Column::new(distance_col.into(), vals),
+    ])
+    .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+    cast_id_columns(out_df, uid_a_dtype, uid_b_dtype).map(PyDataFrame)
+}
+
+/// Cast the `id_1` / `id_2` columns of an output DataFrame back to the original id dtypes.
+pub fn cast_id_columns(
+    mut out_df: DataFrame,
+    uid_a_dtype: &DataType,
+    uid_b_dtype: &DataType,
+) -> PyResult<DataFrame> {
     let id1_casted = out_df.column("id_1")
         .map_err(|e| PyValueError::new_err(e.to_string()))?
         .cast(uid_a_dtype)
@@ -331,5 +1341,112 @@ fn build_output_df(
     let _ = out_df.with_column(id2_casted)
         .map_err(|e| PyValueError::new_err(e.to_string()))?;
 
-    Ok(PyDataFrame(out_df))
+    Ok(out_df)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zscore() {
+        let mut x = vec![1.0, 2.0, 3.0, 4.0];
+        Normalization::ZScore.apply(&mut x);
+        assert!(mean(&x).abs() < 1e-12);
+        let var = x.iter().map(|v| v * v).sum::<f64>() / x.len() as f64;
+        assert!((var - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_minmax_and_mean() {
+        let mut x = vec![2.0, 4.0, 6.0];
+        Normalization::MinMax.apply(&mut x);
+        assert_eq!(x, vec![0.0, 0.5, 1.0]);
+        let mut y = vec![2.0, 4.0, 6.0];
+        Normalization::Mean.apply(&mut y);
+        assert_eq!(y, vec![-0.5, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn test_robust() {
+        // median 3, absolute deviations [2, 1, 0, 1, 97] -> MAD 1
+        let mut x = vec![1.0, 2.0, 3.0, 4.0, 100.0];
+        Normalization::Robust.apply(&mut x);
+        assert_eq!(x, vec![-2.0, -1.0, 0.0, 1.0, 97.0]);
+    }
+
+    #[test]
+    fn test_constant_series_centred() {
+        for norm in [Normalization::ZScore, Normalization::MinMax, Normalization::Mean, Normalization::Robust] {
+            let mut x = vec![5.0; 4];
+            norm.apply(&mut x);
+            assert_eq!(x, vec![0.0; 4]);
+        }
+    }
+
+    fn with_gaps() -> Vec<Option<f64>> {
+        vec![None, Some(1.0), Some(f64::NAN), Some(5.0), None]
+    }
+
+    #[test]
+    fn test_missing_error_names_series() {
+        pyo3::prepare_freethreaded_python();
+        let err = MissingPolicy::Error.resolve("s1", &with_gaps()).unwrap_err();
+        assert!(err.to_string().contains("Series s1"));
+        assert_eq!(MissingPolicy::Error.resolve("s1", &[Some(1.0), Some(2.0)]).unwrap(), vec![1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_missing_fill_policies() {
+        assert_eq!(MissingPolicy::Drop.resolve("s", &with_gaps()).unwrap(), vec![1.0, 5.0]);
+        assert_eq!(MissingPolicy::Ffill.resolve("s", &with_gaps()).unwrap(), vec![1.0, 1.0, 1.0, 5.0, 5.0]);
+        assert_eq!(
+            MissingPolicy::LinearInterpolate.resolve("s", &with_gaps()).unwrap(),
+            vec![1.0, 1.0, 3.0, 5.0, 5.0]
+        );
+    }
+
+    #[test]
+    fn test_missing_drop_multivariate_keeps_alignment() {
+        let x = [Some(1.0), None, Some(3.0)];
+        let y = [Some(10.0), Some(20.0), Some(f64::NAN)];
+        let resolved = MissingPolicy::Drop.resolve_multivariate("s", &[&x, &y]).unwrap();
+        assert_eq!(resolved, vec![vec![1.0], vec![10.0]]);
+    }
+
+    #[test]
+    fn test_multivariate_per_dimension() {
+        let mut series = vec![vec![0.0, 10.0], vec![1.0, 30.0], vec![2.0, 20.0]];
+        Normalization::MinMax.apply_multivariate(&mut series);
+        assert_eq!(series, vec![vec![0.0, 0.0], vec![0.5, 1.0], vec![1.0, 0.5]]);
+    }
+
+    #[test]
+    fn test_dimensions_must_match_across_inputs() {
+        pyo3::prepare_freethreaded_python();
+        let input = |b: Vec<Vec<f64>>| PairwiseInput {
+            map_a: HashMap::from([("a".to_string(), vec![vec![1.0, 2.0]]), ("e".to_string(), vec![])]),
+            map_b: HashMap::from([("b".to_string(), b)]),
+            ids_a: vec!["a".to_string(), "e".to_string()],
+            ids_b: vec!["b".to_string()],
+            uid_a_dtype: DataType::String,
+            uid_b_dtype: DataType::String,
+        };
+        assert_eq!(input(vec![vec![0.0, 0.0]]).dimensions().unwrap(), Some(2));
+        let err = input(vec![vec![0.0, 0.0, 0.0]]).dimensions().unwrap_err();
+        assert!(err.to_string().contains("series a has 2 but series b has 3"));
+    }
+
+    #[test]
+    fn test_job_pool_preserves_order() {
+        let items: Vec<usize> = (0..100).collect();
+        let expected: Vec<usize> = items.iter().map(|i| i * i).collect();
+        for n_jobs in [None, Some(1), Some(3)] {
+            let pool = JobPool::new(n_jobs).unwrap();
+            assert_eq!(pool.map(&items, |i| i * i), expected);
+        }
+        assert!(matches!(JobPool::new(Some(1)).unwrap(), JobPool::Sequential));
+        assert_eq!(JobPool::new(Some(3)).unwrap().num_threads(), 3);
+        assert!(JobPool::new(Some(0)).is_err());
+    }
 }
@@ -0,0 +1,37 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_polars::PyDataFrame;
+
+use crate::ddtw::compute_derivative;
+use crate::pointwise::PointCost;
+use crate::utils::{compute_pairwise, PairwiseOptions};
+use crate::wdtw::wdtw_distance_optimized;
+
+/// WDDTW distance: WDTW on the Keogh & Pazzani derivatives of both series.
+fn wddtw_distance(a: &[f64], b: &[f64], g: f64, cost: PointCost, normalize_path: bool) -> f64 {
+    let a_d = compute_derivative(a);
+    let b_d = compute_derivative(b);
+    wdtw_distance_optimized(&a_d, &b_d, g, cost, normalize_path)
+}
+
+/// Pairwise weighted derivative DTW distances (Jeong et al., 2011).
+///
+/// `g` is the logistic weight penalty of WDTW (default 0.05); `cost` and
+/// `normalize_path` behave as in `compute_pairwise_wdtw`.
+#[pyfunction]
+#[pyo3(signature = (input1, input2, g=None, cost=None, normalize_path=false, **options))]
+pub fn compute_pairwise_wddtw(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    g: Option<f64>,
+    cost: Option<&str>,
+    normalize_path: bool,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let g_value = g.unwrap_or(0.05);
+    let cost = PointCost::parse(cost, PointCost::Squared)?;
+    compute_pairwise(input1, input2, "wddtw", &options, move |a, b| {
+        wddtw_distance(a, b, g_value, cost, normalize_path)
+    })
+}
@@ -1,7 +1,9 @@
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 use pyo3_polars::PyDataFrame;
 
-use crate::utils::compute_pairwise;
+use crate::pointwise::PointCost;
+use crate::utils::{compute_pairwise, PairwiseOptions};
 
 /// Precompute weight vector for WDTW calculation.
 fn compute_weight_vector(len: usize, g: f64) -> Vec<f64> {
@@ -12,7 +14,10 @@ fn compute_weight_vector(len: usize, g: f64) -> Vec<f64> {
 }
 
 /// Memory-optimized WDTW distance using O(m) memory.
-fn wdtw_distance_optimized(a: &[f64], b: &[f64], g: f64) -> f64 {
+///
+/// With `normalize_path` the distance is divided by the length of the
+/// warping path, tracked alongside the accumulated cost.
+pub(crate) fn wdtw_distance_optimized(a: &[f64], b: &[f64], g: f64, cost: PointCost, normalize_path: bool) -> f64 {
     let n = a.len();
     let m = b.len();
     if n == 0 || m == 0 {
@@ -24,30 +29,51 @@ fn wdtw_distance_optimized(a: &[f64], b: &[f64], g: f64) -> f64 {
 
     let mut prev = vec![f64::INFINITY; m + 1];
     let mut curr = vec![f64::INFINITY; m + 1];
+    let mut prev_len = vec![0usize; m + 1];
+    let mut curr_len = vec![0usize; m + 1];
     prev[0] = 0.0;
 
     for i in 1..=n {
         curr[0] = f64::INFINITY;
         for j in 1..=m {
             let weight = weight_vector[((i - 1) as isize - (j - 1) as isize).unsigned_abs()];
-            let diff = (a[i - 1] - b[j - 1]).powi(2);
-            let prev_min = prev[j - 1].min(prev[j]).min(curr[j - 1]);
+            let diff = cost.cost(a[i - 1], b[j - 1]);
+            let (mut prev_min, mut len) = (prev[j - 1], prev_len[j - 1]);
+            if prev[j] < prev_min {
+                (prev_min, len) = (prev[j], prev_len[j]);
+            }
+            if curr[j - 1] < prev_min {
+                (prev_min, len) = (curr[j - 1], curr_len[j - 1]);
+            }
             curr[j] = prev_min + weight * diff;
+            curr_len[j] = len + 1;
         }
         std::mem::swap(&mut prev, &mut curr);
+        std::mem::swap(&mut prev_len, &mut curr_len);
+    }
+    if normalize_path {
+        prev[m] / prev_len[m] as f64
+    } else {
+        prev[m]
     }
-    prev[m]
 }
 
+/// Pairwise WDTW distances. `cost` defaults to `"squared"`; it and
+/// `normalize_path` otherwise behave as in `compute_pairwise_dtw`.
 #[pyfunction]
-#[pyo3(signature = (input1, input2, g=None))]
+#[pyo3(signature = (input1, input2, g=None, cost=None, normalize_path=false, **options))]
 pub fn compute_pairwise_wdtw(
     input1: PyDataFrame,
     input2: PyDataFrame,
     g: Option<f64>,
+    cost: Option<&str>,
+    normalize_path: bool,
+    options: Option<&Bound<'_, PyDict>>,
 ) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
     let g_value = g.unwrap_or(0.05);
-    compute_pairwise(input1, input2, "wdtw", move |a, b| {
-        wdtw_distance_optimized(a, b, g_value)
+    let cost = PointCost::parse(cost, PointCost::Squared)?;
+    compute_pairwise(input1, input2, "wdtw", &options, move |a, b| {
+        wdtw_distance_optimized(a, b, g_value, cost, normalize_path)
     })
 }
@@ -0,0 +1,59 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3_polars::PyDataFrame;
+
+use crate::pointwise::PointMetric;
+use crate::utils::{compute_pairwise_multivariate, PairwiseOptions};
+
+/// Precompute weight vector for WDTW calculation.
+fn compute_weight_vector(len: usize, g: f64) -> Vec<f64> {
+    let half_len = len as f64 / 2.0;
+    (0..len)
+        .map(|i| 1.0 / (1.0 + (-g * (i as f64 - half_len)).exp()))
+        .collect()
+}
+
+/// Multivariate WDTW distance using O(m) memory.
+fn wdtw_distance(a: &[Vec<f64>], b: &[Vec<f64>], g: f64, metric: PointMetric) -> f64 {
+    let n = a.len();
+    let m = b.len();
+    if n == 0 || m == 0 {
+        return f64::INFINITY;
+    }
+
+    let weight_vector = compute_weight_vector(n.max(m), g);
+
+    let mut prev = vec![f64::INFINITY; m + 1];
+    let mut curr = vec![f64::INFINITY; m + 1];
+    prev[0] = 0.0;
+
+    for i in 1..=n {
+        curr[0] = f64::INFINITY;
+        for j in 1..=m {
+            let weight = weight_vector[(i - 1).abs_diff(j - 1)];
+            let prev_min = prev[j - 1].min(prev[j]).min(curr[j - 1]);
+            curr[j] = prev_min + weight * metric.distance(&a[i - 1], &b[j - 1]);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[m]
+}
+
+/// Multivariate WDTW. The default metric is squared Euclidean, which matches
+/// the squared differences of the univariate kernel.
+#[pyfunction]
+#[pyo3(signature = (input1, input2, g=None, metric=None, **options))]
+pub fn compute_pairwise_wdtw_multi(
+    input1: PyDataFrame,
+    input2: PyDataFrame,
+    g: Option<f64>,
+    metric: Option<String>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<PyDataFrame> {
+    let options = PairwiseOptions::from_kwargs(options)?;
+    let g_value = g.unwrap_or(0.05);
+    let metric = PointMetric::parse(metric.as_deref(), PointMetric::SquaredEuclidean)?;
+    compute_pairwise_multivariate(input1, input2, "wdtw_multi", &options, move |a, b| {
+        wdtw_distance(a, b, g_value, metric)
+    })
+}
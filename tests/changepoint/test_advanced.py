@@ -209,6 +209,50 @@ def test_pelt_constant_series():
 # --- Additional BOCPD tests ---
 
 
+def test_pelt_missing_error_names_series():
+    df = _make_shift_df(20, 20).with_columns(
+        pl.when(pl.col("y").cum_count() == 5).then(None).otherwise(pl.col("y")).alias("y")
+    )
+    with pytest.raises(ValueError, match="Series A"):
+        pelt(df, penalty=10.0)
+
+
+@pytest.mark.parametrize("missing", ["drop", "linear_interpolate", "ffill"])
+def test_pelt_missing_policies(missing):
+    clean = _make_shift_df(50, 50, shift=10.0)
+    gappy = clean.with_columns(
+        pl.when(pl.col("y").cum_count().is_in([10, 30, 80])).then(float("nan")).otherwise(pl.col("y")).alias("y")
+    )
+    expected = pelt(clean, penalty=10.0)["changepoint_idx"].to_list()
+    result = pelt(gappy, penalty=10.0, missing=missing)["changepoint_idx"].to_list()
+    assert len(result) == len(expected)
+    assert all(abs(r - e) <= 1 for r, e in zip(result, expected, strict=True))
+
+
+@pytest.mark.parametrize("n_jobs", [1, 2])
+def test_pelt_n_jobs(n_jobs):
+    df = pl.concat([_make_shift_df(), _make_shift_df(30, 70).with_columns(pl.lit("B").alias("unique_id"))])
+    expected = pelt(df, penalty=10.0)
+    assert pelt(df, penalty=10.0, n_jobs=n_jobs).equals(expected)
+
+
+def test_pelt_n_jobs_zero_rejected():
+    with pytest.raises(ValueError, match="n_jobs must be >= 1"):
+        pelt(_make_shift_df(), n_jobs=0)
+
+
+def test_pelt_missing_python_fallback_matches_rust():
+    from polars_ts.changepoint.pelt import _pelt_python
+
+    df = _make_shift_df(30, 30, shift=10.0).with_columns(
+        pl.when(pl.col("y").cum_count().is_in([5, 40])).then(None).otherwise(pl.col("y")).alias("y")
+    )
+    py_result = _pelt_python(df, "y", "unique_id", "ds", "mean", 10.0, 2, "drop")
+    rs_result = pelt(df, penalty=10.0, missing="drop")
+    assert len(py_result) == len(rs_result) > 0
+    assert abs(py_result["changepoint_idx"][0] - rs_result["changepoint_idx"][0]) <= 5
+
+
 def test_bocpd_constant_series():
     """BOCPD on constant series should detect no changepoints."""
     pytest.importorskip("scipy")
@@ -83,3 +83,11 @@ class TestTimeSeriesKNNClassifier:
         test = train_data.select("unique_id", "y")
         result = clf.predict(test)
         assert result.shape[0] > 0
+
+    def test_shared_ids_between_train_and_test(self, train_data):
+        """A test series whose id also exists in training is compared with it."""
+        clf = TimeSeriesKNNClassifier(k=1, metric="dtw")
+        clf.fit(train_data, label_col="label")
+        test = pl.DataFrame({"unique_id": ["C"] * 8, "y": [0.0, 1.0, 0.0, -1.0, 0.0, 1.0, 0.0, -1.0]})
+        result = clf.predict(test)
+        assert result["predicted_label"].to_list() == ["sine"]
@@ -2,7 +2,7 @@ import numpy as np
 import polars as pl
 import pytest
 
-from polars_ts.clustering.dba import dba
+from polars_ts.clustering.dba import dba, softdtw_barycenter
 from polars_ts.clustering.kmeans import TimeSeriesKMeans, kmeans_dba
 
 # ---------------------------------------------------------------------------
@@ -101,6 +101,43 @@ class TestDBA:
         result = dba([s1, s2], init=init)
         np.testing.assert_allclose(result, [2.0, 3.0, 4.0], atol=1e-10)
 
+    def test_zero_window_is_pointwise_mean(self):
+        """With a zero-width band every alignment is the diagonal."""
+        s1 = np.array([0.0, 0.0, 0.0, 5.0, 0.0, 0.0])
+        s2 = np.array([5.0, 0.0, 0.0, 0.0, 0.0, 1.0])
+        result = dba([s1, s2], init=s1, max_iter=1, window=0)
+        np.testing.assert_allclose(result, (s1 + s2) / 2)
+
+    def test_rust_matches_python_fallback(self):
+        """The Rust and pure-Python refinement agree on a fixed point."""
+        from polars_ts.clustering.dba import _dba_rust
+
+        s1 = np.array([1.0, 2.0, 3.0])
+        s2 = np.array([3.0, 4.0, 5.0])
+        result = _dba_rust([s1, s2], max_iter=10, tol=1e-5, init=np.array([2.0, 3.0, 4.0]), window=None)
+        np.testing.assert_allclose(result, [2.0, 3.0, 4.0], atol=1e-10)
+
+    @pytest.mark.parametrize("window", [None, 1])
+    def test_rust_and_python_paths_agree(self, window):
+        """Medoid init, squared cost and the band match between the two paths."""
+        from polars_ts.clustering.dba import _dba_python, _dba_rust
+
+        rng = np.random.default_rng(0)
+        series = [np.cumsum(rng.normal(size=n)) for n in (8, 10, 9, 10, 7)]
+        rust = _dba_rust(series, max_iter=10, tol=1e-5, init=None, window=window)
+        python = _dba_python(series, max_iter=10, tol=1e-5, init=None, window=window)
+        np.testing.assert_allclose(rust, python, atol=1e-10)
+
+    @pytest.mark.parametrize("n_jobs", [1, 2])
+    def test_n_jobs_matches_default(self, n_jobs):
+        rng = np.random.default_rng(2)
+        series = [np.cumsum(rng.normal(size=n)) for n in (8, 10, 9, 10, 7)]
+        np.testing.assert_array_equal(dba(series, n_jobs=n_jobs), dba(series))
+
+    def test_invalid_n_jobs(self):
+        with pytest.raises(ValueError, match="n_jobs"):
+            dba([np.zeros(3), np.ones(3)], n_jobs=0)
+
     def test_alignment_path_no_negative_indices(self):
         """DTW alignment path should never produce negative indices."""
         from polars_ts.clustering.dba import _dtw_alignment_path
@@ -203,6 +240,38 @@ class TestTimeSeriesKMeans:
         with pytest.raises(ValueError, match="Only metric='dtw'"):
             km.fit(cluster_data)
 
+    def test_rust_loop_used_for_dtw(self, cluster_data):
+        km = TimeSeriesKMeans(n_clusters=2, max_iter=10, window=2)
+        km.fit(cluster_data)
+        labels = dict(zip(km.labels_["unique_id"].to_list(), km.labels_["cluster"].to_list(), strict=True))
+        assert labels["A"] == labels["B"] != labels["C"] == labels["D"]
+        assert all(len(c) == 6 for c in km.centroids_)
+
+    @pytest.mark.parametrize("window", [None, 2])
+    def test_rust_and_python_loops_agree(self, window):
+        """Seeding, assignment and centroid updates match between the two paths."""
+        rng = np.random.default_rng(1)
+        series = [np.cumsum(rng.normal(size=n)) for n in (8, 9, 8, 10, 7, 9, 8)]
+        km = TimeSeriesKMeans(n_clusters=3, max_iter=10, dba_max_iter=5, seed=7, window=window)
+        rust_labels, rust_centroids = km._fit_rust(series)
+        python_labels, python_centroids = km._fit_python(series)
+        assert rust_labels == python_labels
+        for r, p in zip(rust_centroids, python_centroids, strict=True):
+            np.testing.assert_allclose(r, p, atol=1e-10)
+
+    @pytest.mark.parametrize("metric", ["dtw", "softdtw"])
+    def test_n_jobs_matches_default(self, cluster_data, metric):
+        default = TimeSeriesKMeans(n_clusters=2, metric=metric, max_iter=10).fit(cluster_data)
+        single = TimeSeriesKMeans(n_clusters=2, metric=metric, max_iter=10, n_jobs=1).fit(cluster_data)
+        assert single.labels_.equals(default.labels_)
+        for a, b in zip(single.centroids_, default.centroids_, strict=True):
+            np.testing.assert_allclose(a, b, atol=1e-10)
+
+    def test_negative_seed(self, cluster_data):
+        """Seeds follow ``random.Random``, which accepts negative ints."""
+        km = TimeSeriesKMeans(n_clusters=2, max_iter=10, seed=-3).fit(cluster_data)
+        assert km.labels_["cluster"].n_unique() == 2
+
     def test_identical_series_same_cluster(self):
         df = pl.DataFrame(
             {
@@ -247,6 +316,47 @@ class TestKmeansDbaFunction:
         assert "ts_id" in result.columns
 
 
+class TestSoftDTW:
+    def test_barycenter_of_identical_series(self):
+        s = np.array([0.0, 1.0, 2.0, 1.0, 0.0])
+        centroid = softdtw_barycenter([s, s.copy()], gamma=0.01, init=s + 0.3)
+        np.testing.assert_allclose(centroid, s, atol=0.05)
+
+    def test_barycenter_lies_between_series(self):
+        low = np.zeros(6)
+        high = np.full(6, 2.0)
+        centroid = softdtw_barycenter([low, high], gamma=0.1)
+        assert np.all(centroid > 0.5)
+        assert np.all(centroid < 1.5)
+
+    def test_kmeans_softdtw_groups_similar(self, cluster_data):
+        km = TimeSeriesKMeans(n_clusters=2, metric="softdtw", gamma=0.1, max_iter=10)
+        km.fit(cluster_data)
+        labels = dict(zip(km.labels_["unique_id"].to_list(), km.labels_["cluster"].to_list(), strict=True))
+        assert labels["A"] == labels["B"]
+        assert labels["C"] == labels["D"]
+        assert labels["A"] != labels["C"]
+
+    def test_batched_assignment_matches_python(self):
+        from polars_ts_rs.polars_ts_rs import softdtw_value
+
+        rng = np.random.default_rng(3)
+        series = [np.cumsum(rng.normal(size=n)) for n in (6, 8, 7, 9, 5)]
+        centroids = [series[0], series[3]]
+        km = TimeSeriesKMeans(n_clusters=2, metric="softdtw", gamma=0.5)
+        python = [
+            min(range(2), key=lambda ci, s=s: TimeSeriesKMeans._softdtw_distance(s, centroids[ci], 0.5))
+            for s in series
+        ]
+        assert km._softdtw_assign_rust(series, centroids) == python
+        value = TimeSeriesKMeans._softdtw_distance(series[1], series[2], 0.5)
+        assert value == pytest.approx(softdtw_value(series[1].tolist(), series[2].tolist(), 0.5))
+
+    def test_kmeans_dba_function_softdtw(self, cluster_data):
+        result = kmeans_dba(cluster_data, k=2, method="softdtw", gamma=0.1)
+        assert result.shape == (4, 2)
+
+
 # ---------------------------------------------------------------------------
 # Top-level import tests
 # ---------------------------------------------------------------------------
@@ -71,6 +71,11 @@ class TestTimeSeriesKMedoids:
             km.fit(cluster_data)
             assert km.labels_ is not None
 
+    def test_similarity_kernel_rejected(self, cluster_data):
+        # GAK is a similarity, so PAM must not treat it as a distance
+        with pytest.raises(ValueError, match="Unknown distance method 'gak'"):
+            TimeSeriesKMedoids(n_clusters=2, metric="gak").fit(cluster_data)
+
     def test_identical_series_same_cluster(self):
         df = pl.DataFrame(
             {
@@ -132,3 +137,11 @@ def test_kmedoids_custom_columns():
     assert "ts_id" in labels.columns
     assert "cluster" in labels.columns
     assert len(labels) == 3
+
+
+@pytest.mark.parametrize("n_jobs", [1, 2])
+def test_kmedoids_n_jobs(cluster_data, n_jobs):
+    from polars_ts.clustering.kmedoids import kmedoids
+
+    expected = kmedoids(cluster_data, k=2, method="dtw")
+    assert kmedoids(cluster_data, k=2, method="dtw", n_jobs=n_jobs).equals(expected)
@@ -166,3 +166,29 @@ def test_kshape_constant_value_convergence():
     ks.fit(df)
     assert ks.labels_ is not None
     assert ks.labels_.shape[0] == 3
+
+
+def test_kshape_rust_loop(shape_data):
+    """The Rust loop groups equal shapes and returns padded centroids."""
+    import numpy as np
+
+    series = [
+        shape_data.filter(pl.col("unique_id") == uid)["y"].to_numpy().astype(np.float64) for uid in ["A", "B", "C", "D"]
+    ]
+    labels, centroids = KShape(n_clusters=2, max_iter=50)._fit_rust(series)
+    assert labels[0] == labels[1]
+    assert labels[2] == labels[3]
+    assert len(centroids) == 2
+    assert all(len(c) == 8 for c in centroids)
+
+
+@pytest.mark.parametrize("n_jobs", [1, 2])
+def test_kshape_n_jobs_matches_default(shape_data, n_jobs):
+    default = KShape(n_clusters=2, max_iter=20).fit(shape_data)
+    threaded = KShape(n_clusters=2, max_iter=20, n_jobs=n_jobs).fit(shape_data)
+    assert threaded.labels_.equals(default.labels_)
+
+
+def test_kshape_invalid_n_jobs(shape_data):
+    with pytest.raises(ValueError, match="n_jobs"):
+        KShape(n_clusters=2, n_jobs=0).fit(shape_data)
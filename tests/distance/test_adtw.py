@@ -0,0 +1,45 @@
+import pytest
+
+from polars_ts import compute_pairwise_adtw, compute_pairwise_dtw
+from polars_ts.distance import compute_pairwise_distance
+
+from .conftest import _to_dict
+
+
+class TestADTW:
+    def test_identical_series_zero(self, identical_series):
+        d = _to_dict(compute_pairwise_adtw(identical_series, identical_series))
+        assert d[("A", "B")] == pytest.approx(0.0)
+
+    def test_output_columns(self, two_series):
+        assert compute_pairwise_adtw(two_series, two_series).columns == ["id_1", "id_2", "adtw"]
+
+    def test_zero_penalty_is_dtw(self, shifted_series):
+        adtw = _to_dict(compute_pairwise_adtw(shifted_series, shifted_series, penalty=0.0))
+        dtw = _to_dict(compute_pairwise_dtw(shifted_series, shifted_series, cost="squared"))
+        assert adtw[("A", "B")] == pytest.approx(dtw[("A", "B")])
+
+    def test_large_penalty_is_lock_step(self, shifted_series):
+        d = _to_dict(compute_pairwise_adtw(shifted_series, shifted_series, penalty=1e9))
+        # Eight positions, each differing by 4
+        assert d[("A", "B")] == pytest.approx(8 * 16.0)
+
+    def test_penalty_is_monotone(self, shifted_series):
+        values = [
+            _to_dict(compute_pairwise_adtw(shifted_series, shifted_series, penalty=p))[("A", "B")]
+            for p in (0.0, 1.0, 10.0, 100.0)
+        ]
+        assert values == sorted(values)
+
+    def test_abs_cost(self, two_series):
+        d = _to_dict(compute_pairwise_adtw(two_series, two_series, cost="abs"))
+        assert d[("A", "B")] == pytest.approx(1.0)
+
+    def test_negative_penalty_raises(self, two_series):
+        with pytest.raises(ValueError, match="penalty"):
+            compute_pairwise_adtw(two_series, two_series, penalty=-1.0)
+
+    def test_via_dispatcher(self, three_series):
+        direct = _to_dict(compute_pairwise_adtw(three_series, three_series, penalty=0.5))
+        dispatched = _to_dict(compute_pairwise_distance(three_series, three_series, method="adtw", penalty=0.5))
+        assert dispatched == direct
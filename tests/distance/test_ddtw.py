@@ -1,4 +1,5 @@
 import polars as pl
+import pytest
 from polars_ts_rs.polars_ts_rs import compute_pairwise_ddtw
 
 from tests.distance.conftest import _to_dict
@@ -103,3 +104,17 @@ class TestDDTWProperties:
         d = _to_dict(result)
         # With only 2 points, derivative is empty → should return inf
         assert d[("A", "B")] == float("inf")
+
+
+class TestDDTWCost:
+    # Derivatives of the two_series fixture are [1, 1] and [1, 1.25]
+
+    def test_default_is_abs(self, two_series):
+        assert compute_pairwise_ddtw(two_series, two_series)["ddtw"][0] == pytest.approx(0.25)
+
+    def test_squared(self, two_series):
+        assert compute_pairwise_ddtw(two_series, two_series, cost="squared")["ddtw"][0] == pytest.approx(0.0625)
+
+    def test_normalize_path(self, two_series):
+        result = compute_pairwise_ddtw(two_series, two_series, normalize_path=True)
+        assert result["ddtw"][0] == pytest.approx(0.125)
@@ -1,8 +1,13 @@
+import math
+
 import polars as pl
+import pytest
 from polars_ts_rs.polars_ts_rs import (
     compute_pairwise_dtw,
+    compute_pairwise_edr,
     compute_pairwise_erp,
     compute_pairwise_lcss,
+    compute_pairwise_msm,
     compute_pairwise_twe,
 )
 
@@ -289,3 +294,64 @@ class TestCrossMetric:
         assert twe.height == 1
         assert erp["erp"][0] > 0
         assert twe["twe"][0] > 0
+
+
+# ===========================================================================
+# Sakoe-Chiba window tests
+# ===========================================================================
+
+WINDOWED = [
+    (compute_pairwise_erp, "erp"),
+    (compute_pairwise_lcss, "lcss"),
+    (compute_pairwise_twe, "twe"),
+    (compute_pairwise_msm, "msm"),
+    (compute_pairwise_edr, "edr"),
+]
+
+
+@pytest.fixture
+def shifted_series():
+    return pl.DataFrame(
+        {
+            "unique_id": ["A"] * 8 + ["B"] * 8,
+            "y": [0.0, 0.0, 1.0, 3.0, 1.0, 0.0, 0.0, 0.0] + [0.0, 0.0, 0.0, 0.0, 1.0, 3.0, 1.0, 0.0],
+        }
+    )
+
+
+class TestWindow:
+    @pytest.mark.parametrize(("func", "col"), WINDOWED)
+    def test_wide_window_matches_unconstrained(self, func, col, three_series):
+        full = _to_dict(func(three_series, three_series))
+        wide = _to_dict(func(three_series, three_series, window=100))
+        assert full == pytest.approx(wide)
+
+    @pytest.mark.parametrize(("func", "col"), WINDOWED)
+    def test_narrow_window_never_lowers_distance(self, func, col, shifted_series):
+        full = func(shifted_series, shifted_series)[col][0]
+        narrow = func(shifted_series, shifted_series, window=0)[col][0]
+        assert narrow >= full - 1e-12
+
+    @pytest.mark.parametrize(("func", "col"), WINDOWED)
+    def test_unequal_lengths_stay_finite(self, func, col):
+        df = pl.DataFrame(
+            {
+                "unique_id": ["A"] * 3 + ["B"] * 7,
+                "y": [1.0, 2.0, 3.0] + [1.0, 1.5, 2.0, 2.5, 3.0, 3.5, 4.0],
+            }
+        )
+        value = func(df, df, window=0)[col][0]
+        assert math.isfinite(value)
+
+    def test_lcss_window_blocks_far_matches(self, shifted_series):
+        full = compute_pairwise_lcss(shifted_series, shifted_series, epsilon=0.5)["lcss"][0]
+        narrow = compute_pairwise_lcss(shifted_series, shifted_series, epsilon=0.5, window=1)["lcss"][0]
+        # The peaks are two steps apart, so a band of one cannot pair them
+        assert narrow > full
+
+    def test_via_dispatcher(self, shifted_series):
+        from polars_ts.distance import compute_pairwise_distance
+
+        result = compute_pairwise_distance(shifted_series, shifted_series, method="twe", window=2, lambda_=0.5)
+        expected = compute_pairwise_twe(shifted_series, shifted_series, window=2, **{"lambda": 0.5})
+        assert result["twe"][0] == pytest.approx(expected["twe"][0])
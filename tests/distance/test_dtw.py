@@ -1,6 +1,11 @@
 import polars as pl
 import pytest
-from polars_ts_rs.polars_ts_rs import compute_pairwise_dtw
+from polars_ts_rs.polars_ts_rs import (
+    compute_dtw_alignment,
+    compute_knn_dtw,
+    compute_pairwise_dtw,
+    compute_subsequence_dtw,
+)
 
 from tests.distance.conftest import _to_dict
 
@@ -366,3 +371,191 @@ class TestEdgeCases:
         default = compute_pairwise_dtw(two_series, two_series)["dtw"][0]
         explicit = compute_pairwise_dtw(two_series, two_series, method="standard")["dtw"][0]
         assert default == explicit
+
+
+# ===========================================================================
+# Pointwise cost and path normalization
+# ===========================================================================
+
+
+class TestPointCost:
+    @pytest.fixture
+    def step_series(self):
+        """B differs from A by 2 at the last step only."""
+        return pl.DataFrame(
+            {
+                "unique_id": ["A"] * 4 + ["B"] * 4,
+                "y": [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0],
+            }
+        )
+
+    @pytest.mark.parametrize(
+        "cost,expected",
+        [("abs", 2.0), ("squared", 4.0), ("huber(1.0)", 1.5), ("huber(4)", 2.0), ("huber", 1.5)],
+    )
+    def test_costs(self, step_series, cost, expected):
+        result = compute_pairwise_dtw(step_series, step_series, cost=cost)
+        assert result["dtw"][0] == pytest.approx(expected)
+
+    def test_default_is_abs(self, three_series):
+        default = _to_dict(compute_pairwise_dtw(three_series, three_series))
+        explicit = _to_dict(compute_pairwise_dtw(three_series, three_series, cost="abs"))
+        assert default == explicit
+
+    @pytest.mark.parametrize("method,param", ALL_METHODS)
+    def test_cost_applies_to_every_method(self, step_series, method, param):
+        result = compute_pairwise_dtw(step_series, step_series, method=method, param=param, cost="squared")
+        assert result["dtw"][0] == pytest.approx(4.0)
+
+    @pytest.mark.parametrize("method,param", ALL_METHODS)
+    def test_normalize_path(self, step_series, method, param):
+        result = compute_pairwise_dtw(step_series, step_series, method=method, param=param, normalize_path=True)
+        assert result["dtw"][0] == pytest.approx(0.5)
+
+    def test_normalized_matches_alignment_length(self, three_series):
+        raw = _to_dict(compute_pairwise_dtw(three_series, three_series, cost="squared"))
+        normalized = _to_dict(compute_pairwise_dtw(three_series, three_series, cost="squared", normalize_path=True))
+        paths = compute_dtw_alignment(three_series, three_series, cost="squared")
+        for (id_1, id_2), path in paths.group_by(["id_1", "id_2"]):
+            assert normalized[(id_1, id_2)] == pytest.approx(raw[(id_1, id_2)] / path.height)
+
+    @pytest.mark.parametrize("cost", ["cubic", "huber(-1)", "huber(x)"])
+    def test_invalid_cost(self, two_series, cost):
+        with pytest.raises(ValueError, match="cost"):
+            compute_pairwise_dtw(two_series, two_series, cost=cost)
+
+    def test_via_dispatcher(self, step_series):
+        from polars_ts.distance import compute_pairwise_distance
+
+        result = compute_pairwise_distance(step_series, step_series, method="dtw", cost="squared", normalize_path=True)
+        assert result["dtw"][0] == pytest.approx(1.0)
+
+
+# ===========================================================================
+# Warping path output
+# ===========================================================================
+
+
+class TestDTWAlignment:
+    def test_output_columns(self, two_series):
+        result = compute_dtw_alignment(two_series, two_series)
+        assert result.columns == ["id_1", "id_2", "idx_1", "idx_2"]
+
+    def test_identical_series_diagonal(self, identical_series):
+        result = compute_dtw_alignment(identical_series, identical_series)
+        assert result["idx_1"].to_list() == result["idx_2"].to_list()
+
+    @pytest.mark.parametrize("method,param", ALL_METHODS)
+    def test_path_spans_both_series(self, three_series, method, param):
+        result = compute_dtw_alignment(three_series, three_series, method=method, param=param)
+        for (_, _), path in result.group_by(["id_1", "id_2"]):
+            assert path["idx_1"][0] == 0 and path["idx_2"][0] == 0
+            assert path["idx_1"][-1] == 3 and path["idx_2"][-1] == 3
+
+    def test_path_cost_matches_distance(self, two_series):
+        dist = compute_pairwise_dtw(two_series, two_series)["dtw"][0]
+        path = compute_dtw_alignment(two_series, two_series)
+        a = two_series.filter(pl.col("unique_id") == path["id_1"][0])["y"].to_list()
+        b = two_series.filter(pl.col("unique_id") == path["id_2"][0])["y"].to_list()
+        cost = sum(abs(a[i] - b[j]) for i, j in zip(path["idx_1"], path["idx_2"]))
+        assert cost == pytest.approx(dist)
+
+    def test_int_id_preserved(self, int_id_series):
+        result = compute_dtw_alignment(int_id_series, int_id_series)
+        assert result["id_1"].dtype == pl.Int64
+
+    def test_invalid_method(self, two_series):
+        with pytest.raises(ValueError, match="Unknown DTW method"):
+            compute_dtw_alignment(two_series, two_series, method="bogus")
+
+
+# ===========================================================================
+# Lower-bound pruned nearest-neighbour search
+# ===========================================================================
+
+
+class TestKnnDTW:
+    def test_output_columns(self, three_series):
+        result = compute_knn_dtw(three_series, three_series)
+        assert result.columns == ["id_1", "id_2", "rank", "dtw"]
+
+    def test_nearest_neighbour(self, three_series):
+        result = compute_knn_dtw(three_series, three_series, k=1)
+        nn = dict(zip(result["id_1"], result["id_2"]))
+        assert nn["A"] == "B"
+        assert nn["B"] == "A"
+
+    @pytest.mark.parametrize("lower_bound", ["none", "kim", "keogh", "improved"])
+    @pytest.mark.parametrize("window", [None, 1])
+    def test_matches_pairwise(self, three_series, lower_bound, window):
+        result = compute_knn_dtw(three_series, three_series, k=2, window=window, lower_bound=lower_bound)
+        if window is None:
+            full = _to_dict(compute_pairwise_dtw(three_series, three_series))
+        else:
+            full = _to_dict(compute_pairwise_dtw(three_series, three_series, method="sakoe_chiba", param=window))
+        for row in result.iter_rows(named=True):
+            key = tuple(sorted([row["id_1"], row["id_2"]]))
+            assert row["dtw"] == pytest.approx(full[key])
+        assert result.group_by("id_1").agg(pl.col("rank").max())["rank"].to_list() == [2, 2, 2]
+
+    @pytest.mark.parametrize("lower_bound", ["none", "kim", "keogh", "improved"])
+    @pytest.mark.parametrize("swap", [False, True])
+    def test_ties_go_to_first_id(self, lower_bound, swap):
+        query = pl.DataFrame({"unique_id": ["Q"] * 4, "y": [0.0] * 4})
+        late_end, early_bump = [0.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 0.0]
+        a, b = (early_bump, late_end) if swap else (late_end, early_bump)
+        train = pl.DataFrame({"unique_id": ["A"] * 4 + ["B"] * 4, "y": a + b})
+        result = compute_knn_dtw(query, train, k=1, lower_bound=lower_bound)
+        assert result.rows() == [("Q", "A", 1, 1.0)]
+
+    def test_invalid_lower_bound(self, two_series):
+        with pytest.raises(ValueError, match="Unknown lower bound"):
+            compute_knn_dtw(two_series, two_series, lower_bound="bogus")
+
+    def test_invalid_k(self, two_series):
+        with pytest.raises(ValueError, match="k must be >= 1"):
+            compute_knn_dtw(two_series, two_series, k=0)
+
+
+class TestSubsequenceDTW:
+    @pytest.fixture
+    def sensor(self):
+        """Two long series with the template planted (once stretched) in each."""
+        a = [1.0] * 60
+        a[10:15] = [0.0, 3.0, 6.0, 3.0, 0.0]
+        a[40:47] = [0.0, 3.0, 3.0, 6.0, 6.0, 3.0, 0.0]
+        b = [2.0] * 30
+        b[20:25] = [0.5, 3.0, 6.5, 3.0, 0.5]
+        return pl.DataFrame({"unique_id": ["A"] * 60 + ["B"] * 30, "y": a + b})
+
+    def test_finds_matches(self, sensor):
+        result = compute_subsequence_dtw(sensor, [0.0, 3.0, 6.0, 3.0, 0.0], k=2)
+        assert result.columns == ["unique_id", "start_idx", "end_idx", "distance"]
+        a = result.filter(pl.col("unique_id") == "A")
+        assert a.select("start_idx", "end_idx").rows() == [(10, 14), (40, 46)]
+        assert a["distance"].to_list() == pytest.approx([0.0, 0.0])
+        b = result.filter(pl.col("unique_id") == "B").row(0, named=True)
+        assert (b["start_idx"], b["end_idx"]) == (20, 24)
+        assert b["distance"] == pytest.approx(1.5)
+
+    def test_non_overlapping_by_default(self, sensor):
+        result = compute_subsequence_dtw(sensor, [0.0, 3.0, 6.0, 3.0, 0.0], k=4)
+        spans = sorted(result.filter(pl.col("unique_id") == "A").select("start_idx", "end_idx").rows())
+        assert all(prev[1] < nxt[0] for prev, nxt in zip(spans, spans[1:], strict=False))
+
+    def test_allow_overlap(self, sensor):
+        result = compute_subsequence_dtw(sensor, [0.0, 3.0, 6.0, 3.0, 0.0], k=4, allow_overlap=True)
+        spans = sorted(result.filter(pl.col("unique_id") == "A").select("start_idx", "end_idx").rows())
+        assert any(prev[1] >= nxt[0] for prev, nxt in zip(spans, spans[1:], strict=False))
+
+    def test_band_rejects_stretched_match(self, sensor):
+        result = compute_subsequence_dtw(sensor, [0.0, 3.0, 6.0, 3.0, 0.0], k=2, window=0)
+        a = result.filter(pl.col("unique_id") == "A")
+        assert a.row(0, named=True)["start_idx"] == 10
+        # With no warping allowed every match has the query length
+        assert (a["end_idx"] - a["start_idx"]).to_list() == [4, 4]
+        assert a["distance"][1] > 0.0
+
+    def test_empty_query_raises(self, sensor):
+        with pytest.raises(ValueError, match="query"):
+            compute_subsequence_dtw(sensor, [])
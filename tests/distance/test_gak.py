@@ -0,0 +1,77 @@
+import math
+
+import numpy as np
+import polars as pl
+import pytest
+
+from polars_ts import compute_pairwise_gak
+
+from .conftest import _to_dict
+
+
+@pytest.fixture
+def wave_series():
+    """Five sine waves with different phases and lengths."""
+    ids, values = [], []
+    for k in range(5):
+        n = 20 + 2 * k
+        ids += [f"s{k}"] * n
+        values += [math.sin(0.4 * i + 0.7 * k) for i in range(n)]
+    return pl.DataFrame({"unique_id": ids, "y": values})
+
+
+class TestGAK:
+    def test_output_columns(self, two_series):
+        assert compute_pairwise_gak(two_series, two_series).columns == ["id_1", "id_2", "gak"]
+
+    def test_identical_series_one(self, identical_series):
+        d = _to_dict(compute_pairwise_gak(identical_series, identical_series))
+        assert d[("A", "B")] == pytest.approx(1.0)
+
+    def test_normalized_range(self, three_series):
+        d = _to_dict(compute_pairwise_gak(three_series, three_series))
+        assert all(0.0 < v < 1.0 for v in d.values())
+        # A is more similar to B than to its reversal C
+        assert d[("A", "B")] > d[("A", "C")]
+
+    def test_log_output(self, three_series):
+        plain = _to_dict(compute_pairwise_gak(three_series, three_series, sigma=2.0))
+        logged = _to_dict(compute_pairwise_gak(three_series, three_series, sigma=2.0, log=True))
+        for pair, v in plain.items():
+            assert logged[pair] == pytest.approx(math.log(v))
+
+    def test_unnormalized(self, identical_series):
+        d = _to_dict(compute_pairwise_gak(identical_series, identical_series, normalized=False))
+        assert d[("A", "B")] > 1.0
+
+    def test_triangular_band_excludes_length_mismatch(self):
+        df = pl.DataFrame({"unique_id": ["A"] * 3 + ["B"] * 6, "y": [0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0]})
+        assert compute_pairwise_gak(df, df, triangular=3)["gak"][0] == 0.0
+        assert compute_pairwise_gak(df, df, triangular=4)["gak"][0] > 0.0
+
+    def test_gram_matrix_is_psd(self, wave_series):
+        gram = compute_pairwise_gak(wave_series, wave_series, sigma=0.5, output="matrix")
+        values = gram.drop("unique_id").to_numpy()
+        assert np.allclose(np.diag(values), 1.0)
+        assert np.allclose(values, values.T)
+        assert np.linalg.eigvalsh(values).min() > -1e-10
+
+    @pytest.mark.parametrize("mode", ["cross_dedup", "self_symmetric"])
+    def test_matrix_diagonal_is_self_similarity(self, wave_series, mode):
+        gram = compute_pairwise_gak(wave_series, wave_series, normalized=False, mode=mode, output="matrix")
+        full = compute_pairwise_gak(wave_series, wave_series, normalized=False, mode="cross_full")
+        expected = full.filter(pl.col("id_1") == pl.col("id_2"))["gak"].to_list()
+        assert np.diag(gram.drop("unique_id").to_numpy()) == pytest.approx(expected)
+        assert min(expected) > 0.0
+
+    def test_invalid_sigma(self, two_series):
+        with pytest.raises(ValueError, match="sigma"):
+            compute_pairwise_gak(two_series, two_series, sigma=0.0)
+
+    def test_invalid_triangular(self, two_series):
+        with pytest.raises(ValueError, match="triangular"):
+            compute_pairwise_gak(two_series, two_series, triangular=0)
+
+    def test_knn_rejected(self, two_series):
+        with pytest.raises(ValueError, match="similarity"):
+            compute_pairwise_gak(two_series, two_series, k=1)
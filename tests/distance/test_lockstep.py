@@ -0,0 +1,128 @@
+import math
+
+import polars as pl
+import pytest
+
+from polars_ts import (
+    compute_pairwise_chebyshev,
+    compute_pairwise_cid,
+    compute_pairwise_correlation,
+    compute_pairwise_cosine,
+    compute_pairwise_euclidean,
+    compute_pairwise_minkowski,
+)
+from polars_ts.distance import compute_pairwise_distance
+
+from .conftest import _to_dict
+
+ALL_LOCKSTEP = [
+    (compute_pairwise_euclidean, "euclidean"),
+    (compute_pairwise_minkowski, "minkowski"),
+    (compute_pairwise_chebyshev, "chebyshev"),
+    (compute_pairwise_correlation, "correlation"),
+    (compute_pairwise_cid, "cid"),
+    (compute_pairwise_cosine, "cosine"),
+]
+
+
+@pytest.fixture
+def unequal_series():
+    """A has 3 observations, B has 5 on the same straight line."""
+    return pl.DataFrame(
+        {
+            "unique_id": ["A"] * 3 + ["B"] * 5,
+            "y": [0.0, 2.0, 4.0, 0.0, 1.0, 2.0, 3.0, 4.0],
+        }
+    )
+
+
+class TestLockStepCommon:
+    @pytest.mark.parametrize(("func", "name"), ALL_LOCKSTEP)
+    def test_identical_series_zero(self, identical_series, func, name):
+        d = _to_dict(func(identical_series, identical_series))
+        assert d[("A", "B")] == pytest.approx(0.0, abs=1e-12)
+
+    @pytest.mark.parametrize(("func", "name"), ALL_LOCKSTEP)
+    def test_output_columns(self, two_series, func, name):
+        assert func(two_series, two_series).columns == ["id_1", "id_2", name]
+
+    @pytest.mark.parametrize(("func", "name"), ALL_LOCKSTEP)
+    def test_unequal_length_errors_by_default(self, unequal_series, func, name):
+        with pytest.raises(ValueError, match="equal-length"):
+            func(unequal_series, unequal_series)
+
+    @pytest.mark.parametrize(("func", "name"), ALL_LOCKSTEP)
+    def test_unequal_length_resample(self, unequal_series, func, name):
+        # Resampling A onto 5 points gives exactly B
+        d = _to_dict(func(unequal_series, unequal_series, unequal_length="resample"))
+        assert d[("A", "B")] == pytest.approx(0.0, abs=1e-12)
+
+    @pytest.mark.parametrize(("func", "name"), ALL_LOCKSTEP)
+    def test_unknown_policy(self, two_series, func, name):
+        with pytest.raises(ValueError, match="unequal_length"):
+            func(two_series, two_series, unequal_length="truncate")
+
+    @pytest.mark.parametrize(("func", "name"), ALL_LOCKSTEP)
+    def test_via_dispatcher(self, three_series, func, name):
+        direct = _to_dict(func(three_series, three_series))
+        dispatched = _to_dict(compute_pairwise_distance(three_series, three_series, method=name))
+        assert dispatched == direct
+
+
+class TestLockStepValues:
+    def test_euclidean(self, three_series):
+        d = _to_dict(compute_pairwise_euclidean(three_series, three_series))
+        assert d[("A", "B")] == pytest.approx(1.0)
+        assert d[("A", "C")] == pytest.approx(math.sqrt(20.0))
+
+    @pytest.mark.parametrize(("p", "expected"), [(1.0, 8.0), (2.0, math.sqrt(20.0)), (3.0, 56.0 ** (1 / 3))])
+    def test_minkowski(self, three_series, p, expected):
+        d = _to_dict(compute_pairwise_minkowski(three_series, three_series, p=p))
+        assert d[("A", "C")] == pytest.approx(expected)
+
+    def test_minkowski_default_is_euclidean(self, three_series):
+        minkowski = _to_dict(compute_pairwise_minkowski(three_series, three_series))
+        euclidean = _to_dict(compute_pairwise_euclidean(three_series, three_series))
+        assert minkowski == pytest.approx(euclidean)
+
+    @pytest.mark.parametrize("p", [0.5, float("inf")])
+    def test_minkowski_invalid_p(self, two_series, p):
+        with pytest.raises(ValueError, match="p must be"):
+            compute_pairwise_minkowski(two_series, two_series, p=p)
+
+    def test_chebyshev(self, three_series):
+        d = _to_dict(compute_pairwise_chebyshev(three_series, three_series))
+        assert d[("A", "B")] == pytest.approx(1.0)
+        assert d[("A", "C")] == pytest.approx(3.0)
+
+    def test_correlation(self, three_series):
+        d = _to_dict(compute_pairwise_correlation(three_series, three_series))
+        assert d[("A", "C")] == pytest.approx(2.0)
+        assert 0.0 < d[("A", "B")] < 0.1
+
+    def test_correlation_is_scale_invariant(self):
+        df = pl.DataFrame({"unique_id": ["A"] * 4 + ["B"] * 4, "y": [1.0, 3.0, 2.0, 5.0, 12.0, 16.0, 14.0, 20.0]})
+        d = _to_dict(compute_pairwise_correlation(df, df))
+        assert d[("A", "B")] == pytest.approx(0.0, abs=1e-12)
+
+    def test_correlation_constant_series(self):
+        df = pl.DataFrame({"unique_id": ["A"] * 3 + ["B"] * 3, "y": [1.0, 1.0, 1.0, 1.0, 2.0, 3.0]})
+        d = _to_dict(compute_pairwise_correlation(df, df))
+        assert d[("A", "B")] == pytest.approx(1.0)
+
+    def test_cid_penalizes_complexity_mismatch(self):
+        df = pl.DataFrame(
+            {
+                "unique_id": ["smooth"] * 4 + ["jagged"] * 4,
+                "y": [0.0, 1.0, 2.0, 3.0, 0.0, 3.0, 0.0, 3.0],
+            }
+        )
+        cid = _to_dict(compute_pairwise_cid(df, df))[("jagged", "smooth")]
+        ed = _to_dict(compute_pairwise_euclidean(df, df))[("jagged", "smooth")]
+        assert cid == pytest.approx(ed * math.sqrt(27.0) / math.sqrt(3.0))
+
+    def test_cosine(self):
+        df = pl.DataFrame({"unique_id": ["A"] * 2 + ["B"] * 2 + ["C"] * 2, "y": [1.0, 0.0, 0.0, 2.0, 3.0, 0.0]})
+        d = _to_dict(compute_pairwise_cosine(df, df))
+        assert d[("A", "B")] == pytest.approx(1.0)
+        assert d[("A", "C")] == pytest.approx(0.0, abs=1e-12)
@@ -1,8 +1,22 @@
 import polars as pl
 import pytest
 from polars_ts_rs.polars_ts_rs import (
+    compute_pairwise_ddtw,
+    compute_pairwise_ddtw_multi,
     compute_pairwise_dtw_multi,
+    compute_pairwise_edr,
+    compute_pairwise_edr_multi,
+    compute_pairwise_erp,
+    compute_pairwise_erp_multi,
+    compute_pairwise_frechet,
+    compute_pairwise_frechet_multi,
+    compute_pairwise_lcss,
+    compute_pairwise_lcss_multi,
     compute_pairwise_msm_multi,
+    compute_pairwise_twe,
+    compute_pairwise_twe_multi,
+    compute_pairwise_wdtw,
+    compute_pairwise_wdtw_multi,
 )
 
 from tests.distance.conftest import _to_dict
@@ -163,6 +177,80 @@ class TestMultivariateDTW:
         assert result["dtw_multi"][0] > 0
 
 
+class TestMultivariateDTWStrategy:
+    @pytest.fixture
+    def lagged_spikes(self):
+        """A spikes in its two dimensions two steps apart; B spikes in both at once."""
+        return pl.DataFrame(
+            {
+                "unique_id": ["A"] * 5 + ["B"] * 5,
+                "y1": [0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+                "y2": [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+            }
+        )
+
+    def _distance(self, df, **kwargs):
+        return compute_pairwise_dtw_multi(df, df, **kwargs)["dtw_multi"][0]
+
+    def test_independent_warps_each_dimension(self, lagged_spikes):
+        assert self._distance(lagged_spikes, strategy="independent") == pytest.approx(0.0)
+        assert self._distance(lagged_spikes, strategy="dependent") > 0.0
+
+    def test_default_is_dependent(self, two_multi_series):
+        assert self._distance(two_multi_series) == self._distance(two_multi_series, strategy="dependent")
+
+    def test_adaptive_picks_per_pair(self, lagged_spikes):
+        assert self._distance(lagged_spikes, strategy="adaptive") == pytest.approx(0.0)
+        dependent = self._distance(lagged_spikes, strategy="dependent")
+        assert self._distance(lagged_spikes, strategy="adaptive", adaptive_threshold=1e12) == dependent
+
+    def test_independent_never_exceeds_dependent(self, three_multi_series):
+        dep = _to_dict(compute_pairwise_dtw_multi(three_multi_series, three_multi_series, strategy="dependent"))
+        ind = _to_dict(compute_pairwise_dtw_multi(three_multi_series, three_multi_series, strategy="independent"))
+        for pair, d in ind.items():
+            assert d <= dep[pair] + 1e-10
+
+    @pytest.mark.parametrize("strategy", ["dependent", "independent"])
+    def test_weights(self, two_multi_series, strategy):
+        unweighted = self._distance(two_multi_series, strategy=strategy)
+        assert self._distance(two_multi_series, strategy=strategy, weights=[1.0, 1.0]) == pytest.approx(unweighted)
+        assert self._distance(two_multi_series, strategy=strategy, weights=[2.0, 2.0]) == pytest.approx(2 * unweighted)
+        assert self._distance(two_multi_series, strategy=strategy, weights=[0.0, 0.0]) == pytest.approx(0.0)
+
+    def test_weights_length_mismatch_raises(self, two_multi_series):
+        with pytest.raises(ValueError, match="2 dimensions"):
+            compute_pairwise_dtw_multi(two_multi_series, two_multi_series, weights=[1.0, 1.0, 1.0])
+
+    @pytest.mark.parametrize("strategy", ["dependent", "independent"])
+    def test_dimension_mismatch_raises(self, two_multi_series, strategy):
+        wider = two_multi_series.with_columns(y3=pl.col("y1"))
+        with pytest.raises(ValueError, match="same number of dimensions"):
+            compute_pairwise_dtw_multi(two_multi_series, wider, strategy=strategy)
+
+    def test_negative_weights_raise(self, two_multi_series):
+        with pytest.raises(ValueError, match="non-negative"):
+            compute_pairwise_dtw_multi(two_multi_series, two_multi_series, weights=[1.0, -1.0])
+
+    def test_window(self, lagged_spikes):
+        assert self._distance(lagged_spikes, strategy="independent", window=1) == pytest.approx(0.0)
+        assert self._distance(lagged_spikes, strategy="independent", window=0) > 0.0
+        assert self._distance(lagged_spikes, window=10) == self._distance(lagged_spikes)
+
+    def test_unknown_strategy_raises(self, two_multi_series):
+        with pytest.raises(ValueError, match="Unknown strategy"):
+            compute_pairwise_dtw_multi(two_multi_series, two_multi_series, strategy="greedy")
+
+    def test_threshold_requires_adaptive(self, two_multi_series):
+        with pytest.raises(ValueError, match="adaptive_threshold"):
+            compute_pairwise_dtw_multi(two_multi_series, two_multi_series, adaptive_threshold=2.0)
+
+    def test_via_dispatcher(self, lagged_spikes):
+        from polars_ts.distance import compute_pairwise_distance
+
+        result = compute_pairwise_distance(lagged_spikes, lagged_spikes, method="dtw_multi", strategy="independent")
+        assert result["dtw_multi"][0] == pytest.approx(0.0)
+
+
 # ===========================================================================
 # Multivariate MSM tests
 # ===========================================================================
@@ -247,3 +335,101 @@ class TestMultivariateMSM:
         result = compute_pairwise_msm_multi(df, df)
         assert result.shape[0] == 1
         assert result["msm_multi"][0] > 0
+
+
+# ---------------------------------------------------------------------------
+# ERP / LCSS / EDR / TWE / WDTW / DDTW / Frechet multivariate kernels
+# ---------------------------------------------------------------------------
+
+MULTI_KERNELS = [
+    (compute_pairwise_erp_multi, compute_pairwise_erp, "erp"),
+    (compute_pairwise_lcss_multi, compute_pairwise_lcss, "lcss"),
+    (compute_pairwise_edr_multi, compute_pairwise_edr, "edr"),
+    (compute_pairwise_twe_multi, compute_pairwise_twe, "twe"),
+    (compute_pairwise_wdtw_multi, compute_pairwise_wdtw, "wdtw"),
+    (compute_pairwise_ddtw_multi, compute_pairwise_ddtw, "ddtw"),
+    (compute_pairwise_frechet_multi, compute_pairwise_frechet, "frechet"),
+]
+
+
+class TestMultiKernels:
+    @pytest.mark.parametrize(("multi", "uni", "name"), MULTI_KERNELS)
+    def test_single_dimension_matches_univariate(self, multi, uni, name):
+        df = pl.DataFrame(
+            {
+                "unique_id": ["A"] * 6 + ["B"] * 6 + ["C"] * 5,
+                "y": [1.0, 2.0, 3.0, 2.5, 1.0, 0.0, 1.5, 2.0, 3.5, 2.0, 1.0, 0.5, 0.0, 1.0, 4.0, 1.0, 0.0],
+            }
+        )
+        multi_result = _to_dict(multi(df, df))
+        uni_result = _to_dict(uni(df, df))
+        assert multi_result == pytest.approx(uni_result)
+        assert multi(df, df).columns[-1] == f"{name}_multi"
+
+    @pytest.mark.parametrize(("multi", "uni", "name"), MULTI_KERNELS)
+    def test_identical_series_zero(self, multi, uni, name, identical_multi_series):
+        d = _to_dict(multi(identical_multi_series, identical_multi_series))
+        assert d[("A", "B")] == pytest.approx(0.0, abs=1e-10)
+
+    @pytest.mark.parametrize(("multi", "uni", "name"), [k for k in MULTI_KERNELS if k[2] not in ("lcss", "edr")])
+    def test_metric_changes_distance(self, multi, uni, name):
+        df = pl.DataFrame(
+            {
+                "unique_id": ["A"] * 4 + ["B"] * 4,
+                "y1": [0.0, 1.0, 2.0, 3.0, 3.0, 5.0, 6.0, 3.0],
+                "y2": [0.0, 1.0, 2.0, 3.0, 4.0, 1.0, 2.0, 7.0],
+            }
+        )
+        values = {
+            metric: multi(df, df, metric=metric)[f"{name}_multi"][0]
+            for metric in ("manhattan", "euclidean", "squared_euclidean")
+        }
+        assert len(set(values.values())) > 1
+
+    @pytest.mark.parametrize(("multi", "uni", "name"), MULTI_KERNELS)
+    def test_unknown_metric_raises(self, multi, uni, name, two_multi_series):
+        with pytest.raises(ValueError, match="Unknown metric"):
+            multi(two_multi_series, two_multi_series, metric="cosine")
+
+    @pytest.mark.parametrize("func", [compute_pairwise_lcss_multi, compute_pairwise_edr_multi])
+    def test_metric_changes_threshold_match(self, func):
+        # Every step differs by (0.6, 0.6): Manhattan 1.2 misses epsilon=1, Euclidean ~0.85 matches
+        df = pl.DataFrame(
+            {
+                "unique_id": ["A"] * 3 + ["B"] * 3,
+                "y1": [0.0, 1.0, 2.0, 0.6, 1.6, 2.6],
+                "y2": [0.0, 1.0, 2.0, 0.6, 1.6, 2.6],
+            }
+        )
+        manhattan = func(df, df, epsilon=1.0, metric="manhattan")[-1, -1]
+        euclidean = func(df, df, epsilon=1.0, metric="euclidean")[-1, -1]
+        assert euclidean == pytest.approx(0.0)
+        assert manhattan > euclidean
+
+    def test_dtw_multi_squared_euclidean(self, two_multi_series):
+        d = _to_dict(compute_pairwise_dtw_multi(two_multi_series, two_multi_series, metric="squared_euclidean"))
+        # Only the last step differs, by (1, 1)
+        assert d[("A", "B")] == pytest.approx(2.0)
+
+    def test_erp_gap_is_broadcast(self):
+        df = pl.DataFrame(
+            {
+                "unique_id": ["A"] * 2 + ["B"],
+                "y1": [1.0, 2.0, 1.0],
+                "y2": [1.0, 4.0, 1.0],
+            }
+        )
+        d = _to_dict(compute_pairwise_erp_multi(df, df, g=1.0))
+        # Deleting the point (2, 4) costs its Manhattan distance to the gap (1, 1)
+        assert d[("A", "B")] == pytest.approx(4.0)
+
+    def test_via_dispatcher(self, two_multi_series):
+        from polars_ts.distance import compute_pairwise_distance
+
+        result = compute_pairwise_distance(
+            two_multi_series, two_multi_series, method="lcss_multi", epsilon=0.5, metric="euclidean", window=1
+        )
+        expected = compute_pairwise_lcss_multi(
+            two_multi_series, two_multi_series, epsilon=0.5, metric="euclidean", window=1
+        )
+        assert result["lcss_multi"][0] == pytest.approx(expected["lcss_multi"][0])
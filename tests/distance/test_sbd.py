@@ -57,3 +57,34 @@ class TestSBDUnifiedAPI:
     def test_rejects_unknown_kwargs(self, two_series):
         with pytest.raises(ValueError, match="Unexpected"):
             compute_pairwise_distance(two_series, two_series, method="sbd", foo=1)
+
+
+class TestSBDShift:
+    def test_shift_column(self):
+        df = pl.DataFrame(
+            {
+                "unique_id": ["A"] * 8 + ["B"] * 8,
+                "y": [0.0, 0.0, 1.0, 3.0, 1.0, 0.0, 0.0, 0.0] + [1.0, 3.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            }
+        )
+        result = compute_pairwise_sbd(df, df, return_shift=True)
+        assert result.columns == ["id_1", "id_2", "sbd", "shift"]
+        assert result.schema["shift"] == pl.Int64
+        row = result.row(0, named=True)
+        assert (row["id_1"], row["id_2"]) == ("A", "B")
+        assert row["sbd"] == pytest.approx(0.0, abs=1e-10)
+        # B must move two steps right to line up with A
+        assert row["shift"] == 2
+
+    def test_distance_unchanged(self, three_series):
+        plain = _to_dict(compute_pairwise_sbd(three_series, three_series))
+        shifted = _to_dict(compute_pairwise_sbd(three_series, three_series, return_shift=True).drop("shift"))
+        assert plain == pytest.approx(shifted)
+
+    def test_via_dispatcher(self, two_series):
+        result = compute_pairwise_distance(two_series, two_series, method="sbd", return_shift=True)
+        assert "shift" in result.columns
+
+    def test_requires_long_output(self, two_series):
+        with pytest.raises(ValueError, match="output"):
+            compute_pairwise_sbd(two_series, two_series, return_shift=True, output="matrix")
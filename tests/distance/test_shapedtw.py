@@ -0,0 +1,66 @@
+import polars as pl
+import pytest
+
+from polars_ts import compute_pairwise_dtw, compute_pairwise_shapedtw
+from polars_ts.distance import compute_pairwise_distance
+
+from .conftest import _to_dict
+
+
+@pytest.fixture
+def wave_series():
+    """Two phase-shifted waves and a flat series, 20 observations each."""
+    wave = [0.0, 1.0, 2.0, 3.0, 2.0, 1.0, 0.0, -1.0, -2.0, -1.0] * 2
+    return pl.DataFrame(
+        {
+            "unique_id": ["A"] * 20 + ["B"] * 20 + ["C"] * 20,
+            "y": wave + wave[2:] + wave[:2] + [0.5] * 20,
+        }
+    )
+
+
+class TestShapeDTW:
+    def test_identical_series_zero(self, identical_series):
+        d = _to_dict(compute_pairwise_shapedtw(identical_series, identical_series, reach=2))
+        assert d[("A", "B")] == pytest.approx(0.0)
+
+    def test_output_columns(self, two_series):
+        assert compute_pairwise_shapedtw(two_series, two_series).columns == ["id_1", "id_2", "shapedtw"]
+
+    def test_zero_reach_is_dtw(self, three_series):
+        shape = _to_dict(compute_pairwise_shapedtw(three_series, three_series, reach=0))
+        dtw = _to_dict(compute_pairwise_dtw(three_series, three_series, cost="squared"))
+        assert shape == pytest.approx(dtw)
+
+    @pytest.mark.parametrize("descriptor", ["raw", "paa", "slope"])
+    def test_descriptors(self, wave_series, descriptor):
+        kwargs = {} if descriptor == "raw" else {"segments": 3}
+        d = _to_dict(compute_pairwise_shapedtw(wave_series, wave_series, reach=3, descriptor=descriptor, **kwargs))
+        assert all(v >= 0.0 for v in d.values())
+        assert d[("A", "B")] < d[("A", "C")]
+
+    def test_never_below_dtw(self, wave_series):
+        # shapeDTW follows a different path over the same raw costs
+        shape = _to_dict(compute_pairwise_shapedtw(wave_series, wave_series, reach=3))
+        dtw = _to_dict(compute_pairwise_dtw(wave_series, wave_series, cost="squared"))
+        for pair, d in shape.items():
+            assert d >= dtw[pair] - 1e-10
+
+    def test_unknown_descriptor(self, two_series):
+        with pytest.raises(ValueError, match="Unknown descriptor"):
+            compute_pairwise_shapedtw(two_series, two_series, descriptor="fft")
+
+    def test_segments_out_of_range(self, two_series):
+        with pytest.raises(ValueError, match="segments must be"):
+            compute_pairwise_shapedtw(two_series, two_series, reach=1, descriptor="paa", segments=4)
+
+    def test_segments_require_paa_or_slope(self, two_series):
+        with pytest.raises(ValueError, match="segments only applies"):
+            compute_pairwise_shapedtw(two_series, two_series, segments=2)
+
+    def test_via_dispatcher(self, wave_series):
+        direct = _to_dict(compute_pairwise_shapedtw(wave_series, wave_series, reach=2, descriptor="slope"))
+        dispatched = _to_dict(
+            compute_pairwise_distance(wave_series, wave_series, method="shapedtw", reach=2, descriptor="slope")
+        )
+        assert dispatched == direct
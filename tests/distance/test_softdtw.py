@@ -0,0 +1,76 @@
+import polars as pl
+import pytest
+from polars_ts_rs.polars_ts_rs import compute_pairwise_softdtw, softdtw_barycenter_grad, softdtw_grad, softdtw_value
+
+from tests.distance.conftest import _to_dict
+
+
+class TestSoftDTWBasic:
+    def test_output_columns(self, two_series):
+        result = compute_pairwise_softdtw(two_series, two_series)
+        assert set(result.columns) == {"id_1", "id_2", "softdtw"}
+
+    def test_three_series_pairs(self, three_series):
+        result = compute_pairwise_softdtw(three_series, three_series)
+        assert len(_to_dict(result)) == 3
+
+    def test_symmetry(self, three_series):
+        df_a = three_series.filter(pl.col("unique_id") == "A")
+        df_c = three_series.filter(pl.col("unique_id") == "C")
+        ac = compute_pairwise_softdtw(df_a, df_c, gamma=0.5)
+        ca = compute_pairwise_softdtw(df_c, df_a, gamma=0.5)
+        assert ac["softdtw"][0] == pytest.approx(ca["softdtw"][0])
+
+    def test_decreases_with_gamma(self, two_series):
+        sharp = compute_pairwise_softdtw(two_series, two_series, gamma=0.01)["softdtw"][0]
+        smooth = compute_pairwise_softdtw(two_series, two_series, gamma=1.0)["softdtw"][0]
+        assert smooth <= sharp
+
+    def test_invalid_gamma(self, two_series):
+        with pytest.raises(ValueError, match="gamma"):
+            compute_pairwise_softdtw(two_series, two_series, gamma=0.0)
+
+    def test_int_id_preserved(self, int_id_series):
+        result = compute_pairwise_softdtw(int_id_series, int_id_series)
+        assert result["id_1"].dtype == pl.Int64
+
+    def test_matrix_diagonal_is_self_value(self, three_series):
+        matrix = compute_pairwise_softdtw(three_series, three_series, output="matrix")
+        for row, uid in enumerate(["A", "B", "C"]):
+            y = three_series.filter(pl.col("unique_id") == uid)["y"].to_list()
+            assert matrix[uid][row] == pytest.approx(softdtw_value(y, y, 1.0))
+            assert matrix[uid][row] != 0.0
+
+
+class TestSoftDTWGradient:
+    def test_value_matches_grad(self):
+        x, y = [0.3, -1.0, 2.0, 0.7], [0.0, 1.0, 1.5]
+        assert softdtw_value(x, y, 0.5) == pytest.approx(softdtw_grad(x, y, 0.5)[0])
+
+    def test_finite_differences(self):
+        x, y, gamma, h = [0.3, -1.0, 2.0, 0.7], [0.0, 1.0, 1.5], 0.5, 1e-6
+        _, grad = softdtw_grad(x, y, gamma)
+        for i in range(len(x)):
+            up = x.copy()
+            down = x.copy()
+            up[i] += h
+            down[i] -= h
+            numeric = (softdtw_grad(up, y, gamma)[0] - softdtw_grad(down, y, gamma)[0]) / (2 * h)
+            assert grad[i] == pytest.approx(numeric, abs=1e-5)
+
+    def test_barycenter_grad_is_weighted_sum(self):
+        z, ys = [0.0, 1.0, 2.0], [[0.5, 1.0], [2.0, 1.0, 0.0, 1.0]]
+        value, grad = softdtw_barycenter_grad(z, ys, 1.0, [0.25, 0.75])
+        parts = [softdtw_grad(z, y, 1.0) for y in ys]
+        assert value == pytest.approx(0.25 * parts[0][0] + 0.75 * parts[1][0])
+        expected = [0.25 * a + 0.75 * b for a, b in zip(parts[0][1], parts[1][1], strict=True)]
+        assert grad == pytest.approx(expected)
+
+    @pytest.mark.parametrize("n_jobs", [1, 2])
+    def test_barycenter_grad_n_jobs(self, n_jobs):
+        z, ys = [0.0, 1.0, 2.0], [[0.5, 1.0], [2.0, 1.0, 0.0, 1.0], [1.0, 1.5, 2.0]]
+        assert softdtw_barycenter_grad(z, ys, 1.0, n_jobs=n_jobs) == softdtw_barycenter_grad(z, ys, 1.0)
+
+    def test_barycenter_weight_length_mismatch(self):
+        with pytest.raises(ValueError, match="weights"):
+            softdtw_barycenter_grad([0.0], [[1.0], [2.0]], 1.0, [1.0])
@@ -1,5 +1,7 @@
 """Tests for the unified compute_pairwise_distance API."""
 
+import math
+
 import polars as pl
 import pytest
 
@@ -10,6 +12,7 @@ from polars_ts import (
     compute_pairwise_edr,
     compute_pairwise_erp,
     compute_pairwise_frechet,
+    compute_pairwise_gak,
     compute_pairwise_lcss,
     compute_pairwise_msm,
     compute_pairwise_sbd,
@@ -71,6 +74,11 @@ class TestUnifiedDispatch:
         direct = compute_pairwise_edr(two_series, two_series, epsilon=0.5)
         assert unified["edr"].to_list() == pytest.approx(direct["edr"].to_list())
 
+    def test_gak_matches_direct(self, two_series):
+        unified = compute_pairwise_distance(two_series, two_series, method="gak", sigma=2.0)
+        direct = compute_pairwise_gak(two_series, two_series, sigma=2.0)
+        assert unified["gak"].to_list() == pytest.approx(direct["gak"].to_list())
+
 
 class TestUnifiedKwargs:
     """Verify that keyword arguments are passed through and affect the result."""
@@ -200,3 +208,407 @@ class TestUnifiedMultivariate:
     def test_dtw_multi_with_metric(self, multi_series):
         result = compute_pairwise_distance(multi_series, multi_series, method="dtw_multi", metric="euclidean")
         assert len(result) > 0
+
+
+class TestNearestNeighbourMode:
+    """Verify the shared ``k`` option returns only the nearest neighbours."""
+
+    @pytest.mark.parametrize("method", ["dtw", "msm", "erp", "lcss", "twe", "sbd", "frechet", "edr"])
+    def test_top_k_matches_full(self, three_series, method):
+        full = compute_pairwise_distance(three_series, three_series, method=method)
+        knn = compute_pairwise_distance(three_series, three_series, method=method, k=1)
+        assert knn.columns == ["id_1", "id_2", "rank", method]
+        assert knn["id_1"].sort().to_list() == ["A", "B", "C"]
+        assert knn["rank"].to_list() == [1, 1, 1]
+        for row in knn.iter_rows(named=True):
+            others = full.filter((pl.col("id_1") == row["id_1"]) | (pl.col("id_2") == row["id_1"]))
+            assert row[method] == pytest.approx(others[method].min())
+
+    def test_k_larger_than_pool(self, three_series):
+        knn = compute_pairwise_dtw(three_series, three_series, k=10)
+        assert len(knn) == 6
+        assert knn.group_by("id_1").agg(pl.col("rank").max())["rank"].to_list() == [2, 2, 2]
+
+    def test_ranks_ordered_by_distance(self, three_series):
+        knn = compute_pairwise_dtw(three_series, three_series, k=2).sort("id_1", "rank")
+        for _, group in knn.group_by("id_1"):
+            assert group["dtw"].to_list() == sorted(group["dtw"].to_list())
+
+    def test_multivariate(self):
+        df = pl.DataFrame(
+            {
+                "unique_id": ["A"] * 3 + ["B"] * 3 + ["C"] * 3,
+                "y": [1.0, 2.0, 3.0, 1.0, 2.0, 4.0, 9.0, 9.0, 9.0],
+                "z": [0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 5.0, 5.0, 5.0],
+            }
+        )
+        knn = compute_pairwise_distance(df, df, method="dtw_multi", k=1)
+        nn = dict(zip(knn["id_1"], knn["id_2"]))
+        assert nn["A"] == "B"
+        assert nn["C"] == "B"
+
+    def test_invalid_k(self, two_series):
+        with pytest.raises(ValueError, match="k must be >= 1"):
+            compute_pairwise_dtw(two_series, two_series, k=0)
+
+    def test_unknown_option(self, two_series):
+        with pytest.raises(TypeError, match="Unexpected keyword argument"):
+            compute_pairwise_dtw(two_series, two_series, bogus=1)
+
+
+class TestMatrixOutput:
+    """Verify the dense ``matrix`` and ``condensed`` output formats."""
+
+    def test_matrix_shape_and_ids(self, three_series):
+        matrix = compute_pairwise_dtw(three_series, three_series, output="matrix")
+        assert matrix.columns == ["unique_id", "A", "B", "C"]
+        assert matrix["unique_id"].to_list() == ["A", "B", "C"]
+
+    def test_matrix_symmetric_zero_diagonal(self, three_series):
+        values = compute_pairwise_dtw(three_series, three_series, output="matrix").drop("unique_id").to_numpy()
+        assert (values == values.T).all()
+        assert (values.diagonal() == 0.0).all()
+
+    def test_matrix_matches_long(self, three_series):
+        long = _to_pairs(compute_pairwise_msm(three_series, three_series))
+        matrix = compute_pairwise_msm(three_series, three_series, output="matrix")
+        for row in matrix.iter_rows(named=True):
+            for other in ("A", "B", "C"):
+                if other != row["unique_id"]:
+                    assert row[other] == pytest.approx(long[tuple(sorted((row["unique_id"], other)))])
+
+    def test_condensed_order(self, three_series):
+        condensed = compute_pairwise_dtw(three_series, three_series, output="condensed")
+        assert list(zip(condensed["id_1"], condensed["id_2"])) == [("A", "B"), ("A", "C"), ("B", "C")]
+
+    def test_condensed_requires_same_ids(self, two_series, three_series):
+        with pytest.raises(ValueError, match="same ids"):
+            compute_pairwise_dtw(two_series, three_series, output="condensed")
+
+    def test_rectangular_matrix(self, two_series, three_series):
+        matrix = compute_pairwise_dtw(two_series, three_series, output="matrix")
+        assert matrix.shape == (2, 4)
+
+    def test_matrix_evaluates_shared_id_pairs(self, three_series):
+        queries = three_series.with_columns(pl.col("y").reverse().over("unique_id"))
+        matrix = compute_pairwise_dtw(queries, three_series, output="matrix")
+        full = _to_pairs(compute_pairwise_dtw(queries, three_series, mode="cross_full"))
+        assert matrix["A"][0] == pytest.approx(full[("A", "A")])
+        assert matrix["A"][0] > 0.0
+
+    def test_int_ids(self, int_id_series):
+        matrix = compute_pairwise_dtw(int_id_series, int_id_series, output="matrix")
+        assert matrix["unique_id"].dtype == pl.Int64
+        assert matrix.columns == ["unique_id", "1", "2"]
+
+    def test_k_with_matrix_rejected(self, two_series):
+        with pytest.raises(ValueError, match="output='long'"):
+            compute_pairwise_dtw(two_series, two_series, k=1, output="matrix")
+
+    def test_unknown_output(self, two_series):
+        with pytest.raises(ValueError, match="Unknown output format"):
+            compute_pairwise_dtw(two_series, two_series, output="wide")
+
+
+def _to_pairs(df: pl.DataFrame) -> dict[tuple[str, str], float]:
+    dist_col = df.columns[-1]
+    return {tuple(sorted((r["id_1"], r["id_2"]))): r[dist_col] for r in df.iter_rows(named=True)}
+
+
+class TestColumnNames:
+    """Verify configurable id/target/time column names."""
+
+    @pytest.fixture
+    def renamed(self, three_series):
+        return three_series.rename({"unique_id": "series_id", "y": "value"}).with_columns(
+            pl.int_range(pl.len()).over("series_id").alias("timestamp")
+        )
+
+    @pytest.mark.parametrize("method", ["dtw", "msm", "erp", "sbd"])
+    def test_renamed_columns_match_defaults(self, three_series, renamed, method):
+        expected = compute_pairwise_distance(three_series, three_series, method=method).sort("id_1", "id_2")
+        result = compute_pairwise_distance(
+            renamed, renamed, method=method, id_col="series_id", target_col="value", time_col="timestamp"
+        ).sort("id_1", "id_2")
+        assert result.equals(expected)
+
+    def test_missing_custom_column(self, three_series):
+        with pytest.raises(KeyError, match="series_id"):
+            compute_pairwise_dtw(three_series, three_series, id_col="series_id")
+
+    def test_matrix_id_column_named_after_id_col(self, renamed):
+        matrix = compute_pairwise_dtw(renamed, renamed, output="matrix", id_col="series_id", target_col="value")
+        assert matrix.columns[0] == "series_id"
+
+    def test_multivariate_excludes_time_col(self):
+        df = pl.DataFrame(
+            {
+                "sid": ["A"] * 3 + ["B"] * 3,
+                "ts": [0, 1, 2, 100, 101, 102],
+                "x": [1.0, 2.0, 3.0, 1.0, 2.0, 3.0],
+            }
+        )
+        result = compute_pairwise_distance(df, df, method="dtw_multi", id_col="sid", time_col="ts")
+        assert result["dtw_multi"].to_list() == [0.0]
+
+
+class TestTimeOrdering:
+    """Verify observations are sorted by the time column before grouping."""
+
+    @pytest.fixture
+    def ordered(self):
+        return pl.DataFrame(
+            {
+                "unique_id": ["A"] * 4 + ["B"] * 4,
+                "ds": [0, 1, 2, 3] * 2,
+                "y": [1.0, 2.0, 3.0, 4.0, 4.0, 1.0, 2.0, 3.0],
+            }
+        )
+
+    @pytest.mark.parametrize("method", ["dtw", "msm", "erp", "twe"])
+    def test_shuffled_rows_same_distance(self, ordered, method):
+        expected = compute_pairwise_distance(ordered, ordered, method=method)
+        shuffled = ordered.sample(fraction=1.0, shuffle=True, seed=3)
+        result = compute_pairwise_distance(shuffled, shuffled, method=method)
+        assert result[method].to_list() == pytest.approx(expected[method].to_list())
+
+    def test_time_col_none_keeps_row_order(self, ordered):
+        # Both series become [4, 3, 2, 1] in row order
+        permuted = ordered.sort("unique_id", "y", descending=[False, True])
+        sorted_result = compute_pairwise_dtw(permuted, permuted)
+        raw_result = compute_pairwise_dtw(permuted, permuted, time_col=None)
+        assert sorted_result["dtw"][0] == compute_pairwise_dtw(ordered, ordered)["dtw"][0]
+        assert raw_result["dtw"][0] == 0.0
+
+    def test_duplicate_timestamps_rejected(self, ordered):
+        dup = ordered.with_columns(pl.when(pl.col("ds") == 1).then(0).otherwise(pl.col("ds")).alias("ds"))
+        with pytest.raises(ValueError, match="Duplicate timestamp 0 in column 'ds'"):
+            compute_pairwise_dtw(dup, dup)
+
+    def test_multivariate_ds_not_a_dimension(self, ordered):
+        df = ordered.with_columns((pl.col("y") * 2).alias("z"))
+        with_ds = compute_pairwise_distance(df, df, method="dtw_multi")
+        without_ds = compute_pairwise_distance(df.drop("ds"), df.drop("ds"), method="dtw_multi")
+        assert with_ds["dtw_multi"].to_list() == pytest.approx(without_ds["dtw_multi"].to_list())
+
+    def test_missing_explicit_time_col(self, ordered):
+        with pytest.raises(KeyError, match="timestamp"):
+            compute_pairwise_dtw(ordered, ordered, time_col="timestamp")
+
+
+class TestNormalization:
+    @pytest.fixture
+    def scaled(self):
+        # B is an affine transform of A
+        return pl.DataFrame(
+            {
+                "unique_id": ["A"] * 4 + ["B"] * 4,
+                "y": [1.0, 2.0, 4.0, 3.0, 10.0, 20.0, 40.0, 30.0],
+            }
+        )
+
+    @pytest.mark.parametrize("normalize", ["zscore", "minmax", "mean", "robust"])
+    def test_affine_copies_have_zero_distance(self, scaled, normalize):
+        result = compute_pairwise_dtw(scaled, scaled, normalize=normalize)
+        assert result["dtw"][0] == pytest.approx(0.0, abs=1e-12)
+
+    def test_none_is_default(self, scaled):
+        default = compute_pairwise_dtw(scaled, scaled)
+        explicit = compute_pairwise_dtw(scaled, scaled, normalize="none")
+        assert default["dtw"].to_list() == explicit["dtw"].to_list()
+        assert default["dtw"][0] > 0.0
+
+    def test_multivariate_per_dimension(self, scaled):
+        df = scaled.with_columns((pl.col("y") * -3.0 + 7.0).alias("z"))
+        result = compute_pairwise_distance(df, df, method="dtw_multi", normalize="zscore")
+        assert result["dtw_multi"][0] == pytest.approx(0.0, abs=1e-12)
+
+    def test_dispatch_forwards_normalize(self, scaled):
+        result = compute_pairwise_distance(scaled, scaled, method="msm", normalize="minmax")
+        assert result["msm"][0] == pytest.approx(0.0, abs=1e-12)
+
+    def test_unknown_normalization(self, scaled):
+        with pytest.raises(ValueError, match="Unknown normalization"):
+            compute_pairwise_dtw(scaled, scaled, normalize="l2")
+
+
+class TestDeterministicOrder:
+    @pytest.fixture
+    def numeric_ids(self):
+        return pl.DataFrame(
+            {
+                "unique_id": [10] * 3 + [2] * 3 + [1] * 3 + [33] * 3,
+                "y": [1.0, 2.0, 3.0, 3.0, 2.0, 1.0, 0.0, 5.0, 0.0, 2.0, 2.0, 2.0],
+            }
+        )
+
+    @pytest.mark.parametrize("method", ["dtw", "msm", "erp", "sbd"])
+    def test_long_rows_in_id_order(self, numeric_ids, method):
+        result = compute_pairwise_distance(numeric_ids, numeric_ids, method=method)
+        pairs = list(zip(result["id_1"], result["id_2"]))
+        assert pairs == [(1, 2), (1, 10), (1, 33), (2, 10), (2, 33), (10, 33)]
+
+    def test_repeated_runs_identical(self, numeric_ids):
+        first = compute_pairwise_dtw(numeric_ids, numeric_ids)
+        for seed in range(5):
+            shuffled = numeric_ids.sample(fraction=1.0, shuffle=True, seed=seed).sort("unique_id", maintain_order=True)
+            assert compute_pairwise_dtw(shuffled, shuffled).equals(first)
+
+    def test_matrix_numeric_order(self, numeric_ids):
+        matrix = compute_pairwise_dtw(numeric_ids, numeric_ids, output="matrix")
+        assert matrix["unique_id"].to_list() == [1, 2, 10, 33]
+        assert matrix.columns == ["unique_id", "1", "2", "10", "33"]
+
+    def test_knn_order(self, numeric_ids):
+        result = compute_pairwise_dtw(numeric_ids, numeric_ids, k=2)
+        assert result["id_1"].to_list() == [1, 1, 2, 2, 10, 10, 33, 33]
+        assert result["rank"].to_list() == [1, 2] * 4
+
+
+class TestPairMode:
+    @pytest.fixture
+    def queries(self):
+        # Shares id "A" with three_series but holds a different series
+        return pl.DataFrame({"unique_id": ["A"] * 4 + ["Q"] * 4, "y": [4.0, 3.0, 2.0, 1.0, 1.0, 2.0, 3.0, 4.0]})
+
+    def test_cross_full_is_rectangular(self, queries, three_series):
+        result = compute_pairwise_dtw(queries, three_series, mode="cross_full")
+        assert result.shape[0] == 2 * 3
+        pairs = list(zip(result["id_1"], result["id_2"]))
+        assert pairs[:3] == [("A", "A"), ("A", "B"), ("A", "C")]
+
+    def test_cross_full_diagonal(self, three_series):
+        result = compute_pairwise_dtw(three_series, three_series, mode="cross_full")
+        assert result.shape[0] == 9
+        diagonal = result.filter(pl.col("id_1") == pl.col("id_2"))
+        assert diagonal["dtw"].to_list() == [0.0, 0.0, 0.0]
+
+    def test_cross_full_shared_id_distance(self, queries, three_series):
+        result = compute_pairwise_dtw(queries, three_series, mode="cross_full")
+        same_id = result.filter((pl.col("id_1") == "A") & (pl.col("id_2") == "A"))
+        assert same_id["dtw"][0] > 0.0
+
+    def test_cross_dedup_is_default(self, queries, three_series):
+        default = compute_pairwise_dtw(queries, three_series)
+        explicit = compute_pairwise_dtw(queries, three_series, mode="cross_dedup")
+        assert default.equals(explicit)
+        assert ("A", "A") not in list(zip(default["id_1"], default["id_2"]))
+
+    def test_self_symmetric(self, three_series):
+        result = compute_pairwise_dtw(three_series, three_series, mode="self_symmetric")
+        assert list(zip(result["id_1"], result["id_2"])) == [("A", "B"), ("A", "C"), ("B", "C")]
+
+    def test_self_symmetric_requires_same_ids(self, two_series, three_series):
+        with pytest.raises(ValueError, match="same ids"):
+            compute_pairwise_dtw(two_series, three_series, mode="self_symmetric")
+
+    def test_cross_full_matrix_diagonal_computed(self, queries, three_series):
+        matrix = compute_pairwise_dtw(queries, three_series, output="matrix", mode="cross_full")
+        assert matrix.filter(pl.col("unique_id") == "A")["A"][0] > 0.0
+
+    def test_cross_full_knn_keeps_shared_id(self, three_series):
+        result = compute_pairwise_dtw(three_series, three_series, k=1, mode="cross_full")
+        assert result["id_1"].to_list() == result["id_2"].to_list()
+
+    def test_unknown_mode(self, two_series):
+        with pytest.raises(ValueError, match="Unknown mode"):
+            compute_pairwise_dtw(two_series, two_series, mode="full")
+
+
+class TestMissingPolicy:
+    @pytest.fixture
+    def gappy(self):
+        return pl.DataFrame(
+            {
+                "unique_id": ["A"] * 5 + ["B"] * 5,
+                "y": [1.0, None, 3.0, float("nan"), 5.0, 1.0, 2.0, 3.0, 4.0, 5.0],
+            }
+        )
+
+    def test_error_is_default_and_names_series(self, gappy):
+        with pytest.raises(ValueError, match="Series A has a missing value"):
+            compute_pairwise_dtw(gappy, gappy)
+
+    def test_linear_interpolate(self, gappy):
+        result = compute_pairwise_dtw(gappy, gappy, missing="linear_interpolate")
+        assert result["dtw"][0] == pytest.approx(0.0)
+
+    def test_ffill(self, gappy):
+        result = compute_pairwise_dtw(gappy, gappy, missing="ffill")
+        # A becomes [1, 1, 3, 3, 5]
+        expected = compute_pairwise_dtw(
+            pl.DataFrame({"unique_id": ["A"] * 5 + ["B"] * 5, "y": [1.0, 1.0, 3.0, 3.0, 5.0, 1.0, 2.0, 3.0, 4.0, 5.0]}),
+            gappy.filter(pl.col("unique_id") == "B"),
+        )
+        assert result["dtw"][0] == pytest.approx(expected["dtw"][0])
+
+    def test_drop(self, gappy):
+        result = compute_pairwise_distance(gappy, gappy, method="erp", missing="drop")
+        assert math.isfinite(result["erp"][0])
+
+    def test_multivariate_drop(self, gappy):
+        df = gappy.with_columns(pl.col("y").fill_nan(None).fill_null(0.0).alias("z"))
+        result = compute_pairwise_distance(df, df, method="dtw_multi", missing="drop")
+        assert math.isfinite(result["dtw_multi"][0])
+
+    def test_unknown_policy(self, gappy):
+        with pytest.raises(ValueError, match="Unknown missing policy"):
+            compute_pairwise_dtw(gappy, gappy, missing="zero")
+
+
+class TestNJobs:
+    @pytest.mark.parametrize("n_jobs", [1, 2])
+    def test_matches_default_pool(self, three_series, n_jobs):
+        expected = compute_pairwise_dtw(three_series, three_series)
+        result = compute_pairwise_dtw(three_series, three_series, n_jobs=n_jobs)
+        assert result.equals(expected)
+
+    @pytest.mark.parametrize("options", [{"k": 1}, {"output": "matrix"}])
+    def test_sequential_with_other_outputs(self, three_series, options):
+        expected = compute_pairwise_dtw(three_series, three_series, **options)
+        result = compute_pairwise_dtw(three_series, three_series, n_jobs=1, **options)
+        assert result.equals(expected)
+
+    def test_via_dispatcher(self, three_series):
+        expected = compute_pairwise_distance(three_series, three_series, method="msm")
+        result = compute_pairwise_distance(three_series, three_series, method="msm", n_jobs=1)
+        assert result.equals(expected)
+
+    def test_zero_rejected(self, two_series):
+        with pytest.raises(ValueError, match="n_jobs must be >= 1"):
+            compute_pairwise_dtw(two_series, two_series, n_jobs=0)
+
+
+class TestSinkParquet:
+    @pytest.mark.parametrize("mode", ["cross_dedup", "cross_full", "self_symmetric"])
+    def test_matches_in_memory_output(self, three_series, tmp_path, mode):
+        path = tmp_path / "dtw.parquet"
+        expected = compute_pairwise_dtw(three_series, three_series, mode=mode)
+        summary = compute_pairwise_dtw(three_series, three_series, mode=mode, sink_parquet=str(path))
+        assert summary["pairs"][0] == len(expected)
+        assert pl.read_parquet(path).equals(expected)
+
+    def test_small_budget_writes_several_blocks(self, three_series, tmp_path):
+        path = tmp_path / "msm.parquet"
+        expected = compute_pairwise_distance(three_series, three_series, method="msm", mode="cross_full")
+        summary = compute_pairwise_distance(
+            three_series, three_series, method="msm", mode="cross_full", sink_parquet=str(path), memory_budget=100
+        )
+        assert summary.columns == ["path", "pairs", "blocks"]
+        assert summary["blocks"][0] > 1
+        assert pl.read_parquet(path).equals(expected)
+
+    def test_keeps_integer_ids(self, tmp_path):
+        df = pl.DataFrame({"unique_id": [1] * 3 + [2] * 3, "y": [0.0, 1.0, 2.0, 0.0, 2.0, 4.0]})
+        path = tmp_path / "ids.parquet"
+        compute_pairwise_dtw(df, df, sink_parquet=str(path))
+        assert pl.read_parquet(path).equals(compute_pairwise_dtw(df, df))
+
+    @pytest.mark.parametrize("options", [{"k": 1}, {"output": "matrix"}])
+    def test_rejects_other_outputs(self, two_series, tmp_path, options):
+        with pytest.raises(ValueError, match="sink_parquet only supports"):
+            compute_pairwise_dtw(two_series, two_series, sink_parquet=str(tmp_path / "x.parquet"), **options)
+
+    def test_memory_budget_requires_sink(self, two_series):
+        with pytest.raises(ValueError, match="memory_budget only applies"):
+            compute_pairwise_dtw(two_series, two_series, memory_budget=1 << 20)
@@ -0,0 +1,43 @@
+import math
+
+import polars as pl
+import pytest
+
+from polars_ts import compute_pairwise_ddtw, compute_pairwise_wddtw
+from polars_ts.distance import compute_pairwise_distance
+
+from .conftest import _to_dict
+
+
+class TestWDDTW:
+    def test_identical_series_zero(self, identical_series):
+        d = _to_dict(compute_pairwise_wddtw(identical_series, identical_series))
+        assert d[("A", "B")] == pytest.approx(0.0)
+
+    def test_output_columns(self, two_series):
+        assert compute_pairwise_wddtw(two_series, two_series).columns == ["id_1", "id_2", "wddtw"]
+
+    def test_weighted_derivative_distance(self, two_series):
+        # Derivatives are [1, 1] and [1, 1.25]; the diagonal step has the
+        # logistic weight 1 / (1 + exp(g * len / 2)) with len = 2
+        d = _to_dict(compute_pairwise_wddtw(two_series, two_series, g=0.05))
+        assert d[("A", "B")] == pytest.approx(0.0625 / (1.0 + math.exp(0.05)))
+
+    def test_offset_invariant(self, two_series):
+        shifted = two_series.with_columns(y=two_series["y"] + 100.0)
+        base = _to_dict(compute_pairwise_wddtw(two_series, two_series))
+        assert _to_dict(compute_pairwise_wddtw(shifted, shifted)) == pytest.approx(base)
+
+    def test_abs_cost_orders_like_ddtw(self, three_series):
+        wddtw = _to_dict(compute_pairwise_wddtw(three_series, three_series, cost="abs"))
+        ddtw = _to_dict(compute_pairwise_ddtw(three_series, three_series))
+        assert (wddtw[("A", "B")] < wddtw[("A", "C")]) == (ddtw[("A", "B")] < ddtw[("A", "C")])
+
+    def test_too_short_series_infinite(self):
+        df = pl.DataFrame({"unique_id": ["A"] * 2 + ["B"] * 2, "y": [1.0, 2.0, 1.0, 3.0]})
+        assert compute_pairwise_wddtw(df, df)["wddtw"][0] == math.inf
+
+    def test_via_dispatcher(self, three_series):
+        direct = _to_dict(compute_pairwise_wddtw(three_series, three_series, g=0.2))
+        dispatched = _to_dict(compute_pairwise_distance(three_series, three_series, method="wddtw", g=0.2))
+        assert dispatched == direct
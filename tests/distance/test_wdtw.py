@@ -1,4 +1,5 @@
 import polars as pl
+import pytest
 from polars_ts_rs.polars_ts_rs import compute_pairwise_wdtw
 
 from tests.distance.conftest import _to_dict
@@ -89,3 +90,29 @@ class TestWDTWProperties:
         result = compute_pairwise_wdtw(df1, df2)
         assert result.shape[0] == 1
         assert result["wdtw"][0] > 0
+
+
+class TestWDTWCost:
+    @pytest.fixture
+    def step_series(self):
+        return pl.DataFrame(
+            {
+                "unique_id": ["A"] * 4 + ["B"] * 4,
+                "y": [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0],
+            }
+        )
+
+    def test_default_is_squared(self, three_series):
+        default = _to_dict(compute_pairwise_wdtw(three_series, three_series))
+        explicit = _to_dict(compute_pairwise_wdtw(three_series, three_series, cost="squared"))
+        assert default == explicit
+
+    def test_abs_cost(self, step_series):
+        squared = compute_pairwise_wdtw(step_series, step_series)["wdtw"][0]
+        absolute = compute_pairwise_wdtw(step_series, step_series, cost="abs")["wdtw"][0]
+        assert squared == pytest.approx(2 * absolute)
+
+    def test_normalize_path(self, step_series):
+        raw = compute_pairwise_wdtw(step_series, step_series)["wdtw"][0]
+        normalized = compute_pairwise_wdtw(step_series, step_series, normalize_path=True)["wdtw"][0]
+        assert normalized == pytest.approx(raw / 4)
@@ -0,0 +1,108 @@
+import math
+
+import polars as pl
+import pytest
+
+from polars_ts import compute_discords, compute_matrix_profile, compute_motifs
+
+
+@pytest.fixture
+def periodic_with_spike():
+    """Two periodic series; series A has a spike at position 120."""
+    a = [math.sin(i * 2 * math.pi / 20) for i in range(200)]
+    a[120] += 4.0
+    b = [math.cos(i * 2 * math.pi / 25) for i in range(150)]
+    return pl.DataFrame({"unique_id": ["A"] * 200 + ["B"] * 150, "y": a + b})
+
+
+class TestMatrixProfile:
+    def test_schema_and_length(self, periodic_with_spike):
+        result = compute_matrix_profile(periodic_with_spike, window=10)
+        assert result.columns == ["unique_id", "idx", "profile", "profile_index"]
+        assert result.schema["idx"] == pl.Int64
+        counts = dict(result.group_by("unique_id").len().iter_rows())
+        assert counts == {"A": 191, "B": 141}
+
+    def test_exclusion_zone(self, periodic_with_spike):
+        result = compute_matrix_profile(periodic_with_spike, window=10, exclusion_zone=5)
+        gaps = result.select((pl.col("idx") - pl.col("profile_index")).abs())
+        assert gaps.to_series().min() > 5
+
+    def test_ab_join(self, periodic_with_spike):
+        other = periodic_with_spike.with_columns(pl.col("y") * 2 + 1)
+        result = compute_matrix_profile(periodic_with_spike, window=10, other=other)
+        # Scaled copies match themselves exactly under z-normalization
+        assert result["profile"].max() == pytest.approx(0.0, abs=1e-4)
+
+    def test_ab_join_missing_id(self, periodic_with_spike):
+        other = periodic_with_spike.filter(pl.col("unique_id") == "A")
+        with pytest.raises(ValueError, match="missing from other"):
+            compute_matrix_profile(periodic_with_spike, window=10, other=other)
+
+    def test_window_longer_than_series(self):
+        df = pl.DataFrame({"unique_id": ["A"] * 5, "y": [1.0, 2.0, 3.0, 4.0, 5.0]})
+        with pytest.raises(ValueError, match="fewer than window"):
+            compute_matrix_profile(df, window=10)
+
+    def test_int_ids(self):
+        df = pl.DataFrame({"unique_id": [1] * 30, "y": [float(i % 7) for i in range(30)]})
+        result = compute_matrix_profile(df, window=7)
+        assert result.schema["unique_id"] == pl.Int64
+
+    def test_custom_columns(self, periodic_with_spike):
+        df = periodic_with_spike.rename({"unique_id": "series", "y": "value"})
+        result = compute_matrix_profile(df, window=10, id_col="series", target_col="value")
+        assert result.columns[0] == "series"
+
+    @pytest.mark.parametrize("n_jobs", [1, 2])
+    def test_n_jobs_matches_default(self, periodic_with_spike, n_jobs):
+        default = compute_matrix_profile(periodic_with_spike, window=10)
+        assert compute_matrix_profile(periodic_with_spike, window=10, n_jobs=n_jobs).equals(default)
+
+    def test_invalid_n_jobs(self, periodic_with_spike):
+        with pytest.raises(ValueError, match="n_jobs"):
+            compute_matrix_profile(periodic_with_spike, window=10, n_jobs=0)
+
+    @pytest.mark.parametrize("func", [compute_matrix_profile, compute_motifs, compute_discords])
+    @pytest.mark.parametrize(
+        "option, match",
+        [
+            ({"mode": "cross_full"}, "mode"),
+            ({"mode": "self_symmetric"}, "mode"),
+            ({"output": "condensed"}, "output"),
+        ],
+    )
+    def test_rejects_pair_table_options(self, periodic_with_spike, func, option, match):
+        with pytest.raises(ValueError, match=match):
+            func(periodic_with_spike, window=10, **option)
+
+    def test_rejects_k(self, periodic_with_spike):
+        with pytest.raises(ValueError, match="'k'"):
+            compute_matrix_profile(periodic_with_spike, window=10, k=2)
+
+
+class TestMotifsAndDiscords:
+    def test_discord_finds_spike(self, periodic_with_spike):
+        result = compute_discords(periodic_with_spike, window=10, k=1)
+        row = result.filter(pl.col("unique_id") == "A").row(0, named=True)
+        assert 111 <= row["idx"] <= 120
+        assert row["rank"] == 1
+
+    def test_discords_ranked(self, periodic_with_spike):
+        result = compute_discords(periodic_with_spike, window=10, k=3).filter(pl.col("unique_id") == "A")
+        assert result["rank"].to_list() == [1, 2, 3]
+        assert result["distance"].is_sorted(descending=True)
+
+    def test_motifs(self, periodic_with_spike):
+        result = compute_motifs(periodic_with_spike, window=10, k=2)
+        assert result.columns == ["unique_id", "rank", "idx", "neighbor_idx", "distance"]
+        a = result.filter(pl.col("unique_id") == "A")
+        assert a.height == 2
+        assert a["distance"].is_sorted()
+        assert a["distance"][0] == pytest.approx(0.0, abs=1e-4)
+        # Repeats of a period-20 series start a multiple of 20 apart
+        assert (a["idx"][0] - a["neighbor_idx"][0]) % 20 == 0
+
+    def test_rejects_output_option(self, periodic_with_spike):
+        with pytest.raises(ValueError, match="output"):
+            compute_motifs(periodic_with_spike, window=10, output="matrix")